@@ -25,6 +25,8 @@ export interface AdminUser {
   id: string;
   name: string;
   email: string;
+  lastActiveAt?: string | null;
+  role?: "APPROVER" | "EDITOR" | "OWNER" | null;
 }
 
 export const useAdminStore = () => {
@@ -56,10 +58,14 @@ export const useAdminStore = () => {
             url: `${API_PREFIX}/workspaces/${workspaceId}/change_sets`,
           });
         },
-        async LIST_WORKSPACE_USERS(workspaceId: string) {
-          return new ApiRequest<{ users: AdminUser[] }>({
+        async LIST_WORKSPACE_USERS(
+          workspaceId: string,
+          page?: { limit: number; offset: number },
+        ) {
+          return new ApiRequest<{ users: AdminUser[]; hasMore: boolean }>({
             method: "get",
             url: `${API_PREFIX}/workspaces/${workspaceId}/users`,
+            params: page,
           });
         },
         async GET_SNAPSHOT(workspaceId: string, changeSetId: string) {
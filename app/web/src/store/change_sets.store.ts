@@ -59,6 +59,7 @@ export interface ChangeSetApproval {
 export interface ApprovalData {
   requirements: ChangeSetApprovalRequirement[];
   latestApprovals: ChangeSetApproval[];
+  unsatisfiableRequirementIds: Ulid[];
 }
 
 export function useChangeSetsStore() {
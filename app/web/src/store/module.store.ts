@@ -227,6 +227,11 @@ export const useModuleStore = () => {
                 upgradeable: Record<SchemaVariantId, LatestModule>;
                 installable: LatestModule[];
                 contributable: SchemaVariantId[];
+                summary: {
+                  installable: number;
+                  upgradeable: number;
+                  contributable: number;
+                };
               },
               Visibility
             >({
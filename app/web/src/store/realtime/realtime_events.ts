@@ -123,6 +123,11 @@ export type WsEventPayloadMap = {
     changeSetId: ChangeSetId;
     userPk: UserId;
   };
+  ChangeSetAbandonRequested: {
+    changeSetId: ChangeSetId;
+    userPk: UserId;
+    userEmail: string;
+  };
   ChangeSetAbandonVote: {
     changeSetId: ChangeSetId;
     userPk: UserId;
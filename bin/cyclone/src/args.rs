@@ -146,6 +146,71 @@ pub(crate) struct Args {
     /// Disables process gatherer.
     #[arg(long, group = "gatherer")]
     pub(crate) disable_process_gatherer: bool,
+
+    /// Enables logging the JSON pointer paths (never the contents) of sensitive values redacted
+    /// from function results.
+    #[arg(long)]
+    pub(crate) enable_redaction_preview: bool,
+
+    /// Interval in seconds at which to ping clients while a function produces no output.
+    #[arg(long)]
+    pub(crate) execution_keepalive_interval: Option<u64>,
+
+    /// Interval in seconds at which to send clients a heartbeat message while a function runs.
+    #[arg(long)]
+    pub(crate) execution_heartbeat_interval: Option<u64>,
+
+    /// Maximum output messages per second forwarded to clients for an execution, with the excess
+    /// replaced by a suppression marker [default: unlimited]
+    #[arg(long)]
+    pub(crate) execution_output_rate_limit: Option<u32>,
+
+    /// Rejects requests of the given kind which carry no sensitive strings [example: management]
+    ///
+    /// May be given multiple times.
+    #[arg(long = "require-sensitive-strings-for", value_name = "KIND")]
+    pub(crate) require_sensitive_strings_for: Vec<String>,
+
+    /// Seconds a function's child process is given to exit after SIGTERM before it is killed.
+    #[arg(long)]
+    pub(crate) child_term_grace: Option<u64>,
+
+    /// Seconds a multi-request execution waits for the client's next request before shutting
+    /// down its child process [default: 300]
+    #[arg(long)]
+    pub(crate) multi_request_idle_timeout: Option<u64>,
+
+    /// Milliseconds to wait for a lang server to declare its protocol version before sending it
+    /// a request. Lang servers which send no handshake within this time are assumed compatible.
+    #[arg(long)]
+    pub(crate) lang_server_handshake_timeout_ms: Option<u64>,
+
+    /// Offers lang servers gzip compressed framing, used when they accept it in their handshake.
+    ///
+    /// Requires a lang server handshake timeout.
+    #[arg(long)]
+    pub(crate) enable_lang_server_compression: bool,
+
+    /// Maximum number of lang server processes running at once [default: 256]
+    #[arg(long)]
+    pub(crate) max_concurrent_processes: Option<usize>,
+
+    /// Sets an environment variable for lang server processes [example: HTTPS_PROXY=http://proxy]
+    ///
+    /// May be given multiple times.
+    #[arg(long = "lang-server-env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub(crate) lang_server_env: Vec<(String, String)>,
+
+    /// Working directory for lang server processes [default: the server's working directory]
+    #[arg(long)]
+    pub(crate) lang_server_working_dir: Option<PathBuf>,
+}
+
+fn parse_env_var(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got: {s}")),
+    }
 }
 
 impl TryFrom<Args> for Config {
@@ -211,6 +276,46 @@ impl TryFrom<Args> for Config {
         } else if args.disable_process_gatherer {
             builder.enable_forwarder(false);
         }
+
+        if args.enable_redaction_preview {
+            builder.enable_redaction_preview(true);
+        }
+
+        builder.execution_keepalive_interval(
+            args.execution_keepalive_interval.map(Duration::from_secs),
+        );
+
+        builder.execution_heartbeat_interval(
+            args.execution_heartbeat_interval.map(Duration::from_secs),
+        );
+
+        builder.execution_output_rate_limit(args.execution_output_rate_limit);
+
+        builder.require_sensitive_strings_for(
+            args.require_sensitive_strings_for.into_iter().collect(),
+        );
+
+        builder.child_term_grace(args.child_term_grace.map(Duration::from_secs));
+
+        builder
+            .multi_request_idle_timeout(args.multi_request_idle_timeout.map(Duration::from_secs));
+
+        builder.lang_server_handshake_timeout(
+            args.lang_server_handshake_timeout_ms
+                .map(Duration::from_millis),
+        );
+
+        if let Some(max_concurrent_processes) = args.max_concurrent_processes {
+            builder.max_concurrent_processes(max_concurrent_processes);
+        }
+
+        builder.lang_server_env(args.lang_server_env);
+        builder.lang_server_working_dir(args.lang_server_working_dir);
+
+        if args.enable_lang_server_compression {
+            builder.enable_lang_server_compression(true);
+        }
+
         builder.build().map_err(Into::into)
     }
 }
@@ -11,7 +11,7 @@ const LIB_NAME: &str = concat!(env!("CARGO_BIN_NAME"), "_server");
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60 * 10);
 
 fn main() -> Result<()> {
-    rt::block_on(BIN_NAME, async_main())
+    rt::try_block_on(BIN_NAME, async_main())
 }
 
 async fn async_main() -> Result<()> {
@@ -16,6 +16,7 @@ const BIN_NAME: &str = env!("CARGO_BIN_NAME");
 const LIB_NAME: &str = concat!(env!("CARGO_BIN_NAME"), "_server");
 
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60 * 10);
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
 
 fn main() -> Result<()> {
     rt::block_on(BIN_NAME, async_main())
@@ -57,7 +58,7 @@ async fn async_main() -> Result<()> {
         telemetry_application::init(config, &telemetry_tracker, telemetry_token.clone())?
     };
 
-    startup::startup(BIN_NAME).await?;
+    rt::with_timeout("startup", STARTUP_TIMEOUT, startup::startup(BIN_NAME)).await??;
 
     if args.verbose > 0 {
         telemetry
@@ -134,13 +135,17 @@ async fn run_server(
     let migration_mode_is_run = config.migration_mode().is_run();
     let is_dev_mode = config.dev_mode();
 
-    let server = Server::from_config(
-        config,
-        main_token.clone(),
-        &helping_tasks_tracker,
-        helping_tasks_token.clone(),
+    let server = rt::with_timeout(
+        "server setup",
+        STARTUP_TIMEOUT,
+        Server::from_config(
+            config,
+            main_token.clone(),
+            &helping_tasks_tracker,
+            helping_tasks_token.clone(),
+        ),
     )
-    .await?;
+    .await??;
 
     if migration_mode_is_run {
         // If migrations fail, process will exit with an error.
@@ -178,8 +183,12 @@ async fn migrate_and_quit(
     telemetry_token: CancellationToken,
     telemetry_shutdown: TelemetryShutdownGuard,
 ) -> Result<()> {
-    let migrator =
-        Migrator::from_config(config, &helping_tasks_tracker, helping_tasks_token.clone()).await?;
+    let migrator = rt::with_timeout(
+        "migrator setup",
+        STARTUP_TIMEOUT,
+        Migrator::from_config(config, &helping_tasks_tracker, helping_tasks_token.clone()),
+    )
+    .await??;
 
     let handle = main_tracker.spawn(migrator.run_migrations(false));
 
@@ -127,6 +127,12 @@ impl BytesLinesCodec {
             ..Self::default()
         }
     }
+
+    /// Returns the maximum line length when decoding.
+    #[must_use]
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
 }
 
 impl Decoder for BytesLinesCodec {
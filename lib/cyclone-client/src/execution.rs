@@ -81,7 +81,7 @@ where
                 let msg = Message::deserialize_from_str(&json_str)
                     .map_err(ExecutionError::JSONDeserialize)?;
                 match msg {
-                    Message::Start => {
+                    Message::Start { .. } => {
                         // received correct message, so proceed
                     }
                     unexpected => return Err(ExecutionError::MessageBeforeStart(unexpected)),
@@ -145,7 +145,7 @@ where
                     .map_err(ExecutionError::JSONDeserialize)?;
                 match msg {
                     // We got a heartbeat message, pass it on
-                    Message::Heartbeat => Poll::Ready(Some(Ok(ProgressMessage::Heartbeat))),
+                    Message::Heartbeat { .. } => Poll::Ready(Some(Ok(ProgressMessage::Heartbeat))),
                     // We got an output message, pass it on
                     Message::OutputStream(output_stream) => {
                         Poll::Ready(Some(Ok(ProgressMessage::OutputStream(output_stream))))
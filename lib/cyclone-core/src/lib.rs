@@ -39,6 +39,7 @@ pub use management::{ManagementFuncStatus, ManagementRequest, ManagementResultSu
 pub use progress::{
     FunctionResult, FunctionResultFailure, FunctionResultFailureError,
     FunctionResultFailureErrorKind, Message, OutputStream, ProgressMessage,
+    CURRENT_PROTOCOL_VERSION,
 };
 pub use readiness::{ReadinessStatus, ReadinessStatusParseError};
 pub use request::{CycloneRequest, CycloneRequestable};
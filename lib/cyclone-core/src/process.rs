@@ -22,6 +22,10 @@ pub enum ShutdownError {
     StartKill(#[source] io::Error),
 }
 
+/// Optionally signals a child process and waits for it to exit.
+///
+/// If the child has not exited within `wait_timeout` (or 10 seconds when `None`), it is sent a
+/// `SIGKILL` and reaped.
 pub async fn child_shutdown(
     child: &mut Child,
     signal: Option<Signal>,
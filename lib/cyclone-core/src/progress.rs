@@ -1,6 +1,15 @@
+use std::time::Duration;
+
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use strum::Display;
 
+/// The version of the protocol spoken between cyclone, its clients and its lang servers.
+///
+/// Sent by cyclone in the [`Message::Start`] message and declared by a lang server in its
+/// handshake, so that peers of incompatible vintages fail loudly rather than misinterpret each
+/// other's messages.
+pub const CURRENT_PROTOCOL_VERSION: u32 = 1;
+
 /// A line of output, streamed from an executing function.
 ///
 /// An instance of this type typically maps to a single line of output from a process--either on
@@ -29,6 +38,11 @@ pub struct OutputStream {
     pub group: Option<String>,
     /// The contents of the output line.
     pub message: String,
+    /// Structured key/value fields attached to the output line, if any.
+    ///
+    /// Older producers don't send fields, in which case this is `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<serde_json::Map<String, serde_json::Value>>,
     /// A timestamp in seconds since UNIX epoch.
     ///
     /// The timestamp generated locally when the message was created.
@@ -57,10 +71,18 @@ pub enum ProgressMessage {
 pub enum Message<R> {
     Fail(Fail),
     Finish,
-    Heartbeat,
+    /// Sent periodically while a function executes, as a liveness signal which is independent of
+    /// the transport.
+    Heartbeat {
+        /// Milliseconds since the execution started.
+        elapsed_ms: u64,
+    },
     OutputStream(OutputStream),
     Result(FunctionResult<R>),
-    Start,
+    Start {
+        /// The protocol version the sender speaks (see [`CURRENT_PROTOCOL_VERSION`]).
+        protocol_version: u32,
+    },
 }
 
 impl<R> Message<R> {
@@ -69,6 +91,18 @@ impl<R> Message<R> {
             message: message.into(),
         })
     }
+
+    pub fn start() -> Self {
+        Self::Start {
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+        }
+    }
+
+    pub fn heartbeat(elapsed: Duration) -> Self {
+        Self::Heartbeat {
+            elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
 }
 
 impl<R> Message<R>
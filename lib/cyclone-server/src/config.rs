@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     net::{SocketAddr, ToSocketAddrs},
     path::{Path, PathBuf},
     time::Duration,
@@ -23,6 +24,8 @@ pub enum ConfigError {
 
 type Result<T> = std::result::Result<T, ConfigError>;
 
+const DEFAULT_MAX_CONCURRENT_PROCESSES: usize = 256;
+
 #[derive(Debug, Builder)]
 pub struct Config {
     #[builder(default)]
@@ -66,6 +69,42 @@ pub struct Config {
 
     #[builder(setter(into), default = "false")]
     enable_process_gatherer: bool,
+
+    #[builder(setter(into), default = "false")]
+    enable_redaction_preview: bool,
+
+    #[builder(default)]
+    execution_keepalive_interval: Option<Duration>,
+
+    #[builder(default)]
+    execution_heartbeat_interval: Option<Duration>,
+
+    #[builder(default)]
+    execution_output_rate_limit: Option<u32>,
+
+    #[builder(default)]
+    require_sensitive_strings_for: HashSet<String>,
+
+    #[builder(default)]
+    child_term_grace: Option<Duration>,
+
+    #[builder(default)]
+    multi_request_idle_timeout: Option<Duration>,
+
+    #[builder(default)]
+    lang_server_handshake_timeout: Option<Duration>,
+
+    #[builder(default = "DEFAULT_MAX_CONCURRENT_PROCESSES")]
+    max_concurrent_processes: usize,
+
+    #[builder(default)]
+    lang_server_env: Vec<(String, String)>,
+
+    #[builder(default)]
+    lang_server_working_dir: Option<PathBuf>,
+
+    #[builder(setter(into), default = "false")]
+    enable_lang_server_compression: bool,
 }
 
 impl Config {
@@ -158,6 +197,78 @@ impl Config {
     pub fn enable_process_gatherer(&self) -> bool {
         self.enable_process_gatherer
     }
+
+    /// Gets a reference to the config's enable redaction preview.
+    #[must_use]
+    pub fn enable_redaction_preview(&self) -> bool {
+        self.enable_redaction_preview
+    }
+
+    /// Gets a reference to the config's execution keepalive interval.
+    #[must_use]
+    pub fn execution_keepalive_interval(&self) -> Option<Duration> {
+        self.execution_keepalive_interval
+    }
+
+    /// Gets a reference to the config's execution heartbeat interval.
+    #[must_use]
+    pub fn execution_heartbeat_interval(&self) -> Option<Duration> {
+        self.execution_heartbeat_interval
+    }
+
+    /// Gets a reference to the config's maximum output messages per second for an execution.
+    #[must_use]
+    pub fn execution_output_rate_limit(&self) -> Option<u32> {
+        self.execution_output_rate_limit
+    }
+
+    /// Gets a reference to the config's set of request kinds which must carry sensitive strings.
+    #[must_use]
+    pub fn require_sensitive_strings_for(&self) -> &HashSet<String> {
+        &self.require_sensitive_strings_for
+    }
+
+    /// Gets a reference to the config's child process `SIGTERM` grace period optional override.
+    #[must_use]
+    pub fn child_term_grace(&self) -> Option<Duration> {
+        self.child_term_grace
+    }
+
+    /// Gets a reference to the config's multi-request idle timeout optional override.
+    #[must_use]
+    pub fn multi_request_idle_timeout(&self) -> Option<Duration> {
+        self.multi_request_idle_timeout
+    }
+
+    /// Gets a reference to the config's optional lang server handshake timeout.
+    #[must_use]
+    pub fn lang_server_handshake_timeout(&self) -> Option<Duration> {
+        self.lang_server_handshake_timeout
+    }
+
+    /// Gets the config's maximum number of concurrently running lang server processes.
+    #[must_use]
+    pub fn max_concurrent_processes(&self) -> usize {
+        self.max_concurrent_processes
+    }
+
+    /// Gets the config's environment variables set for lang server processes.
+    #[must_use]
+    pub fn lang_server_env(&self) -> &[(String, String)] {
+        &self.lang_server_env
+    }
+
+    /// Gets the config's working directory for lang server processes.
+    #[must_use]
+    pub fn lang_server_working_dir(&self) -> Option<&Path> {
+        self.lang_server_working_dir.as_deref()
+    }
+
+    /// Gets whether lang server processes are offered compressed framing.
+    #[must_use]
+    pub fn enable_lang_server_compression(&self) -> bool {
+        self.enable_lang_server_compression
+    }
 }
 
 impl ConfigBuilder {
@@ -1,8 +1,9 @@
 use std::{
+    collections::HashSet,
     fmt, io,
     marker::{PhantomData, Unpin},
-    path::PathBuf,
-    process::Stdio,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
     string::FromUtf8Error,
     sync::Arc,
     time::Duration,
@@ -14,8 +15,10 @@ use cyclone_core::{
     process::{self, ShutdownError},
     CycloneRequest, CycloneRequestable, FunctionResult, FunctionResultFailure,
     FunctionResultFailureError, FunctionResultFailureErrorKind, Message, OutputStream,
+    CURRENT_PROTOCOL_VERSION,
 };
 use futures::{SinkExt, StreamExt, TryStreamExt};
+use nix::{libc, sys::signal::kill, unistd::Pid};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use si_crypto::SensitiveStrings;
@@ -23,16 +26,32 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::time::timeout;
 use tokio::{
+    io::AsyncRead,
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
+    sync::{OwnedSemaphorePermit, RwLock, Semaphore},
     time,
 };
 use tokio_serde::{formats::SymmetricalJson, Deserializer, Framed, SymmetricallyFramed};
 use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
 
-use crate::WebSocketMessage;
+use crate::{
+    lines_codec::{ChildLinesCodec, GZIP_CAPABILITY},
+    WebSocketMessage,
+};
 
 const TX_TIMEOUT_SECS: Duration = Duration::from_secs(5);
 const DEFAULT_LANG_SERVER_PROCESS_TIMEOUT: Duration = Duration::from_secs(32 * 60);
+const DEFAULT_CHILD_TERM_GRACE: Duration = Duration::from_secs(10);
+const DEFAULT_MULTI_REQUEST_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How long an execution waits for a spawn permit before giving up with
+/// [`ExecutionError::AtCapacity`].
+const SPAWN_PERMIT_WAIT: Duration = Duration::from_secs(10);
+const SKIPPED_OUTPUT_MESSAGE: &str = "[output line could not be serialized and was skipped]";
+/// The window over which an execution's output rate limit is counted.
+const OUTPUT_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+/// The environment variable through which a lang server is offered compression, set to the
+/// handshake capability it should declare to accept it.
+const LANG_SERVER_COMPRESSION_ENV: &str = "SI_LANG_SERVER_COMPRESSION";
 
 pub fn new<Request, LangServerSuccess, Success>(
     lang_server_path: impl Into<PathBuf>,
@@ -40,6 +59,7 @@ pub fn new<Request, LangServerSuccess, Success>(
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Option<u64>,
     command: String,
+    env: Vec<(String, String)>,
 ) -> Execution<Request, LangServerSuccess, Success>
 where
     Request: CycloneRequestable,
@@ -53,6 +73,18 @@ where
             None => DEFAULT_LANG_SERVER_PROCESS_TIMEOUT,
         },
         command,
+        env,
+        redaction_preview: false,
+        keepalive_interval: None,
+        heartbeat_interval: None,
+        output_rate_limit: None,
+        require_sensitive_strings: false,
+        term_grace: DEFAULT_CHILD_TERM_GRACE,
+        multi_request_idle_timeout: DEFAULT_MULTI_REQUEST_IDLE_TIMEOUT,
+        handshake_timeout: None,
+        compression: false,
+        spawn_permits: None,
+        working_dir: None,
         request_marker: PhantomData,
         lang_server_success_marker: PhantomData,
         success_marker: PhantomData,
@@ -62,6 +94,10 @@ where
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ExecutionError {
+    #[error("no child process could be spawned within {0:?}; the server is at capacity")]
+    AtCapacity(Duration),
+    #[error("child process exited before sending a result")]
+    ChildExitedEarly,
     #[error("failed to consume the {0} stream for the child process")]
     ChildIO(&'static str),
     #[error("failed to receive child process message")]
@@ -80,6 +116,18 @@ pub enum ExecutionError {
     JSONDeserialize(#[source] serde_json::Error),
     #[error("failed to serialize json message")]
     JSONSerialize(#[source] serde_json::Error),
+    #[error("lang server is not an executable file: {0} (mode {1:o})")]
+    LangServerNotExecutable(PathBuf, u32),
+    #[error("lang server not found: {0}")]
+    LangServerNotFound(PathBuf),
+    #[error("request requires sensitive strings but none were provided; kind={0}")]
+    MissingSensitiveStrings(String),
+    #[error(
+        "lang server declared protocol version {lang_server}, incompatible with server protocol version {server}"
+    )]
+    ProtocolVersionMismatch { server: u32, lang_server: u32 },
+    #[error("no request received from the client within {0:?}")]
+    RequestIdleTimeout(Duration),
     #[error("send timeout")]
     SendTimeout(#[source] tokio::time::error::Elapsed),
     #[error("unexpected websocket message type: {0:?}")]
@@ -92,6 +140,44 @@ pub enum ExecutionError {
     WSRecvIO(#[source] axum::Error),
     #[error("failed to send websocket message")]
     WSSendIO(#[source] axum::Error),
+    #[error("working directory for child process does not exist: {0}")]
+    WorkingDirNotFound(PathBuf),
+}
+
+impl ExecutionError {
+    /// Returns whether the execution which failed with this error is worth retrying.
+    ///
+    /// Failures to spawn or talk to the child process, timeouts, and a lost websocket connection
+    /// are retryable since another attempt may well succeed. Errors in serializing or decoding
+    /// messages, protocol violations, and problems with the request or the server's setup are
+    /// terminal, as they would fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // The server had no capacity to spawn a child process in time
+            Self::AtCapacity(_) => true,
+            // The child process crashed or its stdio failed
+            Self::ChildExitedEarly
+            | Self::ChildIO(_)
+            | Self::ChildRecvIO(_)
+            | Self::ChildSendIO(_)
+            | Self::ChildShutdown(_) => true,
+            // The child process could not be started
+            Self::ChildSpawn(_, _) => true,
+            // The child process or the client took too long
+            Self::ChildTimeout(_) | Self::RequestIdleTimeout(_) | Self::SendTimeout(_) => true,
+            // The websocket connection to the client failed
+            Self::WSClose(_) | Self::WSRecvClosed | Self::WSRecvIO(_) | Self::WSSendIO(_) => true,
+            // A message could not be encoded or decoded
+            Self::FromUtf8(_) | Self::JSONDeserialize(_) | Self::JSONSerialize(_) => false,
+            // The client or lang server did not follow the protocol
+            Self::ProtocolVersionMismatch { .. } | Self::UnexpectedMessageType(_) => false,
+            // The request or the server's configuration can not be executed as is
+            Self::LangServerNotExecutable(_, _)
+            | Self::LangServerNotFound(_)
+            | Self::MissingSensitiveStrings(_)
+            | Self::WorkingDirNotFound(_) => false,
+        }
+    }
 }
 
 type Result<T> = std::result::Result<T, ExecutionError>;
@@ -106,6 +192,18 @@ where
     lang_server_function_timeout: Option<usize>,
     lang_server_process_timeout: Duration,
     command: String,
+    env: Vec<(String, String)>,
+    redaction_preview: bool,
+    keepalive_interval: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    output_rate_limit: Option<u32>,
+    require_sensitive_strings: bool,
+    term_grace: Duration,
+    multi_request_idle_timeout: Duration,
+    handshake_timeout: Option<Duration>,
+    compression: bool,
+    spawn_permits: Option<Arc<Semaphore>>,
+    working_dir: Option<PathBuf>,
     request_marker: PhantomData<Request>,
     lang_server_success_marker: PhantomData<LangServerSuccess>,
     success_marker: PhantomData<Success>,
@@ -117,6 +215,108 @@ where
     LangServerSuccess: DeserializeOwned,
     Success: Serialize,
 {
+    /// Enables (or disables) a debug mode which, whenever a sensitive string is redacted from a
+    /// function result, emits an event recording the JSON pointer path of the redacted value (but
+    /// never its contents).
+    pub fn with_redaction_preview(mut self, enabled: bool) -> Self {
+        self.redaction_preview = enabled;
+        self
+    }
+
+    /// Sets an interval at which to send websocket pings to the client while waiting on output
+    /// from the child process, so that intermediaries with idle timeouts don't drop the
+    /// connection. No pings are sent when `None` (the default).
+    pub fn with_keepalive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.keepalive_interval = interval;
+        self
+    }
+
+    /// Sets an interval at which to send [`Message::Heartbeat`] messages, carrying the time
+    /// elapsed since the execution started, while waiting on the function result. Unlike
+    /// websocket pings, these are visible to the client as part of the protocol. No heartbeats are
+    /// sent when `None` (the default).
+    pub fn with_heartbeat_interval(mut self, interval: Option<Duration>) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// Caps the number of output messages forwarded to the client per second. Output beyond the
+    /// cap is dropped and later reported as a single "[N lines suppressed]" output line, while
+    /// results are always forwarded. Output is unlimited when `None` (the default).
+    pub fn with_output_rate_limit(mut self, limit: Option<u32>) -> Self {
+        self.output_rate_limit = limit;
+        self
+    }
+
+    /// Requires (or not) that the request carries at least one sensitive string. Kinds of
+    /// functions which always operate on secrets should never receive an empty set, as that most
+    /// likely means the secrets weren't wired through and so wouldn't be redacted from output.
+    pub fn with_require_sensitive_strings(mut self, required: bool) -> Self {
+        self.require_sensitive_strings = required;
+        self
+    }
+
+    /// Sets how long the child process is given to exit after a `SIGTERM` before it is sent a
+    /// `SIGKILL`. Defaults to 10 seconds when `None`.
+    pub fn with_term_grace(mut self, term_grace: Option<Duration>) -> Self {
+        self.term_grace = term_grace.unwrap_or(DEFAULT_CHILD_TERM_GRACE);
+        self
+    }
+
+    /// Sets how long a multi-request execution waits for the client's next request before giving
+    /// up and shutting down the child process. Defaults to 5 minutes when `None`.
+    pub fn with_multi_request_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.multi_request_idle_timeout =
+            idle_timeout.unwrap_or(DEFAULT_MULTI_REQUEST_IDLE_TIMEOUT);
+        self
+    }
+
+    /// Waits up to the given duration after spawning the child for the lang server to declare its
+    /// protocol version in a handshake line, failing if the version is incompatible. Lang servers
+    /// which don't send a handshake are assumed compatible once the wait elapses. No handshake is
+    /// read when `None` (the default).
+    pub fn with_handshake_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Offers (or not) the lang server gzip compressed framing for the request written to its stdin
+    /// and the messages read from its stdout. Compression is only used when the lang server accepts
+    /// it by declaring the `gzip` capability in its handshake, so it requires a handshake timeout
+    /// and is never used in multi-request mode. Lines are uncompressed by default.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Caps the number of concurrently running child processes, shared with all other executions
+    /// given the same semaphore. A permit is held from just before the child is spawned until the
+    /// execution completes, and an execution waits a bounded time for one before failing with
+    /// [`ExecutionError::AtCapacity`].
+    pub fn with_spawn_permits(mut self, spawn_permits: Arc<Semaphore>) -> Self {
+        self.spawn_permits = Some(spawn_permits);
+        self
+    }
+
+    /// Runs the child process in the given working directory, which must exist when the child is
+    /// spawned. The child inherits the server's working directory when `None` (the default).
+    pub fn with_working_dir(mut self, working_dir: Option<PathBuf>) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    async fn acquire_spawn_permit(&self) -> Result<Option<OwnedSemaphorePermit>> {
+        let Some(spawn_permits) = &self.spawn_permits else {
+            return Ok(None);
+        };
+
+        match timeout(SPAWN_PERMIT_WAIT, Arc::clone(spawn_permits).acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            // The semaphore is never closed, but a closed one has no permits to give out either
+            Ok(Err(_)) | Err(_) => Err(ExecutionError::AtCapacity(SPAWN_PERMIT_WAIT)),
+        }
+    }
+
     pub async fn start(
         self,
         ws: &mut WebSocket,
@@ -126,29 +326,37 @@ where
         // Read the request message from the web socket
         let cyclone_request = Self::read_request(ws).await?;
         let (request, sensitive_strings) = cyclone_request.into_parts();
+        ensure_sensitive_strings(
+            &self.command,
+            self.require_sensitive_strings,
+            &sensitive_strings,
+        )?;
 
-        // Spawn lang server as a child process with handles on all i/o descriptors
-        let mut command = Command::new(&self.lang_server_path);
-        command
-            .arg(&self.command)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        check_lang_server_path(&self.lang_server_path).await?;
+        let spawn_permit = self.acquire_spawn_permit().await?;
+        let mut child = self.spawn_child(false)?;
 
-        if let Some(timeout) = self.lang_server_function_timeout {
-            command.arg("--timeout").arg(timeout.to_string());
-        }
-        if self.lang_server_debugging {
-            command.env("SI_LANG_JS_LOG", "*");
+        let mut stdout_lines = {
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or(ExecutionError::ChildIO("stdout"))?;
+            FramedRead::new(stdout, ChildLinesCodec::plain())
+        };
+        let mut stdin_codec = ChildLinesCodec::plain();
+        if let Some(handshake_timeout) = self.handshake_timeout {
+            let handshake = read_handshake(&mut stdout_lines, handshake_timeout).await?;
+            if self.compression
+                && handshake.is_some_and(|handshake| handshake.supports(GZIP_CAPABILITY))
+            {
+                debug!("lang server accepted gzip compression");
+                *stdout_lines.decoder_mut() = ChildLinesCodec::gzip();
+                stdin_codec = ChildLinesCodec::gzip();
+            }
         }
 
-        debug!(cmd = ?command, "spawning child process");
-        let mut child = command
-            .spawn()
-            .map_err(|err| ExecutionError::ChildSpawn(err, self.lang_server_path.clone()))?;
-
         let stdin = child.stdin.take().ok_or(ExecutionError::ChildIO("stdin"))?;
-        Self::child_send_function_request(stdin, request).await?;
+        Self::child_send_function_request(stdin, request, stdin_codec).await?;
 
         let stderr = {
             let stderr = child
@@ -158,25 +366,66 @@ where
             FramedRead::new(stderr, BytesLinesCodec::new())
         };
 
-        let stdout = {
-            let stdout = child
-                .stdout
-                .take()
-                .ok_or(ExecutionError::ChildIO("stdout"))?;
-            let codec = FramedRead::new(stdout, BytesLinesCodec::new());
-            SymmetricallyFramed::new(codec, SymmetricalJson::default())
-        };
+        let stdout = SymmetricallyFramed::new(stdout_lines, SymmetricalJson::default());
 
         Ok(ExecutionStarted {
             child,
             stdout,
             stderr,
             sensitive_strings: Arc::new(sensitive_strings),
+            redaction_preview: self.redaction_preview,
+            keepalive_interval: self.keepalive_interval,
+            heartbeat_interval: self.heartbeat_interval,
+            output_rate_limit: self.output_rate_limit,
+            spawn_permit,
+            term_grace: self.term_grace,
             success_marker: self.success_marker,
             lang_server_process_timeout: self.lang_server_process_timeout,
         })
     }
 
+    fn spawn_child(&self, multi_request: bool) -> Result<Child> {
+        // Spawn lang server as a child process with handles on all i/o descriptors
+        let mut command = Command::new(&self.lang_server_path);
+        command
+            .arg(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        if multi_request {
+            command.arg("--multi-request");
+        }
+        if let Some(timeout) = self.lang_server_function_timeout {
+            command.arg("--timeout").arg(timeout.to_string());
+        }
+        command.envs(self.env.iter().map(|(key, value)| (key, value)));
+        if let Some(working_dir) = &self.working_dir {
+            if !working_dir.is_dir() {
+                return Err(ExecutionError::WorkingDirNotFound(working_dir.clone()));
+            }
+            command.current_dir(working_dir);
+        }
+        if self.lang_server_debugging {
+            command.env("SI_LANG_JS_LOG", "*");
+        }
+        if self.compression && self.handshake_timeout.is_some() && !multi_request {
+            command.env(LANG_SERVER_COMPRESSION_ENV, GZIP_CAPABILITY);
+        }
+
+        // Environment values may hold credentials, so only their keys are logged
+        let std_command = command.as_std();
+        debug!(
+            program = ?std_command.get_program(),
+            args = ?std_command.get_args().collect::<Vec<_>>(),
+            env_keys = ?std_command.get_envs().map(|(key, _)| key).collect::<Vec<_>>(),
+            "spawning child process",
+        );
+        command
+            .spawn()
+            .map_err(|err| ExecutionError::ChildSpawn(err, self.lang_server_path.clone()))
+    }
+
     async fn read_request(ws: &mut WebSocket) -> Result<CycloneRequest<Request>> {
         let request = match ws.next().await {
             Some(Ok(WebSocketMessage::Text(json_str))) => {
@@ -190,7 +439,7 @@ where
     }
 
     async fn ws_send_start(ws: &mut WebSocket) -> Result<()> {
-        let msg = Message::<Success>::Start
+        let msg = Message::<Success>::start()
             .serialize_to_string()
             .map_err(ExecutionError::JSONSerialize)?;
 
@@ -201,10 +450,14 @@ where
         Ok(())
     }
 
-    async fn child_send_function_request(stdin: ChildStdin, request: Request) -> Result<()> {
+    async fn child_send_function_request(
+        stdin: ChildStdin,
+        request: Request,
+        codec: ChildLinesCodec,
+    ) -> Result<()> {
         let value = serde_json::to_value(&request).map_err(ExecutionError::JSONSerialize)?;
 
-        let codec = FramedWrite::new(stdin, BytesLinesCodec::new());
+        let codec = FramedWrite::new(stdin, codec);
         let mut stdin = SymmetricallyFramed::new(codec, SymmetricalJson::default());
 
         time::timeout(TX_TIMEOUT_SECS, stdin.send(value))
@@ -219,10 +472,328 @@ where
     }
 }
 
-type SiFramedRead = FramedRead<ChildStdout, BytesLinesCodec>;
+impl<Request, LangServerSuccess, Success> Execution<Request, LangServerSuccess, Success>
+where
+    Request: Serialize + DeserializeOwned + Unpin + core::fmt::Debug + CycloneRequestable,
+    Success: Serialize + Unpin + fmt::Debug,
+    LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
+    SymmetricalJson<SiMessage<LangServerSuccess>>: Deserializer<SiMessage<LangServerSuccess>>,
+    SiDecoderError: From<SiJsonError<LangServerSuccess>>,
+{
+    /// Runs the execution in multi-request mode, serving a sequence of requests from the client
+    /// with a single child process until the client closes the websocket, or sends no request
+    /// within the idle timeout (see [`Self::with_multi_request_idle_timeout`]).
+    ///
+    /// After the initial start message, each request sent by the client is written to the child's
+    /// stdin as its own line and the resulting output and result messages are streamed back. No
+    /// finish message is sent; the child is shut down once the client closes the websocket.
+    ///
+    /// Lang servers must opt in to this mode: the child is passed a `--multi-request` argument and
+    /// is expected to read newline-delimited requests, emitting exactly one result per request,
+    /// until its stdin is closed (rather than treating the end of stdin as the end of its only
+    /// request).
+    pub async fn process_many(self, ws: &mut WebSocket) -> Result<()> {
+        Self::ws_send_start(ws).await?;
+
+        check_lang_server_path(&self.lang_server_path).await?;
+        let _spawn_permit = self.acquire_spawn_permit().await?;
+        let mut child = self.spawn_child(true)?;
+
+        let mut stdin = {
+            let stdin = child.stdin.take().ok_or(ExecutionError::ChildIO("stdin"))?;
+            let codec = FramedWrite::new(stdin, BytesLinesCodec::new());
+            SymmetricallyFramed::new(codec, SymmetricalJson::<Value>::default())
+        };
+
+        let stderr = {
+            let stderr = child
+                .stderr
+                .take()
+                .ok_or(ExecutionError::ChildIO("stderr"))?;
+            FramedRead::new(stderr, BytesLinesCodec::new())
+        };
+
+        let mut stdout: SiFramed<SiMessage<LangServerSuccess>> = {
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or(ExecutionError::ChildIO("stdout"))?;
+            let codec = FramedRead::new(stdout, ChildLinesCodec::plain());
+            SymmetricallyFramed::new(codec, SymmetricalJson::default())
+        };
+
+        // Sensitive strings accumulate across requests as the child (and so its stderr) is shared
+        let sensitive_strings = Arc::new(RwLock::new(SensitiveStrings::default()));
+        tokio::spawn(handle_stderr(stderr, sensitive_strings.clone()));
+
+        let served = async {
+            loop {
+                let next_request =
+                    timeout(self.multi_request_idle_timeout, Self::read_next_request(ws))
+                        .await
+                        .map_err(|_elapsed| {
+                            ExecutionError::RequestIdleTimeout(self.multi_request_idle_timeout)
+                        })?;
+                let Some(cyclone_request) = next_request? else {
+                    break;
+                };
+                let (request, request_sensitive_strings) = cyclone_request.into_parts();
+                ensure_sensitive_strings(
+                    &self.command,
+                    self.require_sensitive_strings,
+                    &request_sensitive_strings,
+                )?;
+                sensitive_strings
+                    .write()
+                    .await
+                    .extend(HashSet::from(request_sensitive_strings));
+
+                let value =
+                    serde_json::to_value(&request).map_err(ExecutionError::JSONSerialize)?;
+                time::timeout(TX_TIMEOUT_SECS, stdin.send(value))
+                    .await
+                    .map_err(ExecutionError::SendTimeout)?
+                    .map_err(ExecutionError::ChildSendIO)?;
+
+                let forwarded = Self::forward_until_result(
+                    &mut stdout,
+                    ws,
+                    &sensitive_strings,
+                    self.redaction_preview,
+                    self.output_rate_limit,
+                );
+                match timeout(self.lang_server_process_timeout, forwarded).await {
+                    Ok(forwarded) => forwarded?,
+                    Err(_elapsed) => {
+                        return Err(ExecutionError::ChildTimeout(
+                            self.lang_server_process_timeout,
+                        ))
+                    }
+                }
+            }
+
+            Result::<_>::Ok(())
+        }
+        .await;
+
+        // Closing stdin signals to the child that no more requests are coming
+        if let Err(err) = time::timeout(TX_TIMEOUT_SECS, stdin.close()).await {
+            warn!(error = ?err, "timed out closing child process stdin");
+        }
+        let shutdown = process::child_shutdown(
+            &mut child,
+            Some(process::Signal::SIGTERM),
+            Some(self.term_grace),
+        )
+        .await;
+        drop(child);
+
+        served?;
+        shutdown?;
+        Ok(())
+    }
+
+    /// Reads the next request from the websocket, returning `None` once the client has closed it.
+    async fn read_next_request(ws: &mut WebSocket) -> Result<Option<CycloneRequest<Request>>> {
+        loop {
+            match ws.next().await {
+                Some(Ok(WebSocketMessage::Text(json_str))) => {
+                    return serde_json::from_str(&json_str)
+                        .map(Some)
+                        .map_err(ExecutionError::JSONDeserialize);
+                }
+                Some(Ok(WebSocketMessage::Ping(_) | WebSocketMessage::Pong(_))) => continue,
+                Some(Ok(WebSocketMessage::Close(_))) | None => return Ok(None),
+                Some(Ok(unexpected)) => {
+                    return Err(ExecutionError::UnexpectedMessageType(unexpected))
+                }
+                Some(Err(err)) => return Err(ExecutionError::WSRecvIO(err)),
+            }
+        }
+    }
+
+    /// Forwards output messages from the child to the websocket until a result is forwarded.
+    async fn forward_until_result(
+        stdout: &mut SiFramed<SiMessage<LangServerSuccess>>,
+        ws: &mut WebSocket,
+        sensitive_strings: &RwLock<SensitiveStrings>,
+        redaction_preview: bool,
+        output_rate_limit: Option<u32>,
+    ) -> Result<()> {
+        let mut output_limiter = OutputRateLimiter::new(output_rate_limit);
+        while let Some(ls_msg) = stdout
+            .try_next()
+            .await
+            .map_err(ExecutionError::ChildRecvIO)?
+        {
+            let sensitive_strings = sensitive_strings.read().await;
+            let (is_result, msg) = match ls_msg {
+                LangServerMessage::Output(mut output) => {
+                    ExecutionStarted::<LangServerSuccess, Success>::filter_output(
+                        &mut output,
+                        &sensitive_strings,
+                    )?;
+                    (false, Message::<Success>::OutputStream(output.into()))
+                }
+                LangServerMessage::Result(mut result) => {
+                    let redacted_paths =
+                        ExecutionStarted::<LangServerSuccess, Success>::filter_result(
+                            &mut result,
+                            &sensitive_strings,
+                            redaction_preview,
+                        )?;
+                    for json_pointer in redacted_paths {
+                        warn!(
+                            json_pointer = %json_pointer,
+                            "redacted sensitive value from function result",
+                        );
+                    }
+                    (true, Message::Result(result.into()))
+                }
+            };
+
+            for msg in output_limiter.forward(msg, time::Instant::now()) {
+                let ws_msg = serialize_ws_message(msg, Message::serialize_to_string)?;
+                ws.send(ws_msg).await.map_err(ExecutionError::WSSendIO)?;
+            }
+
+            if is_result {
+                return Ok(());
+            }
+        }
+
+        Err(ExecutionError::ChildExitedEarly)
+    }
+}
+
+/// Serializes a message to be sent over the websocket.
+///
+/// A single output message failing to serialize shouldn't lose an otherwise successful execution,
+/// so it is logged and replaced by a synthetic error output line. A result failing to serialize
+/// (or any other message) is still an error.
+fn serialize_ws_message<Success>(
+    msg: Message<Success>,
+    serialize: impl Fn(&Message<Success>) -> serde_json::Result<String>,
+) -> Result<WebSocketMessage> {
+    let err = match serialize(&msg) {
+        Ok(json_str) => return Ok(WebSocketMessage::Text(json_str)),
+        Err(err) => err,
+    };
+
+    match msg {
+        Message::OutputStream(output) => {
+            warn!(
+                error = ?err,
+                execution_id = %output.execution_id,
+                "failed to serialize output message, skipping",
+            );
+            let synthetic = Message::OutputStream(OutputStream {
+                stream: output.stream,
+                execution_id: output.execution_id,
+                level: "error".to_owned(),
+                group: output.group,
+                message: SKIPPED_OUTPUT_MESSAGE.to_owned(),
+                fields: None,
+                timestamp: crate::timestamp(),
+            });
+            serialize(&synthetic)
+                .map(WebSocketMessage::Text)
+                .map_err(ExecutionError::JSONSerialize)
+        }
+        _ => Err(ExecutionError::JSONSerialize(err)),
+    }
+}
+
+/// Reads the lang server's handshake line, if one is sent within `handshake_timeout`, and checks
+/// that its declared protocol version is compatible.
+///
+/// Lang servers which predate the handshake write nothing to stdout until they have received a
+/// request, so a timeout is treated as compatible and `None` is returned.
+async fn read_handshake<R>(
+    stdout: &mut FramedRead<R, ChildLinesCodec>,
+    handshake_timeout: Duration,
+) -> Result<Option<LangServerHandshake>>
+where
+    R: AsyncRead + Unpin,
+{
+    let line = match timeout(handshake_timeout, stdout.next()).await {
+        Ok(Some(line)) => line.map_err(ExecutionError::ChildRecvIO)?,
+        Ok(None) => return Err(ExecutionError::ChildExitedEarly),
+        Err(_elapsed) => {
+            debug!(
+                ?handshake_timeout,
+                "no lang server handshake received, assuming compatible protocol"
+            );
+            return Ok(None);
+        }
+    };
+
+    let LangServerHandshakeMessage::Handshake(handshake) =
+        serde_json::from_slice(&line).map_err(ExecutionError::JSONDeserialize)?;
+    if handshake.version != CURRENT_PROTOCOL_VERSION {
+        return Err(ExecutionError::ProtocolVersionMismatch {
+            server: CURRENT_PROTOCOL_VERSION,
+            lang_server: handshake.version,
+        });
+    }
+    debug!(
+        version = handshake.version,
+        capabilities = ?handshake.capabilities,
+        "received lang server handshake"
+    );
+
+    Ok(Some(handshake))
+}
+
+/// Checks that the lang server exists and is executable, so that a misconfigured path is reported
+/// as such rather than as the io error of a failed spawn. Bare program names are looked up on the
+/// `PATH` when spawning, so they are left to the spawn itself, as is any other metadata error.
+async fn check_lang_server_path(path: &Path) -> Result<()> {
+    if path.components().count() < 2 {
+        return Ok(());
+    }
+
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(ExecutionError::LangServerNotFound(path.to_path_buf()));
+        }
+        Err(_) => return Ok(()),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        if !metadata.is_file() || mode & 0o111 == 0 {
+            return Err(ExecutionError::LangServerNotExecutable(
+                path.to_path_buf(),
+                mode,
+            ));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    Ok(())
+}
+
+fn ensure_sensitive_strings(
+    kind: &str,
+    required: bool,
+    sensitive_strings: &SensitiveStrings,
+) -> Result<()> {
+    if required && sensitive_strings.is_empty() {
+        return Err(ExecutionError::MissingSensitiveStrings(kind.to_owned()));
+    }
+    Ok(())
+}
+
+type SiFramedRead = FramedRead<ChildStdout, ChildLinesCodec>;
 type SiFramed<S> = Framed<SiFramedRead, S, S, SymmetricalJson<S>>;
 type SiMessage<S> = LangServerMessage<S>;
-type SiDecoderError = <BytesLinesCodec as Decoder>::Error;
+type SiDecoderError = <ChildLinesCodec as Decoder>::Error;
 type SiJsonError<S> = <SymmetricalJson<SiMessage<S>> as Deserializer<SiMessage<S>>>::Error;
 
 #[derive(Debug)]
@@ -231,23 +802,126 @@ pub struct ExecutionStarted<LangServerSuccess, Success> {
     stdout: SiFramed<SiMessage<LangServerSuccess>>,
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
     sensitive_strings: Arc<SensitiveStrings>,
+    redaction_preview: bool,
+    keepalive_interval: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    output_rate_limit: Option<u32>,
+    spawn_permit: Option<OwnedSemaphorePermit>,
+    term_grace: Duration,
     success_marker: PhantomData<Success>,
     lang_server_process_timeout: Duration,
 }
 
+/// Redacts all sensitive strings in the values (but not the keys) of structured output fields.
+fn redact_fields(
+    fields: &mut serde_json::Map<String, Value>,
+    sensitive_strings: &SensitiveStrings,
+) {
+    for value in fields.values_mut() {
+        sensitive_strings.redact_value(value);
+    }
+}
+
+/// Caps the number of output messages forwarded to a client per [`OUTPUT_RATE_LIMIT_WINDOW`].
+///
+/// Output beyond the cap is dropped and counted, and the count is reported as a single synthetic
+/// output line ahead of the next message forwarded once the window has passed, or ahead of the
+/// result. Messages other than output are never dropped.
+#[derive(Debug)]
+struct OutputRateLimiter {
+    limit: Option<u32>,
+    window_start: Option<time::Instant>,
+    forwarded: u32,
+    suppressed: u64,
+    last_suppressed: Option<OutputStream>,
+}
+
+impl OutputRateLimiter {
+    fn new(limit: Option<u32>) -> Self {
+        Self {
+            limit,
+            window_start: None,
+            forwarded: 0,
+            suppressed: 0,
+            last_suppressed: None,
+        }
+    }
+
+    /// Returns the messages to forward for a message received at `now`, in order.
+    fn forward<Success>(
+        &mut self,
+        msg: Message<Success>,
+        now: time::Instant,
+    ) -> Vec<Message<Success>> {
+        let Some(limit) = self.limit else {
+            return vec![msg];
+        };
+
+        let output = match msg {
+            Message::OutputStream(output) => output,
+            other => return self.take_marker().into_iter().chain([other]).collect(),
+        };
+
+        let mut forward = Vec::new();
+        if self.window_start.map_or(true, |start| {
+            now.duration_since(start) >= OUTPUT_RATE_LIMIT_WINDOW
+        }) {
+            self.window_start = Some(now);
+            self.forwarded = 0;
+            forward.extend(self.take_marker());
+        }
+
+        if self.forwarded < limit {
+            self.forwarded += 1;
+            forward.push(Message::OutputStream(output));
+        } else {
+            self.suppressed += 1;
+            self.last_suppressed = Some(output);
+        }
+
+        forward
+    }
+
+    /// Takes a synthetic output line reporting the output suppressed since the last one, if any.
+    fn take_marker<Success>(&mut self) -> Option<Message<Success>> {
+        let suppressed = std::mem::take(&mut self.suppressed);
+        self.last_suppressed.take().map(|output| {
+            Message::OutputStream(OutputStream {
+                stream: output.stream,
+                execution_id: output.execution_id,
+                level: "warn".to_owned(),
+                group: output.group,
+                message: format!("[{suppressed} lines suppressed]"),
+                fields: None,
+                timestamp: crate::timestamp(),
+            })
+        })
+    }
+}
+
+/// Waits for the next tick of an optional interval, never completing when there is none.
+async fn tick(interval: &mut Option<time::Interval>) {
+    match interval.as_mut() {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
 // TODO: implement shutdown oneshot
 async fn handle_stderr(
     stderr: FramedRead<ChildStderr, BytesLinesCodec>,
-    sensitive_strings: Arc<SensitiveStrings>,
+    sensitive_strings: Arc<RwLock<SensitiveStrings>>,
 ) {
     async fn handle_stderr_fallible(
         mut stderr: FramedRead<ChildStderr, BytesLinesCodec>,
-        sensitive_strings: Arc<SensitiveStrings>,
+        sensitive_strings: Arc<RwLock<SensitiveStrings>>,
     ) -> Result<()> {
         while let Some(line) = stderr.next().await {
             let line = line.map_err(ExecutionError::ChildRecvIO)?;
             let line = String::from_utf8(line.to_vec())?;
-            let line = sensitive_strings.redact(line.as_ref());
+            let line = sensitive_strings.read().await.redact(line.as_ref());
 
             eprintln!("{line}");
         }
@@ -266,37 +940,84 @@ where
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
     pub async fn process(mut self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
-        tokio::spawn(handle_stderr(self.stderr, self.sensitive_strings.clone()));
+        tokio::spawn(handle_stderr(
+            self.stderr,
+            Arc::new(RwLock::new(SensitiveStrings::clone(
+                &self.sensitive_strings,
+            ))),
+        ));
 
         let mut stream = self
             .stdout
-            .map(|ls_result| match ls_result {
-                Ok(ls_msg) => match ls_msg {
-                    LangServerMessage::Output(mut output) => {
-                        Self::filter_output(&mut output, &self.sensitive_strings)?;
-                        Ok(Message::OutputStream(output.into()))
-                    }
-                    LangServerMessage::Result(mut result) => {
-                        Self::filter_result(&mut result, &self.sensitive_strings)?;
-                        Ok(Message::Result(result.into()))
-                    }
-                },
-                Err(err) => Err(ExecutionError::ChildRecvIO(err)),
-            })
-            .map(|msg_result: Result<_>| match msg_result {
-                Ok(msg) => match msg
-                    .serialize_to_string()
-                    .map_err(ExecutionError::JSONSerialize)
-                {
-                    Ok(json_str) => Ok(WebSocketMessage::Text(json_str)),
-                    Err(err) => Err(err),
-                },
-                Err(err) => Err(err),
+            .map(|ls_result| -> Result<(bool, Message<Success>)> {
+                match ls_result {
+                    Ok(ls_msg) => match ls_msg {
+                        LangServerMessage::Output(mut output) => {
+                            Self::filter_output(&mut output, &self.sensitive_strings)?;
+                            Ok((false, Message::OutputStream(output.into())))
+                        }
+                        LangServerMessage::Result(mut result) => {
+                            let redacted_paths = Self::filter_result(
+                                &mut result,
+                                &self.sensitive_strings,
+                                self.redaction_preview,
+                            )?;
+                            for json_pointer in redacted_paths {
+                                warn!(
+                                    json_pointer = %json_pointer,
+                                    "redacted sensitive value from function result",
+                                );
+                            }
+                            Ok((true, Message::Result(result.into())))
+                        }
+                    },
+                    Err(err) => Err(ExecutionError::ChildRecvIO(err)),
+                }
             });
 
+        let keepalive_interval = self.keepalive_interval;
+        let heartbeat_interval = self.heartbeat_interval;
+        let mut output_limiter = OutputRateLimiter::new(self.output_rate_limit);
         let receive_loop = async {
-            while let Some(msg) = stream.try_next().await? {
-                ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+            let started = time::Instant::now();
+            // Pings and heartbeats stop once the result arrives (and with this future, should it
+            // time out)
+            let mut keepalive =
+                keepalive_interval.map(|period| time::interval_at(started + period, period));
+            let mut heartbeat =
+                heartbeat_interval.map(|period| time::interval_at(started + period, period));
+
+            loop {
+                let next = tokio::select! {
+                    next = stream.try_next() => next?,
+                    _ = tick(&mut keepalive) => {
+                        trace!("sending execution keepalive websocket ping");
+                        ws.send(WebSocketMessage::Ping(vec![]))
+                            .await
+                            .map_err(ExecutionError::WSSendIO)?;
+                        continue;
+                    }
+                    _ = tick(&mut heartbeat) => {
+                        trace!("sending execution heartbeat message");
+                        let msg = serialize_ws_message(
+                            Message::<Success>::heartbeat(started.elapsed()),
+                            Message::serialize_to_string,
+                        )?;
+                        ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+                        continue;
+                    }
+                };
+                let Some((is_result, msg)) = next else {
+                    break;
+                };
+                if is_result {
+                    keepalive = None;
+                    heartbeat = None;
+                }
+                for msg in output_limiter.forward(msg, time::Instant::now()) {
+                    let ws_msg = serialize_ws_message(msg, Message::serialize_to_string)?;
+                    ws.send(ws_msg).await.map_err(ExecutionError::WSSendIO)?;
+                }
             }
 
             Result::<_>::Ok(())
@@ -306,8 +1027,12 @@ where
             Ok(execution) => execution?,
             Err(err) => {
                 // Exceeded timeout, shutdown child process
-                process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None)
-                    .await?;
+                process::child_shutdown(
+                    &mut self.child,
+                    Some(process::Signal::SIGTERM),
+                    Some(self.term_grace),
+                )
+                .await?;
                 drop(self.child);
 
                 error!(?err, "shutdown child process due to timeout");
@@ -319,6 +1044,8 @@ where
 
         Ok(ExecutionClosing {
             child: self.child,
+            _spawn_permit: self.spawn_permit,
+            term_grace: self.term_grace,
             success_marker: PhantomData,
         })
     }
@@ -331,37 +1058,167 @@ where
             output.message = sensitive_strings.redact(&output.message);
         }
 
+        if let Some(fields) = output.fields.as_mut() {
+            redact_fields(fields, sensitive_strings);
+        }
+
         Ok(())
     }
 
+    /// Redacts all sensitive strings in the result.
+    ///
+    /// When `track_paths` is set, returns the JSON pointer paths of all redacted values.
     fn filter_result(
         result: &mut LangServerResult<LangServerSuccess>,
         sensitive_strings: &SensitiveStrings,
-    ) -> Result<()> {
+        track_paths: bool,
+    ) -> Result<Vec<String>> {
         let mut value = serde_json::to_value(&result).map_err(ExecutionError::JSONSerialize)?;
-
-        let mut work_queue = vec![&mut value];
-        while let Some(work) = work_queue.pop() {
-            match work {
-                Value::Array(values) => work_queue.extend(values),
-                Value::Object(object) => object.values_mut().for_each(|v| work_queue.push(v)),
-                Value::String(string) if sensitive_strings.has_sensitive(string) => {
-                    *string = sensitive_strings.redact(string);
-                }
-                Value::String(_) | Value::Null | Value::Number(_) | Value::Bool(_) => {}
-            }
-        }
+        let redacted_paths = if track_paths {
+            sensitive_strings.redact_value_with_paths(&mut value)
+        } else {
+            sensitive_strings.redact_value(&mut value);
+            Vec::new()
+        };
 
         let mut filtered_result: LangServerResult<LangServerSuccess> =
             serde_json::from_value(value).map_err(ExecutionError::JSONDeserialize)?;
         std::mem::swap(result, &mut filtered_result);
-        Ok(())
+        Ok(redacted_paths)
+    }
+}
+
+/// Resource usage of a completed child process.
+#[derive(Clone, Copy, Debug)]
+pub struct ResourceUsage {
+    /// Maximum resident set size, in kilobytes.
+    pub max_rss_kb: i64,
+    /// Time spent executing in user mode.
+    pub user_cpu_time: Duration,
+    /// Time spent executing in kernel mode.
+    pub system_cpu_time: Duration,
+}
+
+impl From<&libc::rusage> for ResourceUsage {
+    fn from(usage: &libc::rusage) -> Self {
+        fn duration(time: libc::timeval) -> Duration {
+            Duration::from_secs(u64::try_from(time.tv_sec).unwrap_or(0))
+                + Duration::from_micros(u64::try_from(time.tv_usec).unwrap_or(0))
+        }
+
+        Self {
+            max_rss_kb: i64::from(usage.ru_maxrss),
+            user_cpu_time: duration(usage.ru_utime),
+            system_cpu_time: duration(usage.ru_stime),
+        }
+    }
+}
+
+/// Blocks until the child process with the given pid exits, returning its own resource usage.
+///
+/// The child is left unreaped (i.e. `WNOWAIT`), so that it is still reaped through its [`Child`]
+/// and tokio never waits on a pid which may have been reused.
+#[cfg(target_os = "linux")]
+fn wait_for_exit(pid: libc::pid_t) -> io::Result<ResourceUsage> {
+    // SAFETY: `siginfo_t` and `rusage` only contain integers (and padding), for which all zeroes
+    // is a valid value
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both pointers are valid for writes for the duration of the call. The system call
+        // is made directly as only it, and not the libc wrapper, reports the resource usage.
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_waitid,
+                libc::P_PID,
+                pid,
+                &mut info as *mut libc::siginfo_t,
+                libc::WEXITED | libc::WNOWAIT,
+                &mut usage as *mut libc::rusage,
+            )
+        };
+        if result == -1 {
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        } else {
+            return Ok(ResourceUsage::from(&usage));
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn wait_for_exit(_pid: libc::pid_t) -> io::Result<ResourceUsage> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "child resource usage is only available on linux",
+    ))
+}
+
+/// Shuts down and reaps a child process, returning the child's own resource usage (or `None` if
+/// it's unavailable).
+///
+/// If the child has not exited within `term_grace`, it is sent a `SIGKILL`. The resource usage is
+/// read once the child has exited but before it is reaped, which still happens through [`Child`].
+async fn child_shutdown_with_resource_usage(
+    child: &mut Child,
+    signal: Option<process::Signal>,
+    term_grace: Duration,
+) -> (
+    std::result::Result<ExitStatus, ShutdownError>,
+    Option<ResourceUsage>,
+) {
+    // Without a pid the child has already been reaped, along with its resource usage
+    let Some(pid) = child.id() else {
+        return (
+            process::child_shutdown(child, signal, Some(term_grace)).await,
+            None,
+        );
+    };
+    let pid = match i32::try_from(pid) {
+        Ok(pid) => pid,
+        Err(err) => return (Err(err.into()), None),
+    };
+    // The child can't have been reaped yet, so its pid can't have been reused
+    if let Some(signal) = signal {
+        trace!("sending {} to child process {}", signal, pid);
+        if let Err(err) = kill(Pid::from_raw(pid), signal) {
+            return (Err(err.into()), None);
+        }
     }
+
+    let mut exited = tokio::task::spawn_blocking(move || wait_for_exit(pid));
+    let exited = match time::timeout(term_grace, &mut exited).await {
+        Ok(exited) => exited,
+        Err(_elapsed) => {
+            if let Err(err) = child.start_kill() {
+                return (Err(ShutdownError::StartKill(err)), None);
+            }
+            exited.await
+        }
+    };
+    let resource_usage = match exited.map_err(io::Error::other).and_then(|exited| exited) {
+        Ok(resource_usage) => Some(resource_usage),
+        Err(err) => {
+            warn!(error = ?err, "failed to read resource usage of child process {}", pid);
+            None
+        }
+    };
+
+    // Once the child has exited this only reaps it, otherwise it's given the usual grace again
+    (
+        process::child_shutdown(child, None, Some(term_grace)).await,
+        resource_usage,
+    )
 }
 
 #[derive(Debug)]
 pub struct ExecutionClosing<Success> {
     child: Child,
+    /// Released once the execution is finished, i.e. when this is dropped.
+    _spawn_permit: Option<OwnedSemaphorePermit>,
+    term_grace: Duration,
     success_marker: PhantomData<Success>,
 }
 
@@ -369,18 +1226,23 @@ impl<Success> ExecutionClosing<Success>
 where
     Success: Serialize,
 {
-    pub async fn finish(mut self, mut ws: WebSocket) -> Result<()> {
+    /// Finishes the protocol and shuts down the child process, returning the child's resource
+    /// usage when it could be determined.
+    pub async fn finish(mut self, mut ws: WebSocket) -> Result<Option<ResourceUsage>> {
         let finished = Self::ws_send_finish(&mut ws).await;
         let closed = Self::ws_close(ws).await;
-        let shutdown =
-            process::child_shutdown(&mut self.child, Some(process::Signal::SIGTERM), None)
-                .await
-                .map_err(Into::into);
+        let (shutdown, resource_usage) = child_shutdown_with_resource_usage(
+            &mut self.child,
+            Some(process::Signal::SIGTERM),
+            self.term_grace,
+        )
+        .await;
+        let shutdown = shutdown.map_err(Into::into);
         drop(self.child);
 
         match (finished, closed, shutdown) {
             // Everything succeeds, great!
-            (Ok(_), Ok(_), Ok(_)) => Ok(()),
+            (Ok(_), Ok(_), Ok(_)) => Ok(resource_usage),
 
             // One of the steps failed, return its error
             (Ok(_), Ok(_), Err(err)) | (Ok(_), Err(err), Ok(_)) | (Err(err), Ok(_), Ok(_)) => {
@@ -436,6 +1298,30 @@ pub enum LangServerMessage<Success> {
     Result(LangServerResult<Success>),
 }
 
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "protocol", rename_all = "camelCase")]
+enum LangServerHandshakeMessage {
+    Handshake(LangServerHandshake),
+}
+
+/// The protocol a lang server declares it supports before processing a request.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangServerHandshake {
+    version: u32,
+    #[serde(default)]
+    capabilities: Vec<String>,
+}
+
+impl LangServerHandshake {
+    /// Returns whether the lang server declared the given capability.
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|declared| declared == capability)
+    }
+}
+
 #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LangServerOutput {
@@ -444,6 +1330,8 @@ pub struct LangServerOutput {
     level: String,
     group: Option<String>,
     message: String,
+    #[serde(default)]
+    fields: Option<serde_json::Map<String, Value>>,
 }
 
 impl From<LangServerOutput> for OutputStream {
@@ -454,6 +1342,7 @@ impl From<LangServerOutput> for OutputStream {
             level: value.level,
             group: value.group,
             message: value.message,
+            fields: value.fields,
             timestamp: crate::timestamp(),
         }
     }
@@ -501,3 +1390,385 @@ struct LangServerFailureError {
     kind: FunctionResultFailureErrorKind,
     message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output_line(n: usize) -> Message<()> {
+        Message::OutputStream(OutputStream {
+            stream: "output".to_owned(),
+            execution_id: "ex-1".to_owned(),
+            level: "info".to_owned(),
+            group: None,
+            message: format!("line {n}"),
+            fields: None,
+            timestamp: 0,
+        })
+    }
+
+    fn output_messages(forwarded: &[Message<()>]) -> Vec<&str> {
+        forwarded
+            .iter()
+            .filter_map(|msg| match msg {
+                Message::OutputStream(output) => Some(output.message.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn output_rate_limiter_caps_output_and_reports_suppressed_lines() {
+        let mut limiter = OutputRateLimiter::new(Some(3));
+        let start = time::Instant::now();
+        let next_window = start + OUTPUT_RATE_LIMIT_WINDOW;
+
+        let forwarded: Vec<_> = (0..1000)
+            .flat_map(|n| limiter.forward(output_line(n), start))
+            .collect();
+        assert_eq!(
+            vec!["line 0", "line 1", "line 2"],
+            output_messages(&forwarded)
+        );
+
+        // The next window starts by reporting the output suppressed in the last one
+        let forwarded = limiter.forward(output_line(1000), next_window);
+        assert_eq!(
+            vec!["[997 lines suppressed]", "line 1000"],
+            output_messages(&forwarded)
+        );
+
+        // A result is never dropped and is preceded by any pending suppression marker
+        let forwarded: Vec<_> = (1001..1010)
+            .flat_map(|n| limiter.forward(output_line(n), next_window))
+            .collect();
+        assert_eq!(vec!["line 1001", "line 1002"], output_messages(&forwarded));
+        let forwarded = limiter.forward(
+            Message::Result(FunctionResult::Failure(
+                FunctionResultFailure::new_for_veritech_server_error("ex-1", "boom", 0),
+            )),
+            next_window,
+        );
+        assert_eq!(vec!["[7 lines suppressed]"], output_messages(&forwarded));
+        assert!(matches!(forwarded.last(), Some(Message::Result(_))));
+    }
+
+    #[test]
+    fn output_rate_limiter_is_unlimited_by_default() {
+        let mut limiter = OutputRateLimiter::new(None);
+        let now = time::Instant::now();
+
+        let forwarded: Vec<_> = (0..1000)
+            .flat_map(|n| limiter.forward(output_line(n), now))
+            .collect();
+
+        assert_eq!(1000, forwarded.len());
+    }
+
+    #[test]
+    fn execution_errors_are_classified_as_retryable_or_terminal() {
+        let io_error = || io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe");
+        let json_error = || {
+            serde_json::from_str::<serde_json::Value>("{").expect_err("invalid json should fail")
+        };
+
+        assert!(ExecutionError::AtCapacity(Duration::from_secs(1)).is_retryable());
+        assert!(ExecutionError::ChildExitedEarly.is_retryable());
+        assert!(ExecutionError::ChildSendIO(io_error()).is_retryable());
+        assert!(ExecutionError::ChildSpawn(io_error(), PathBuf::from("lang-js")).is_retryable());
+        assert!(ExecutionError::ChildTimeout(Duration::from_secs(1)).is_retryable());
+        assert!(ExecutionError::RequestIdleTimeout(Duration::from_secs(1)).is_retryable());
+        assert!(ExecutionError::WSRecvClosed.is_retryable());
+
+        assert!(!ExecutionError::JSONDeserialize(json_error()).is_retryable());
+        assert!(!ExecutionError::JSONSerialize(json_error()).is_retryable());
+        assert!(!ExecutionError::ProtocolVersionMismatch {
+            server: 1,
+            lang_server: 2
+        }
+        .is_retryable());
+        assert!(!ExecutionError::MissingSensitiveStrings("action".to_owned()).is_retryable());
+        assert!(!ExecutionError::WorkingDirNotFound(PathBuf::from("/nope")).is_retryable());
+        assert!(!ExecutionError::LangServerNotFound(PathBuf::from("/nope")).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn check_lang_server_path_reports_missing_lang_server() {
+        let result = check_lang_server_path(Path::new("/nonexistent/bin/lang-js")).await;
+
+        assert!(matches!(
+            result,
+            Err(ExecutionError::LangServerNotFound(path))
+                if path == Path::new("/nonexistent/bin/lang-js")
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn check_lang_server_path_reports_non_executable_lang_server() {
+        check_lang_server_path(Path::new("/bin/sh"))
+            .await
+            .expect("an executable lang server should be accepted");
+        // Bare program names are resolved from the `PATH` when spawning
+        check_lang_server_path(Path::new("lang-js"))
+            .await
+            .expect("a bare program name should be left to the spawn");
+
+        let result = check_lang_server_path(Path::new("/etc/passwd")).await;
+        assert!(matches!(
+            result,
+            Err(ExecutionError::LangServerNotExecutable(path, mode))
+                if path == Path::new("/etc/passwd") && mode & 0o111 == 0
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_handshake_accepts_compatible_version() {
+        let line = br#"{"protocol":"handshake","version":1,"capabilities":["multiRequest"]}
+"#;
+        let mut stdout = FramedRead::new(&line[..], ChildLinesCodec::plain());
+
+        let handshake = read_handshake(&mut stdout, Duration::from_secs(1))
+            .await
+            .expect("compatible handshake should be accepted");
+
+        assert_eq!(
+            Some(LangServerHandshake {
+                version: 1,
+                capabilities: vec!["multiRequest".to_owned()],
+            }),
+            handshake
+        );
+    }
+
+    #[tokio::test]
+    async fn read_handshake_rejects_incompatible_version() {
+        let line = br#"{"protocol":"handshake","version":2}
+"#;
+        let mut stdout = FramedRead::new(&line[..], ChildLinesCodec::plain());
+
+        let result = read_handshake(&mut stdout, Duration::from_secs(1)).await;
+
+        assert!(matches!(
+            result,
+            Err(ExecutionError::ProtocolVersionMismatch {
+                server: CURRENT_PROTOCOL_VERSION,
+                lang_server: 2,
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_handshake_falls_back_when_none_is_sent() {
+        // Keep the writer open so the read waits rather than seeing the end of the stream
+        let (_writer, reader) = tokio::io::duplex(64);
+        let mut stdout = FramedRead::new(reader, ChildLinesCodec::plain());
+
+        let handshake = read_handshake(&mut stdout, Duration::from_millis(10))
+            .await
+            .expect("missing handshake should be treated as compatible");
+
+        assert_eq!(None, handshake);
+    }
+
+    #[test]
+    fn ensure_sensitive_strings_rejects_empty_set_when_required() {
+        let empty = SensitiveStrings::default();
+
+        let err = ensure_sensitive_strings("management", true, &empty)
+            .expect_err("empty sensitive strings should be rejected");
+        assert!(matches!(
+            err,
+            ExecutionError::MissingSensitiveStrings(kind) if kind == "management"
+        ));
+
+        ensure_sensitive_strings("resolverfunction", false, &empty)
+            .expect("kinds not requiring sensitive strings are unaffected");
+
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("hunter2".to_owned());
+        ensure_sensitive_strings("management", true, &sensitive_strings)
+            .expect("non-empty sensitive strings should be accepted");
+    }
+
+    #[test]
+    fn spawn_child_rejects_missing_working_dir() {
+        use cyclone_core::{ResolverFunctionRequest, ResolverFunctionResultSuccess};
+
+        use crate::result::LangServerResolverFunctionResultSuccess;
+
+        let execution: Execution<
+            ResolverFunctionRequest,
+            LangServerResolverFunctionResultSuccess,
+            ResolverFunctionResultSuccess,
+        > = new(
+            "sh",
+            false,
+            None,
+            None,
+            "resolverfunction".to_owned(),
+            vec![],
+        )
+        .with_working_dir(Some(PathBuf::from("/nonexistent/working/dir")));
+
+        let err = execution
+            .spawn_child(false)
+            .expect_err("a missing working directory should be rejected");
+        assert!(matches!(
+            err,
+            ExecutionError::WorkingDirNotFound(dir) if dir == Path::new("/nonexistent/working/dir")
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn resource_usage_is_populated_for_completed_child() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("i=0; while [ $i -lt 200000 ]; do i=$((i + 1)); done")
+            .spawn()
+            .expect("failed to spawn child");
+
+        let (shutdown, resource_usage) =
+            child_shutdown_with_resource_usage(&mut child, None, Duration::from_secs(30)).await;
+
+        assert!(shutdown.expect("failed to shutdown child").success());
+        let resource_usage = resource_usage.expect("resource usage should be available");
+        assert!(resource_usage.max_rss_kb > 0);
+        assert!(resource_usage.user_cpu_time + resource_usage.system_cpu_time > Duration::ZERO);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn resource_usage_is_not_shared_between_children() {
+        let mut busy = Command::new("sh")
+            .arg("-c")
+            .arg("i=0; while [ $i -lt 200000 ]; do i=$((i + 1)); done")
+            .spawn()
+            .expect("failed to spawn child");
+        let mut idle = Command::new("sh")
+            .arg("-c")
+            .arg("sleep 1")
+            .spawn()
+            .expect("failed to spawn child");
+
+        let (_, busy_usage) =
+            child_shutdown_with_resource_usage(&mut busy, None, Duration::from_secs(30)).await;
+        let (_, idle_usage) =
+            child_shutdown_with_resource_usage(&mut idle, None, Duration::from_secs(30)).await;
+
+        let cpu_time = |usage: Option<ResourceUsage>| {
+            let usage = usage.expect("resource usage should be available");
+            usage.user_cpu_time + usage.system_cpu_time
+        };
+        assert!(cpu_time(idle_usage) < cpu_time(busy_usage));
+    }
+
+    #[test]
+    fn serialize_ws_message_skips_unserializable_output() {
+        fn output(message: &str) -> Message<String> {
+            Message::OutputStream(OutputStream {
+                stream: "stdout".to_owned(),
+                execution_id: "1234".to_owned(),
+                level: "info".to_owned(),
+                group: None,
+                message: message.to_owned(),
+                fields: None,
+                timestamp: 0,
+            })
+        }
+        let serialize = |msg: &Message<String>| match msg {
+            Message::OutputStream(output) if output.message == "bad" => {
+                Err(<serde_json::Error as serde::ser::Error>::custom("nope"))
+            }
+            msg => msg.serialize_to_string(),
+        };
+
+        let msgs = vec![
+            output("bad"),
+            output("good"),
+            Message::Result(FunctionResult::Success("done".to_owned())),
+        ];
+        let sent: Vec<String> = msgs
+            .into_iter()
+            .map(|msg| match serialize_ws_message(msg, serialize) {
+                Ok(WebSocketMessage::Text(json_str)) => json_str,
+                unexpected => panic!("unexpected serialization: {unexpected:?}"),
+            })
+            .collect();
+
+        assert_eq!(3, sent.len());
+        assert!(sent[0].contains(SKIPPED_OUTPUT_MESSAGE));
+        assert!(sent[1].contains("good"));
+        assert!(matches!(
+            Message::<String>::deserialize_from_str(&sent[2]),
+            Ok(Message::Result(FunctionResult::Success(result))) if result == "done"
+        ));
+
+        let result: Message<String> = Message::Result(FunctionResult::Success("done".to_owned()));
+        let failing =
+            |_: &Message<String>| Err(<serde_json::Error as serde::ser::Error>::custom("nope"));
+        assert!(matches!(
+            serialize_ws_message(result, failing),
+            Err(ExecutionError::JSONSerialize(_))
+        ));
+    }
+
+    #[test]
+    fn heartbeat_message_carries_elapsed_millis() {
+        let msg = serialize_ws_message(
+            Message::<String>::heartbeat(Duration::from_millis(1500)),
+            Message::serialize_to_string,
+        );
+
+        let json_str = match msg {
+            Ok(WebSocketMessage::Text(json_str)) => json_str,
+            unexpected => panic!("unexpected serialization: {unexpected:?}"),
+        };
+        assert!(matches!(
+            Message::<String>::deserialize_from_str(&json_str),
+            Ok(Message::Heartbeat { elapsed_ms: 1500 })
+        ));
+    }
+
+    #[test]
+    fn lang_server_output_fields_are_optional() {
+        let output: LangServerOutput = serde_json::from_str(
+            r#"{"executionId":"1234","stream":"stdout","level":"info","group":null,"message":"hi"}"#,
+        )
+        .expect("failed to deserialize output without fields");
+        assert_eq!(None, OutputStream::from(output).fields);
+
+        let output: LangServerOutput = serde_json::from_str(
+            r#"{"executionId":"1234","stream":"stdout","level":"info","group":null,"message":"hi","fields":{"region":"us-east-1"}}"#,
+        )
+        .expect("failed to deserialize output with fields");
+        assert_eq!(
+            Some(&serde_json::json!("us-east-1")),
+            OutputStream::from(output)
+                .fields
+                .as_ref()
+                .and_then(|fields| fields.get("region"))
+        );
+    }
+
+    #[test]
+    fn redact_fields_redacts_nested_string_values() {
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("hunter2".to_owned());
+        let mut fields = match serde_json::json!({
+            "password": "hunter2",
+            "nested": { "list": ["ok", "the hunter2 secret"], "count": 2 },
+        }) {
+            Value::Object(fields) => fields,
+            _ => unreachable!("literal is an object"),
+        };
+
+        redact_fields(&mut fields, &sensitive_strings);
+
+        let redacted = Value::Object(fields).to_string();
+        assert!(!redacted.contains("hunter2"));
+        assert!(redacted.contains("\"ok\""));
+        assert!(redacted.contains("\"count\":2"));
+    }
+}
@@ -1,14 +1,13 @@
 use std::{
     fmt,
     marker::{PhantomData, Unpin},
-    path::PathBuf,
     sync::Arc,
 };
 
 use axum::{
     extract::{
         ws::{self, WebSocket},
-        Extension, State, WebSocketUpgrade,
+        Extension, Query, State, WebSocketUpgrade,
     },
     response::IntoResponse,
 };
@@ -19,21 +18,18 @@ use cyclone_core::{
     SchemaVariantDefinitionResultSuccess, ValidationRequest, ValidationResultSuccess,
 };
 use hyper::StatusCode;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use telemetry::prelude::*;
 use telemetry_http::ParentSpan;
 
 use super::extract::LimitRequestGuard;
 use crate::{
-    execution::{self, Execution},
+    execution::Execution,
     result::{
         LangServerActionRunResultSuccess, LangServerResolverFunctionResultSuccess,
         LangServerValidationResultSuccess,
     },
-    state::{
-        LangServerFunctionTimeout, LangServerPath, LangServerProcessTimeout, TelemetryLevel,
-        WatchKeepalive,
-    },
+    state::{ExecutionSettings, TelemetryLevel, WatchKeepalive},
     watch,
 };
 
@@ -100,14 +96,12 @@ pub async fn ws_execute_ping(
 
 pub async fn ws_execute_resolver(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
-    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
-    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_settings): State<ExecutionSettings>,
+    Query(execute_params): Query<ExecuteParams>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ResolverFunctionRequest> = PhantomData;
@@ -115,10 +109,9 @@ pub async fn ws_execute_resolver(
         let success: PhantomData<ResolverFunctionResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            execution_settings,
             telemetry_level,
-            lang_server_function_timeout.inner(),
-            lang_server_process_timeout.inner(),
+            execute_params.multi_request,
             limit_request_guard,
             "resolverfunction".to_owned(),
             request,
@@ -131,14 +124,12 @@ pub async fn ws_execute_resolver(
 
 pub async fn ws_execute_validation(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
-    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
-    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_settings): State<ExecutionSettings>,
+    Query(execute_params): Query<ExecuteParams>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ValidationRequest> = PhantomData;
@@ -146,10 +137,9 @@ pub async fn ws_execute_validation(
         let success: PhantomData<ValidationResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            execution_settings,
             telemetry_level,
-            lang_server_function_timeout.inner(),
-            lang_server_process_timeout.inner(),
+            execute_params.multi_request,
             limit_request_guard,
             "validation".to_owned(),
             request,
@@ -162,14 +152,12 @@ pub async fn ws_execute_validation(
 
 pub async fn ws_execute_action_run(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
-    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
-    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_settings): State<ExecutionSettings>,
+    Query(execute_params): Query<ExecuteParams>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ActionRunRequest> = PhantomData;
@@ -177,10 +165,9 @@ pub async fn ws_execute_action_run(
         let success: PhantomData<ActionRunResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            execution_settings,
             telemetry_level,
-            lang_server_function_timeout.inner(),
-            lang_server_process_timeout.inner(),
+            execute_params.multi_request,
             limit_request_guard,
             "actionRun".to_owned(),
             request,
@@ -193,14 +180,12 @@ pub async fn ws_execute_action_run(
 
 pub async fn ws_execute_schema_variant_definition(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
-    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
-    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_settings): State<ExecutionSettings>,
+    Query(execute_params): Query<ExecuteParams>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<SchemaVariantDefinitionRequest> = PhantomData;
@@ -208,10 +193,9 @@ pub async fn ws_execute_schema_variant_definition(
         let success: PhantomData<SchemaVariantDefinitionResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            execution_settings,
             telemetry_level,
-            lang_server_function_timeout.inner(),
-            lang_server_process_timeout.inner(),
+            execute_params.multi_request,
             limit_request_guard,
             "schemaVariantDefinition".to_owned(),
             request,
@@ -224,14 +208,12 @@ pub async fn ws_execute_schema_variant_definition(
 
 pub async fn ws_execute_management(
     wsu: WebSocketUpgrade,
-    State(lang_server_path): State<LangServerPath>,
     State(telemetry_level): State<TelemetryLevel>,
-    State(lang_server_function_timeout): State<LangServerFunctionTimeout>,
-    State(lang_server_process_timeout): State<LangServerProcessTimeout>,
+    State(execution_settings): State<ExecutionSettings>,
+    Query(execute_params): Query<ExecuteParams>,
     limit_request_guard: LimitRequestGuard,
     Extension(request_span): Extension<ParentSpan>,
 ) -> impl IntoResponse {
-    let lang_server_path = lang_server_path.as_path().to_path_buf();
     let telemetry_level = telemetry_level.is_debug_or_lower().await;
     wsu.on_upgrade(move |socket| {
         let request: PhantomData<ManagementRequest> = PhantomData;
@@ -239,10 +221,9 @@ pub async fn ws_execute_management(
         let success: PhantomData<ManagementResultSuccess> = PhantomData;
         handle_socket(
             socket,
-            lang_server_path,
+            execution_settings,
             telemetry_level,
-            lang_server_function_timeout.inner(),
-            lang_server_process_timeout.inner(),
+            execute_params.multi_request,
             limit_request_guard,
             "management".to_owned(),
             request,
@@ -253,6 +234,16 @@ pub async fn ws_execute_management(
     })
 }
 
+/// Query parameters accepted by the function execution endpoints.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteParams {
+    /// Serves a sequence of requests with a single lang server child process until the client
+    /// closes the websocket (see [`Execution::process_many`]).
+    #[serde(default)]
+    multi_request: bool,
+}
+
 #[instrument(
     name = "web_socket.handle_socket",
     parent = &request_span,
@@ -263,10 +254,9 @@ pub async fn ws_execute_management(
 #[allow(clippy::too_many_arguments)]
 async fn handle_socket<Request, LangServerSuccess, Success>(
     mut socket: WebSocket,
-    lang_server_path: PathBuf,
+    execution_settings: ExecutionSettings,
     lang_server_debugging: bool,
-    lang_server_function_timeout: Option<usize>,
-    lang_server_process_timeout: Option<u64>,
+    multi_request: bool,
     _limit_request_guard: LimitRequestGuard,
     sub_command: String,
     _request_marker: PhantomData<Request>,
@@ -279,17 +269,43 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
     let proto = {
-        let execution: Execution<Request, LangServerSuccess, Success> = execution::new(
-            lang_server_path,
-            lang_server_debugging,
-            lang_server_function_timeout,
-            lang_server_process_timeout,
-            sub_command,
-        );
+        let execution: Execution<Request, LangServerSuccess, Success> =
+            execution_settings.execution(&sub_command, lang_server_debugging);
+        if multi_request {
+            match execution.process_many(&mut socket).await {
+                Ok(()) => request_span.record_ok(),
+                Err(err) => {
+                    warn!(
+                        error = ?err,
+                        retryable = err.is_retryable(),
+                        "failed to process multi-request protocol",
+                    );
+                    request_span.record_err(&err);
+                    if let Err(err) = fail_to_process(
+                        socket,
+                        format!("failed to process multi-request protocol: {err:?}"),
+                        success_marker,
+                    )
+                    .await
+                    {
+                        warn!(
+                            error = ?err,
+                            kind = std::any::type_name::<Request>(),
+                            "failed to fail execute function",
+                        );
+                    };
+                }
+            }
+            return;
+        }
         match execution.start(&mut socket).await {
             Ok(started) => started,
             Err(err) => {
-                warn!(error = ?err, "failed to start protocol");
+                warn!(
+                    error = ?err,
+                    retryable = err.is_retryable(),
+                    "failed to start protocol",
+                );
                 request_span.record_err(&err);
                 if let Err(err) =
                     fail_to_process(socket, "failed to start protocol", success_marker).await
@@ -307,7 +323,11 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     let proto = match proto.process(&mut socket).await {
         Ok(processed) => processed,
         Err(err) => {
-            warn!(error = ?err, "failed to process protocol");
+            warn!(
+                error = ?err,
+                retryable = err.is_retryable(),
+                "failed to process protocol",
+            );
             request_span.record_err(&err);
             if let Err(err) = fail_to_process(
                 socket,
@@ -325,10 +345,25 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
             return;
         }
     };
-    if let Err(err) = proto.finish(socket).await {
-        request_span.record_err(&err);
-        warn!(error = ?err, "failed to finish protocol");
-        return;
+    match proto.finish(socket).await {
+        Ok(Some(resource_usage)) => {
+            info!(
+                max_rss_kb = resource_usage.max_rss_kb,
+                user_cpu_time = ?resource_usage.user_cpu_time,
+                system_cpu_time = ?resource_usage.system_cpu_time,
+                "child process resource usage",
+            );
+        }
+        Ok(None) => {}
+        Err(err) => {
+            request_span.record_err(&err);
+            warn!(
+                error = ?err,
+                retryable = err.is_retryable(),
+                "failed to finish protocol",
+            );
+            return;
+        }
     }
 
     request_span.record_ok();
@@ -2,6 +2,7 @@ mod config;
 mod execution;
 mod extract;
 mod handlers;
+mod lines_codec;
 #[cfg(target_os = "linux")]
 pub mod process_gatherer;
 mod result;
@@ -17,6 +18,7 @@ mod watch;
 
 pub use axum::extract::ws::Message as WebSocketMessage;
 pub use config::{Config, ConfigBuilder, ConfigError, IncomingStream};
+pub use execution::ExecutionError;
 #[cfg(target_os = "linux")]
 pub use process_gatherer::init;
 pub use server::{Runnable, Server, ShutdownSource};
@@ -0,0 +1,176 @@
+//! Newline delimited framing for the messages exchanged with a lang server child process, with
+//! optional per-line gzip compression.
+
+use std::io::{self, Read, Write};
+
+use base64::{engine::general_purpose, Engine};
+use bytes::{Bytes, BytesMut};
+use bytes_lines_codec::BytesLinesCodec;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The handshake capability a lang server declares when it accepts gzip compressed lines.
+pub const GZIP_CAPABILITY: &str = "gzip";
+
+/// A codec for the lines written to a lang server's stdin and read from its stdout.
+///
+/// When compressed, every line carries a single message which is gzip compressed and then base64
+/// encoded, so that the compressed bytes never contain the newline delimiter. Compression uses the
+/// fastest level: large requests (such as those carrying big component views) are typically
+/// JSON which shrinks several times over even at that level, while the added CPU time per message
+/// stays small next to the time a lang server spends executing a function. Small messages, such
+/// as output lines, grow slightly from the gzip header and base64 encoding, which is why
+/// compression is only used when it has been enabled and the lang server has opted in.
+///
+/// A compressed line is rejected if it decompresses to more than the codec's maximum line length,
+/// the same limit which applies to plain lines.
+#[derive(Debug)]
+pub enum ChildLinesCodec {
+    Gzip(BytesLinesCodec),
+    Plain(BytesLinesCodec),
+}
+
+impl ChildLinesCodec {
+    pub fn plain() -> Self {
+        Self::Plain(BytesLinesCodec::new())
+    }
+
+    pub fn gzip() -> Self {
+        Self::Gzip(BytesLinesCodec::new())
+    }
+}
+
+impl Decoder for ChildLinesCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self {
+            Self::Gzip(lines) => {
+                let max_length = lines.max_length();
+                lines
+                    .decode(src)?
+                    .map(|line| decompress(line, max_length))
+                    .transpose()
+            }
+            Self::Plain(lines) => lines.decode(src),
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self {
+            Self::Gzip(lines) => {
+                let max_length = lines.max_length();
+                lines
+                    .decode_eof(src)?
+                    .map(|line| decompress(line, max_length))
+                    .transpose()
+            }
+            Self::Plain(lines) => lines.decode_eof(src),
+        }
+    }
+}
+
+impl Encoder<Bytes> for ChildLinesCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, data: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match self {
+            Self::Gzip(lines) => lines.encode(compress(&data)?, dst),
+            Self::Plain(lines) => lines.encode(data, dst),
+        }
+    }
+}
+
+fn compress(data: &[u8]) -> io::Result<Bytes> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(data)?;
+    let compressed = encoder.finish()?;
+
+    Ok(general_purpose::STANDARD.encode(compressed).into())
+}
+
+fn decompress(line: BytesMut, max_length: usize) -> io::Result<BytesMut> {
+    let compressed = general_purpose::STANDARD
+        .decode(&line)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut decompressed = Vec::new();
+    // Reading one byte past the limit tells a line which is exactly at the limit apart from one
+    // which exceeds it, without ever decompressing more than that
+    GzDecoder::new(compressed.as_slice())
+        .take(
+            u64::try_from(max_length)
+                .unwrap_or(u64::MAX)
+                .saturating_add(1),
+        )
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > max_length {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed line exceeds the maximum length of {max_length} bytes"),
+        ));
+    }
+
+    Ok(BytesMut::from(decompressed.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_lines_round_trip() {
+        let messages = [
+            Bytes::from_static(br#"{"protocol":"output","message":"hello"}"#),
+            Bytes::from(format!(r#"{{"value":"{}"}}"#, "x".repeat(64 * 1024))),
+        ];
+
+        let mut codec = ChildLinesCodec::gzip();
+        let mut buf = BytesMut::new();
+        for message in &messages {
+            codec
+                .encode(message.clone(), &mut buf)
+                .expect("failed to encode");
+        }
+        // every message is framed as a single line
+        assert_eq!(
+            messages.len(),
+            buf.iter().filter(|byte| **byte == b'\n').count()
+        );
+
+        for message in &messages {
+            let decoded = codec
+                .decode(&mut buf)
+                .expect("failed to decode")
+                .expect("no line decoded");
+            assert_eq!(message.as_ref(), decoded.as_ref());
+        }
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn gzip_lines_over_max_length_are_rejected() {
+        let mut buf = BytesMut::new();
+        ChildLinesCodec::gzip()
+            .encode(Bytes::from("x".repeat(64 * 1024)), &mut buf)
+            .expect("failed to encode");
+
+        // The compressed line is well under the limit, but decompresses to far more than it
+        let mut codec = ChildLinesCodec::Gzip(BytesLinesCodec::new_with_max_length(1024));
+        let err = codec
+            .decode(&mut buf)
+            .expect_err("an oversized decompressed line should be rejected");
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn plain_lines_are_not_compressed() {
+        let mut codec = ChildLinesCodec::plain();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(Bytes::from_static(b"{}"), &mut buf)
+            .expect("failed to encode");
+
+        assert_eq!(&b"{}\n"[..], buf.as_ref());
+    }
+}
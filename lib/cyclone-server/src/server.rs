@@ -16,8 +16,9 @@ use tokio::{
 };
 
 use crate::{
-    routes::routes, state::AppState, Config, IncomingStream, UdsIncomingStream,
-    UdsIncomingStreamError,
+    routes::routes,
+    state::{AppState, ExecutionSettings},
+    Config, IncomingStream, UdsIncomingStream, UdsIncomingStreamError,
 };
 
 #[cfg(target_os = "linux")]
@@ -214,12 +215,7 @@ fn build_service(
 ) -> Result<(IntoMakeService<Router>, oneshot::Receiver<()>)> {
     let (shutdown_tx, shutdown_rx) = mpsc::channel(4);
 
-    let state = AppState::new(
-        config.lang_server_path(),
-        telemetry_level,
-        config.lang_server_function_timeout(),
-        config.lang_server_process_timeout(),
-    );
+    let state = AppState::new(telemetry_level, ExecutionSettings::new(config));
 
     let routes = routes(config, state, shutdown_tx);
 
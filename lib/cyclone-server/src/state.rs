@@ -1,50 +1,33 @@
-use std::{
-    ops::Deref,
-    path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::HashSet, fmt, ops::Deref, path::PathBuf, sync::Arc, time::Duration};
 
 use axum::extract::FromRef;
-use tokio::sync::mpsc;
+use cyclone_core::CycloneRequestable;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::{
+    execution::{self, Execution},
+    Config,
+};
 
 #[derive(Clone, FromRef)]
 pub struct AppState {
-    lang_server_path: LangServerPath,
     telemetry_level: TelemetryLevel,
-    lang_server_function_timeout: LangServerFunctionTimeout,
-    lang_server_process_timeout: LangServerProcessTimeout,
+    execution_settings: ExecutionSettings,
 }
 
 impl AppState {
     pub fn new(
-        lang_server_path: impl Into<PathBuf>,
         telemetry_level: Box<dyn telemetry::TelemetryLevel>,
-        lang_server_function_timeout: Option<usize>,
-        lang_server_process_timeout: Option<u64>,
+        execution_settings: ExecutionSettings,
     ) -> Self {
         Self {
-            lang_server_path: LangServerPath(Arc::new(lang_server_path.into())),
             telemetry_level: TelemetryLevel(Arc::new(telemetry_level)),
-            lang_server_function_timeout: LangServerFunctionTimeout(Arc::new(
-                lang_server_function_timeout,
-            )),
-            lang_server_process_timeout: LangServerProcessTimeout(Arc::new(
-                lang_server_process_timeout,
-            )),
+            execution_settings,
         }
     }
 }
 
-#[derive(Clone, Debug, FromRef)]
-pub struct LangServerPath(Arc<PathBuf>);
-
-impl LangServerPath {
-    pub fn as_path(&self) -> &Path {
-        self.0.as_path()
-    }
-}
-
 #[derive(Clone, FromRef)]
 pub struct TelemetryLevel(Arc<Box<dyn telemetry::TelemetryLevel>>);
 
@@ -56,21 +39,81 @@ impl Deref for TelemetryLevel {
     }
 }
 
-#[derive(Clone, Debug, FromRef)]
-pub struct LangServerFunctionTimeout(Arc<Option<usize>>);
-
-impl LangServerFunctionTimeout {
-    pub fn inner(&self) -> Option<usize> {
-        Arc::clone(&self.0).as_ref().to_owned()
-    }
+/// The settings every function execution is run with, built once from the server's [`Config`].
+#[derive(Clone, Debug)]
+pub struct ExecutionSettings {
+    lang_server_path: Arc<PathBuf>,
+    lang_server_function_timeout: Option<usize>,
+    lang_server_process_timeout: Option<u64>,
+    redaction_preview: bool,
+    keepalive_interval: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    output_rate_limit: Option<u32>,
+    require_sensitive_strings_for: Arc<HashSet<String>>,
+    child_term_grace: Option<Duration>,
+    multi_request_idle_timeout: Option<Duration>,
+    lang_server_handshake_timeout: Option<Duration>,
+    /// Permits for spawning lang server processes, shared by all executions.
+    process_spawn_permits: Arc<Semaphore>,
+    lang_server_env: Arc<Vec<(String, String)>>,
+    lang_server_working_dir: Option<Arc<PathBuf>>,
+    lang_server_compression: bool,
 }
 
-#[derive(Clone, Debug, FromRef)]
-pub struct LangServerProcessTimeout(Arc<Option<u64>>);
+impl ExecutionSettings {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            lang_server_path: Arc::new(config.lang_server_path().to_path_buf()),
+            lang_server_function_timeout: config.lang_server_function_timeout(),
+            lang_server_process_timeout: config.lang_server_process_timeout(),
+            redaction_preview: config.enable_redaction_preview(),
+            keepalive_interval: config.execution_keepalive_interval(),
+            heartbeat_interval: config.execution_heartbeat_interval(),
+            output_rate_limit: config.execution_output_rate_limit(),
+            require_sensitive_strings_for: Arc::new(config.require_sensitive_strings_for().clone()),
+            child_term_grace: config.child_term_grace(),
+            multi_request_idle_timeout: config.multi_request_idle_timeout(),
+            lang_server_handshake_timeout: config.lang_server_handshake_timeout(),
+            process_spawn_permits: Arc::new(Semaphore::new(config.max_concurrent_processes())),
+            lang_server_env: Arc::new(config.lang_server_env().to_vec()),
+            lang_server_working_dir: config
+                .lang_server_working_dir()
+                .map(|dir| Arc::new(dir.to_path_buf())),
+            lang_server_compression: config.enable_lang_server_compression(),
+        }
+    }
 
-impl LangServerProcessTimeout {
-    pub fn inner(&self) -> Option<u64> {
-        Arc::clone(&self.0).as_ref().to_owned()
+    /// Builds an execution of the given lang server sub-command (i.e. request kind) with these
+    /// settings.
+    pub fn execution<Request, LangServerSuccess, Success>(
+        &self,
+        sub_command: &str,
+        lang_server_debugging: bool,
+    ) -> Execution<Request, LangServerSuccess, Success>
+    where
+        Request: Serialize + DeserializeOwned + Unpin + fmt::Debug + CycloneRequestable,
+        LangServerSuccess: DeserializeOwned,
+        Success: Serialize,
+    {
+        execution::new(
+            self.lang_server_path.as_path(),
+            lang_server_debugging,
+            self.lang_server_function_timeout,
+            self.lang_server_process_timeout,
+            sub_command.to_owned(),
+            self.lang_server_env.as_ref().clone(),
+        )
+        .with_redaction_preview(self.redaction_preview)
+        .with_keepalive_interval(self.keepalive_interval)
+        .with_heartbeat_interval(self.heartbeat_interval)
+        .with_output_rate_limit(self.output_rate_limit)
+        .with_require_sensitive_strings(self.require_sensitive_strings_for.contains(sub_command))
+        .with_term_grace(self.child_term_grace)
+        .with_multi_request_idle_timeout(self.multi_request_idle_timeout)
+        .with_handshake_timeout(self.lang_server_handshake_timeout)
+        .with_spawn_permits(Arc::clone(&self.process_spawn_permits))
+        .with_working_dir(self.lang_server_working_dir.as_deref().cloned())
+        .with_compression(self.lang_server_compression)
     }
 }
 
@@ -5,6 +5,8 @@
 //! variant for the "current" version of the node weight must be annotated
 //! `#[si_versioned_node_weight(current)]`, and be a newtype style variant. The macro checks to
 //! make sure that exactly one variant is marked as "current", and that it has the correct shape.
+//! Every variant must be named `V<number>`, which is used to report the version of the stored
+//! variant.
 //!
 //! ```ignore
 //! #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SiVersionedNodeWeight)]
@@ -39,6 +41,14 @@
 //!             }
 //!         }
 //!     }
+//!
+//!     fn version(&self) -> u32 {
+//!         match self {
+//!             Self::V0 => 0,
+//!             Self::V1(..) => 1,
+//!             Self::V2(..) => 2,
+//!         }
+//!     }
 //! }
 //! ```
 //!
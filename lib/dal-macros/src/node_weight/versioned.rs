@@ -29,8 +29,33 @@ pub fn derive_si_versioned_node_weight(
 
     let mut maybe_current_variant = None;
     let mut maybe_inner_type = None;
+    let mut version_match_arms = Vec::new();
     for variant in &enum_data.variants {
         let variant_attrs = SiVersionedNodeWeightVariantOptions::from_attributes(&variant.attrs)?;
+
+        let variant_ident = &variant.ident;
+        match variant_ident
+            .to_string()
+            .strip_prefix('V')
+            .and_then(|version| version.parse::<u32>().ok())
+        {
+            Some(version) => {
+                let pattern = match &variant.fields {
+                    Fields::Named(_) => quote! { Self::#variant_ident { .. } },
+                    Fields::Unnamed(_) => quote! { Self::#variant_ident(..) },
+                    Fields::Unit => quote! { Self::#variant_ident },
+                };
+                version_match_arms.push(quote! { #pattern => #version, });
+            }
+            None => {
+                emit!(
+                    errors,
+                    variant_ident,
+                    "Variant name must be of the form `V<number>`."
+                );
+            }
+        }
+
         if variant_attrs.current {
             if maybe_current_variant.is_none() {
                 maybe_current_variant = Some(variant.ident.clone());
@@ -114,6 +139,13 @@ pub fn derive_si_versioned_node_weight(
                     }
                 }
             }
+
+            /// Return the version number of the stored enum variant
+            fn version(&self) -> u32 {
+                match self {
+                    #(#version_match_arms)*
+                }
+            }
         }
     };
 
@@ -5,12 +5,17 @@ pub trait SiVersionedNodeWeight {
 
     fn inner(&self) -> &Self::Inner;
     fn inner_mut(&mut self) -> &mut Self::Inner;
+    fn version(&self) -> u32;
 }
 
 #[derive(dal_macros::SiVersionedNodeWeight)]
 pub enum VersionedNodeWeight {
+    V0,
     #[si_versioned_node_weight(current)]
     V1(InnerType),
 }
 
-fn main() {}
+fn main() {
+    assert_eq!(0, VersionedNodeWeight::V0.version());
+    assert_eq!(1, VersionedNodeWeight::V1(InnerType).version());
+}
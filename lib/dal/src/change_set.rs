@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_pg::{PgError, PgRow};
 use si_events::audit_log::AuditLogKind;
-use si_events::{ulid::Ulid, WorkspaceSnapshotAddress};
+use si_events::{ulid::Ulid, ContentHash, WorkspaceSnapshotAddress};
 use si_layer_cache::LayerDbError;
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -37,6 +37,10 @@ const FIND_ANCESTORS_QUERY: &str = include_str!("queries/change_set/find_ancesto
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetError {
+    #[error("no abandon approval flow is active for change set: {0}")]
+    AbandonApprovalFlowNotActive(ChangeSetId),
+    #[error("base change set {0} is not open (status: {1})")]
+    BaseChangeSetNotOpen(ChangeSetId, ChangeSetStatus),
     #[error("billing publish error: {0}")]
     BillingPublish(#[from] Box<BillingPublishError>),
     #[error("change set not approved for apply. Current state: {0}")]
@@ -53,6 +57,8 @@ pub enum ChangeSetError {
     Func(#[from] Box<FuncError>),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("idempotency key {0} was already used for a different request")]
+    IdempotencyKeyReused(String),
     #[error("invalid user actor pk")]
     InvalidActor(UserPk),
     #[error("invalid user system init")]
@@ -239,6 +245,113 @@ impl ChangeSet {
         Ok(change_set)
     }
 
+    /// Creates a new change set based on the snapshot of the given change set rather than HEAD.
+    /// The base change set must be open.
+    pub async fn fork_from(
+        ctx: &DalContext,
+        base_change_set_id: ChangeSetId,
+        name: impl AsRef<str>,
+    ) -> ChangeSetResult<Self> {
+        let base = Self::get_by_id(ctx, base_change_set_id).await?;
+        if base.status != ChangeSetStatus::Open {
+            return Err(ChangeSetError::BaseChangeSetNotOpen(base.id, base.status));
+        }
+
+        let change_set =
+            ChangeSet::new(ctx, name, Some(base.id), base.workspace_snapshot_address).await?;
+
+        Ok(change_set)
+    }
+
+    /// Finds the change set that was created in the current workspace with the given idempotency
+    /// key, as long as the key was recorded less than `ttl` ago.
+    ///
+    /// The key must have been recorded for the same request, identified by `request_hash`;
+    /// reusing a key for a different request is an error rather than a replay.
+    ///
+    /// This first takes a lock on the key for the rest of the transaction, so that concurrent
+    /// requests with the same key wait for this one to commit (and then find its change set)
+    /// rather than each creating their own.
+    pub async fn find_by_idempotency_key(
+        ctx: &DalContext,
+        idempotency_key: &str,
+        request_hash: ContentHash,
+        ttl: Duration,
+    ) -> ChangeSetResult<Option<Self>> {
+        let workspace_id = ctx.workspace_pk()?;
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .execute(
+                "SELECT pg_advisory_xact_lock(hashtextextended($1, 0))",
+                &[&format!(
+                    "change_set_idempotency_key:{workspace_id}:{idempotency_key}"
+                )],
+            )
+            .await?;
+        let maybe_row = txns
+            .pg()
+            .query_opt(
+                "SELECT change_set_pointers.*,
+                        change_set_idempotency_keys.request_hash AS idempotency_request_hash
+                    FROM change_set_pointers
+                    JOIN change_set_idempotency_keys
+                        ON change_set_idempotency_keys.change_set_id = change_set_pointers.id
+                    WHERE change_set_idempotency_keys.workspace_id = $1
+                        AND change_set_idempotency_keys.idempotency_key = $2
+                        AND change_set_idempotency_keys.created_at
+                            > CLOCK_TIMESTAMP() - make_interval(secs => $3)",
+                &[&workspace_id, &idempotency_key, &ttl.as_secs_f64()],
+            )
+            .await?;
+
+        let Some(row) = maybe_row else {
+            return Ok(None);
+        };
+        let recorded_request_hash: String = row.try_get("idempotency_request_hash")?;
+        if recorded_request_hash != request_hash.to_string() {
+            return Err(ChangeSetError::IdempotencyKeyReused(
+                idempotency_key.to_owned(),
+            ));
+        }
+
+        Ok(Some(Self::try_from(row)?))
+    }
+
+    /// Records that this change set was created with the given idempotency key for the request
+    /// identified by `request_hash`, so that [`Self::find_by_idempotency_key`] returns it. Must be
+    /// called in the same transaction as [`Self::find_by_idempotency_key`], which locks the key.
+    ///
+    /// Keys in the workspace recorded more than `ttl` ago are deleted first, so an expired key is
+    /// taken over while one that is still live is never repointed.
+    pub async fn record_idempotency_key(
+        &self,
+        ctx: &DalContext,
+        idempotency_key: &str,
+        request_hash: ContentHash,
+        ttl: Duration,
+    ) -> ChangeSetResult<()> {
+        let workspace_id = ctx.workspace_pk()?;
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .execute(
+                "DELETE FROM change_set_idempotency_keys
+                    WHERE workspace_id = $1
+                        AND created_at <= CLOCK_TIMESTAMP() - make_interval(secs => $2)",
+                &[&workspace_id, &ttl.as_secs_f64()],
+            )
+            .await?;
+        txns.pg()
+            .execute(
+                "INSERT INTO change_set_idempotency_keys
+                    (workspace_id, idempotency_key, change_set_id, request_hash)
+                    VALUES ($1, $2, $3, $4)",
+                &[&workspace_id, &idempotency_key, &self.id, &request_hash],
+            )
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn into_frontend_type(
         &self,
         ctx: &DalContext,
@@ -901,6 +1014,37 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Restarts the abandon approval flow by cancelling and beginning it again, returning the
+    /// status the change set had beforehand.
+    ///
+    /// The change set's row is locked while the flow is restarted, so a concurrent begin or cancel
+    /// can't slip in between. The cancellation publishes no events of its own: only those of
+    /// beginning the flow are published, including the initiator's vote which resets the votes.
+    pub async fn restart_abandon_approval_flow(
+        &mut self,
+        ctx: &DalContext,
+    ) -> ChangeSetResult<ChangeSetStatus> {
+        let txns = ctx.txns().await?;
+        let row = txns
+            .pg()
+            .query_one(
+                "SELECT status FROM change_set_pointers WHERE id = $1 FOR UPDATE",
+                &[&self.id],
+            )
+            .await?;
+        let status_string: String = row.try_get("status")?;
+        let old_status = ChangeSetStatus::try_from(status_string.as_str())?;
+        if old_status != ChangeSetStatus::NeedsAbandonApproval {
+            return Err(ChangeSetError::AbandonApprovalFlowNotActive(self.id));
+        }
+
+        // Cancel the flow without publishing the cancellation, as the flow is begun again below
+        self.update_status(ctx, ChangeSetStatus::Open).await?;
+        self.begin_abandon_approval_flow(ctx).await?;
+
+        Ok(old_status)
+    }
+
     pub async fn begin_approval_flow(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         self.update_status(ctx, ChangeSetStatus::NeedsApproval)
             .await?;
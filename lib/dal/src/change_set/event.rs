@@ -161,6 +161,23 @@ impl WsEvent {
         .await
     }
 
+    pub async fn change_set_abandon_requested(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        user_pk: UserPk,
+        user_email: String,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetAbandonRequested(ChangeSetAbandonRequestedPayload {
+                change_set_id,
+                user_pk,
+                user_email,
+            }),
+        )
+        .await
+    }
+
     pub async fn change_set_cancel_abandon_approval_process(
         ctx: &DalContext,
         change_set_id: ChangeSetId,
@@ -198,6 +215,15 @@ pub struct ChangeSetActorPayload {
     change_set_id: ChangeSetId,
     user_pk: Option<UserPk>,
 }
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetAbandonRequestedPayload {
+    change_set_id: ChangeSetId,
+    user_pk: UserPk,
+    user_email: String,
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeSetStateChangePayload {
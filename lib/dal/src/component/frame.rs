@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use itertools::Itertools;
+use si_events::audit_log::AuditLogKind;
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -12,8 +13,8 @@ use crate::socket::output::OutputSocketError;
 use crate::workspace_snapshot::edge_weight::{EdgeWeightKind, EdgeWeightKindDiscriminants};
 use crate::workspace_snapshot::WorkspaceSnapshotError;
 use crate::{
-    Component, ComponentError, ComponentId, ComponentType, DalContext, InputSocket, OutputSocket,
-    TransactionsError, WsEvent, WsEventError,
+    AttributeValueId, Component, ComponentError, ComponentId, ComponentType, DalContext,
+    InputSocket, OutputSocket, TransactionsError, WsEvent, WsEventError,
 };
 
 use super::inferred_connection_graph::InferredConnectionGraphError;
@@ -40,6 +41,11 @@ pub enum FrameError {
     Transactions(#[from] TransactionsError),
     #[error("workspace snapshot error: {0}")]
     WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
+    #[error("attaching child {child_id} to parent {parent_id} would create a cycle")]
+    WouldCreateCycle {
+        child_id: ComponentId,
+        parent_id: ComponentId,
+    },
     #[error("WsEvent error: {0}")]
     WsEvent(#[from] WsEventError),
 }
@@ -144,11 +150,20 @@ impl Frame {
                 .into();
 
             Self::detach_child_from_parent_inner(ctx, parent_id, child_id).await?;
-        } else {
+            ctx.write_audit_log(
+                AuditLogKind::DetachComponentFromFrame {
+                    child_id,
+                    former_parent_id: parent_id,
+                },
+                Component::name_by_id(ctx, child_id).await?,
+            )
+            .await?;
+        } else if !parent_idxs.is_empty() {
             // When there are multiple parents, we're trying to recover from a broken state, and we
             // can't reliably detect everything necessary to do a DependentValuesUpdate, or most of
             // the other things we'd normally do. This means there won't be any WsEvents for
             // removal of the inferred edges.
+            let mut former_parent_ids = Vec::with_capacity(parent_idxs.len());
             for parent_idx in parent_idxs {
                 let parent_id = ctx
                     .workspace_snapshot()?
@@ -157,7 +172,16 @@ impl Frame {
                     .id()
                     .into();
                 Component::remove_edge_from_frame(ctx, parent_id, child_id).await?;
+                former_parent_ids.push(parent_id);
             }
+            ctx.write_audit_log(
+                AuditLogKind::RepairComponentFrameParents {
+                    child_id,
+                    former_parent_ids,
+                },
+                Component::name_by_id(ctx, child_id).await?,
+            )
+            .await?;
         }
 
         Ok(())
@@ -184,6 +208,59 @@ impl Frame {
         Self::upsert_parent_inner(ctx, child_id, new_parent_id, false).await
     }
 
+    /// Attaches or replaces the parents of many child [`Components`](Component) at once.
+    ///
+    /// The moves are performed in order, but the inferred edge changes of all of them are coalesced
+    /// into a single removal and a single upsertion WsEvent, and the impacted values are enqueued
+    /// once at the end, rather than once per move as [`Self::upsert_parent`] would.
+    #[instrument(level = "info", skip(ctx))]
+    pub async fn upsert_parents(
+        ctx: &DalContext,
+        moves: Vec<(ComponentId, ComponentId)>,
+    ) -> FrameResult<InferredEdgeChanges> {
+        let mut removed_edges: HashSet<SummaryDiagramInferredEdge> = HashSet::new();
+        let mut upserted_edges: HashSet<SummaryDiagramInferredEdge> = HashSet::new();
+        let mut values_to_run: HashSet<AttributeValueId> = HashSet::new();
+
+        for (child_id, new_parent_id) in moves {
+            if !Self::needs_new_parent(ctx, child_id, new_parent_id).await? {
+                continue;
+            }
+            let (changes, impacted_values) =
+                Self::attach_child_to_parent_changes(ctx, new_parent_id, child_id).await?;
+
+            // A later move takes precedence over an earlier one for the same edge
+            for edge in changes.removed_edges {
+                upserted_edges.remove(&edge);
+                removed_edges.insert(edge);
+            }
+            for edge in changes.upserted_edges {
+                removed_edges.remove(&edge);
+                upserted_edges.insert(edge);
+            }
+            values_to_run.extend(impacted_values);
+        }
+
+        let changes = InferredEdgeChanges {
+            removed_edges: removed_edges.into_iter().collect(),
+            upserted_edges: upserted_edges.into_iter().collect(),
+        };
+
+        WsEvent::remove_inferred_edges(ctx, changes.removed_edges.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        WsEvent::upsert_inferred_edges(ctx, changes.upserted_edges.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        ctx.add_dependent_values_and_enqueue(values_to_run.into_iter().collect_vec())
+            .await?;
+
+        Ok(changes)
+    }
+
     #[instrument(level = "info", skip(ctx))]
     async fn upsert_parent_inner(
         ctx: &DalContext,
@@ -191,20 +268,55 @@ impl Frame {
         new_parent_id: ComponentId,
         send_events: bool,
     ) -> FrameResult<Option<InferredEdgeChanges>> {
+        if !Self::needs_new_parent(ctx, child_id, new_parent_id).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            Self::attach_child_to_parent_inner(ctx, new_parent_id, child_id, send_events).await?,
+        ))
+    }
+
+    /// Errors if the child is the parent or one of its ancestors, as attaching it would create a
+    /// cycle of frames.
+    async fn ensure_not_ancestor(
+        ctx: &DalContext,
+        child_id: ComponentId,
+        parent_id: ComponentId,
+    ) -> FrameResult<()> {
+        let mut visited = HashSet::new();
+        let mut maybe_ancestor_id = Some(parent_id);
+        while let Some(ancestor_id) = maybe_ancestor_id {
+            if ancestor_id == child_id {
+                return Err(FrameError::WouldCreateCycle {
+                    child_id,
+                    parent_id,
+                });
+            }
+            if !visited.insert(ancestor_id) {
+                break;
+            }
+            maybe_ancestor_id = Component::get_parent_by_id(ctx, ancestor_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the child needs to be attached to the new parent, i.e. it isn't already its
+    /// parent, erroring if the new parent can't have children attached to it.
+    async fn needs_new_parent(
+        ctx: &DalContext,
+        child_id: ComponentId,
+        new_parent_id: ComponentId,
+    ) -> FrameResult<bool> {
         // let's see if we need to even do anything
         if let Some(current_parent_id) = Component::get_parent_by_id(ctx, child_id).await? {
             if current_parent_id == new_parent_id {
-                return Ok(None);
+                return Ok(false);
             }
         }
 
         match Component::get_type_by_id(ctx, new_parent_id).await? {
-            ComponentType::ConfigurationFrameDown | ComponentType::ConfigurationFrameUp => {
-                Ok(Some(
-                    Self::attach_child_to_parent_inner(ctx, new_parent_id, child_id, send_events)
-                        .await?,
-                ))
-            }
+            ComponentType::ConfigurationFrameDown | ComponentType::ConfigurationFrameUp => Ok(true),
             ComponentType::Component => Err(FrameError::ParentIsNotAFrame(child_id, new_parent_id)),
             ComponentType::AggregationFrame => {
                 Err(FrameError::AggregateFramesUnsupported(new_parent_id))
@@ -222,6 +334,37 @@ impl Frame {
         child_id: ComponentId,
         send_events: bool,
     ) -> FrameResult<InferredEdgeChanges> {
+        let (changes, values_to_run) =
+            Self::attach_child_to_parent_changes(ctx, parent_id, child_id).await?;
+
+        if send_events {
+            // Let the frontend know what edges should be removed, then what should be upserted.
+            WsEvent::remove_inferred_edges(ctx, changes.removed_edges.clone())
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+            WsEvent::upsert_inferred_edges(ctx, changes.upserted_edges.clone())
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+        }
+
+        // enqueue those values that we now know need to run
+        ctx.add_dependent_values_and_enqueue(values_to_run).await?;
+
+        Ok(changes)
+    }
+
+    /// Moves the child under the new parent, returning the resulting inferred edge changes and the
+    /// values which need to rerun, without sending WsEvents or enqueueing the values.
+    async fn attach_child_to_parent_changes(
+        ctx: &DalContext,
+        parent_id: ComponentId,
+        child_id: ComponentId,
+    ) -> FrameResult<(InferredEdgeChanges, Vec<AttributeValueId>)> {
+        // check before detaching the child from its current parent, so a rejected move changes nothing
+        Self::ensure_not_ancestor(ctx, child_id, parent_id).await?;
+
         // cache current map of input <-> output sockets based on what the parent knows about right now!!!!
         let initial_impacted_values: HashSet<SocketAttributeValuePair> =
             Self::get_all_inferred_connections_for_component_tree(ctx, parent_id, child_id).await?;
@@ -247,12 +390,30 @@ impl Frame {
 
         let cycle_check_guard = ctx.workspace_snapshot()?.enable_cycle_check().await;
         // add the new edge
-        Component::add_edge_to_frame(ctx, parent_id, child_id, EdgeWeightKind::FrameContains)
-            .await?;
+        let added =
+            Component::add_edge_to_frame(ctx, parent_id, child_id, EdgeWeightKind::FrameContains)
+                .await;
         drop(cycle_check_guard);
+        match added {
+            Err(ComponentError::WorkspaceSnapshot(err)) if err.is_create_graph_cycle() => {
+                return Err(FrameError::WouldCreateCycle {
+                    child_id,
+                    parent_id,
+                });
+            }
+            added => added?,
+        }
         ctx.workspace_snapshot()?
             .clear_inferred_connection_graph()
             .await;
+        ctx.write_audit_log(
+            AuditLogKind::AttachComponentToFrame {
+                child_id,
+                parent_id,
+            },
+            Component::name_by_id(ctx, child_id).await?,
+        )
+        .await?;
 
         // now figure out what needs to rerun!
         let mut values_to_run: HashSet<SocketAttributeValuePair> = HashSet::new();
@@ -274,7 +435,7 @@ impl Frame {
                 .copied(),
         );
 
-        // Let the frontend know what edges should be removed.
+        // Determine what edges the frontend should remove.
         let mut inferred_edges_to_remove: Vec<SummaryDiagramInferredEdge> =
             Vec::with_capacity(values_to_run.len());
         for pair in &values_to_run {
@@ -287,14 +448,7 @@ impl Frame {
             })
         }
 
-        if send_events {
-            WsEvent::remove_inferred_edges(ctx, inferred_edges_to_remove.clone())
-                .await?
-                .publish_on_commit(ctx)
-                .await?;
-        }
-
-        // After we let the frontend know what edges should be removed, now we should handle upsertion.
+        // After determining what edges should be removed, now we should handle upsertion.
         let removed_edges_to_skip: HashSet<SummaryDiagramInferredEdge> =
             HashSet::from_iter(inferred_edges_to_remove.clone().into_iter());
 
@@ -312,13 +466,6 @@ impl Frame {
             }
         }
 
-        if send_events {
-            WsEvent::upsert_inferred_edges(ctx, inferred_edges_to_upsert.clone())
-                .await?
-                .publish_on_commit(ctx)
-                .await?;
-        }
-
         // an input socket needs to rerun if:
         // the input socket has a new/different output socket driving it
         values_to_run.extend(
@@ -336,19 +483,16 @@ impl Frame {
             );
         }
 
-        // enqueue those values that we now know need to run
-        ctx.add_dependent_values_and_enqueue(
+        Ok((
+            InferredEdgeChanges {
+                removed_edges: inferred_edges_to_remove,
+                upserted_edges: inferred_edges_to_upsert,
+            },
             values_to_run
                 .into_iter()
                 .map(|values| values.component_input_socket.attribute_value_id)
                 .collect_vec(),
-        )
-        .await?;
-
-        Ok(InferredEdgeChanges {
-            removed_edges: inferred_edges_to_remove,
-            upserted_edges: inferred_edges_to_upsert,
-        })
+        ))
     }
 
     #[instrument(
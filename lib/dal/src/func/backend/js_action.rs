@@ -65,6 +65,7 @@ impl FuncDispatch for FuncBackendJsAction {
                             level: "error".to_owned(),
                             group: None,
                             message: message.clone(),
+                            fields: None,
                             timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
                         })
                         .await
@@ -83,6 +84,7 @@ impl FuncDispatch for FuncBackendJsAction {
                         level: "error".to_owned(),
                         group: None,
                         message: failure.error().message.to_owned(),
+                        fields: None,
                         timestamp: std::cmp::max(Utc::now().timestamp(), 0) as u64,
                     })
                     .await
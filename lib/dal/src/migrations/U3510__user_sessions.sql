@@ -0,0 +1,14 @@
+CREATE TABLE user_sessions
+(
+    id           ident primary key NOT NULL DEFAULT ident_create_v1(),
+    created_at   timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    user_pk      ident NOT NULL,
+    workspace_pk ident NOT NULL,
+    token_hash   text NOT NULL,
+    issued_at    timestamp with time zone NULL,
+    last_seen_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    client       text NULL,
+    revoked_at   timestamp with time zone NULL
+);
+CREATE UNIQUE INDEX ON user_sessions (token_hash);
+CREATE INDEX ON user_sessions (user_pk, workspace_pk);
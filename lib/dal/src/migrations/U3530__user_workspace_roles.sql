@@ -0,0 +1 @@
+ALTER TABLE user_belongs_to_workspaces ADD COLUMN role text NULL;
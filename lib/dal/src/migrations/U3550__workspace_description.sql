@@ -0,0 +1 @@
+ALTER TABLE workspaces ADD COLUMN description text NULL;
@@ -0,0 +1,8 @@
+CREATE TABLE change_set_idempotency_keys
+(
+    workspace_id    ident                    NOT NULL,
+    idempotency_key text                     NOT NULL,
+    change_set_id   ident                    NOT NULL,
+    created_at      timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    PRIMARY KEY (workspace_id, idempotency_key)
+);
@@ -0,0 +1,4 @@
+-- Keys are only kept for a short window, so existing ones are dropped rather than backfilled with a
+-- hash of a request that was never recorded
+DELETE FROM change_set_idempotency_keys;
+ALTER TABLE change_set_idempotency_keys ADD COLUMN request_hash text NOT NULL;
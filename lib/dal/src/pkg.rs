@@ -10,6 +10,7 @@ use crate::attribute::prototype::argument::{
 };
 use crate::attribute::prototype::AttributePrototypeError;
 use crate::attribute::value::AttributeValueError;
+use crate::diagram::DiagramError;
 use crate::func::argument::FuncArgumentId;
 use crate::management::prototype::ManagementPrototypeError;
 use crate::schema::variant::SchemaVariantError;
@@ -24,7 +25,10 @@ use crate::{
     DalContext, FuncBackendKind, FuncBackendResponseType, OutputSocketId, SchemaError,
     TransactionsError, UserPk, WorkspaceError, WorkspacePk, WsEvent, WsEventResult, WsPayload,
 };
-use crate::{AttributePrototypeId, FuncId, HistoryEventError, PropId, PropKind};
+use crate::{
+    AttributePrototypeId, ComponentError, ComponentId, FuncId, HistoryEventError, PropId, PropKind,
+    SchemaVariantId,
+};
 
 use crate::module::ModuleError;
 use crate::socket::connection_annotation::ConnectionAnnotationError;
@@ -38,6 +42,8 @@ pub mod import;
 pub enum PkgError {
     #[error("action prototype error: {0}")]
     ActionPrototype(#[from] ActionPrototypeError),
+    #[error("more than one schema found with name: {0}")]
+    AmbiguousSchemaName(String),
     #[error("attribute function for context {0:?} has key {1} but is not setting a prop value")]
     AttributeFuncForKeyMissingProp(import::AttrFuncContext, String),
     #[error("attribute function for prop {0} has a key {1} but prop kind is {2} not a map)")]
@@ -50,12 +56,25 @@ pub enum PkgError {
     AttributePrototypeArgumentMissingFuncArgument(AttributePrototypeArgumentId, FuncArgumentId),
     #[error("attribute value error: {0}")]
     AttributeValueError(#[from] AttributeValueError),
+    #[error("export cancelled")]
+    Cancelled,
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component {0} is not an instance of schema variant {1}")]
+    ComponentNotForVariant(ComponentId, SchemaVariantId),
     #[error("connection annotation error: {0}")]
     ConnectionAnnotation(#[from] ConnectionAnnotationError),
+    #[error("func {unique_id} referenced by {referenced_by} is not in the exported funcs")]
+    DanglingFuncReference {
+        unique_id: String,
+        referenced_by: String,
+    },
     #[error("expected data on an SiPkg node, but none found: {0}")]
     DataNotFound(String),
+    #[error("diagram error: {0}")]
+    Diagram(Box<DiagramError>),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("func argument error: {0}")]
@@ -68,12 +87,19 @@ pub enum PkgError {
     HistoryEvent(#[from] HistoryEventError),
     #[error("input socket error: {0}")]
     InputSocket(#[from] InputSocketError),
+    #[error(
+        "pkg spec is invalid: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidPkgSpec(Vec<SpecError>),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
     #[error("management prototype error: {0}")]
     ManagementPrototype(#[from] ManagementPrototypeError),
     #[error("Missing Func {1} for AttributePrototype {0}")]
     MissingAttributePrototypeFunc(AttributePrototypeId, FuncId),
-    #[error("Func {0} missing from exported funcs")]
-    MissingExportedFunc(FuncId),
+    #[error("Func {0} missing from exported funcs while exporting {1}")]
+    MissingExportedFunc(FuncId, &'static str),
     #[error("Cannot find FuncArgument {0} for Func {1}")]
     MissingFuncArgument(String, FuncId),
     #[error("Package asked for a function with the unique id {0} but none could be found ({1})")]
@@ -106,6 +132,8 @@ pub enum PkgError {
     PropSpecChildrenInvalid(String),
     #[error("schema error: {0}")]
     Schema(#[from] SchemaError),
+    #[error("schema not found with name: {0}")]
+    SchemaNameNotFound(String),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] SchemaVariantError),
     #[error("json serialization error: {0}")]
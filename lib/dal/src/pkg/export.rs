@@ -1,13 +1,17 @@
 use std::collections::HashSet;
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
 use std::ops::Deref;
+use std::time::{Duration, Instant};
 
 use strum::IntoEnumIterator;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
 
 use si_pkg::{
-    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AuthenticationFuncSpec,
-    ComponentSpec, EdgeSpec, FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec,
-    ManagementFuncSpec, MapKeyFuncSpec, PkgSpec, PropSpec, PropSpecBuilder, PropSpecKind,
+    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AttributeValuePath,
+    AttributeValueSpec, AuthenticationFuncSpec, ComponentSpec, ComponentSpecVariant, EdgeSpec,
+    EdgeSpecKind, FuncArgumentSpec, FuncSpec, FuncSpecData, LeafFunctionSpec, ManagementFuncSpec,
+    MapKeyFuncSpec, PkgSpec, PositionSpec, PropSpec, PropSpecBuilder, PropSpecKind,
     RootPropFuncSpec, SchemaSpec, SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecBuilder,
     SchemaVariantSpecData, SchemaVariantSpecPropRoot, SiPkg, SiPkgKind, SiPropFuncSpec,
     SiPropFuncSpecKind, SocketSpec, SocketSpecData, SocketSpecKind, SpecError,
@@ -19,6 +23,7 @@ use crate::attribute::prototype::argument::{
     AttributePrototypeArgument, AttributePrototypeArgumentId,
 };
 
+use crate::diagram::view::View;
 use crate::func::is_intrinsic;
 use crate::management::prototype::ManagementPrototype;
 use crate::schema::variant::leaves::{LeafInputLocation, LeafKind};
@@ -28,22 +33,49 @@ use crate::{
     AttributePrototype, DalContext, Func, FuncId, Prop, PropId, PropKind, Schema, SchemaId,
     SchemaVariant, SchemaVariantId, Workspace,
 };
-use crate::{AttributePrototypeId, InputSocket, OutputSocket};
+use crate::{
+    AttributePrototypeId, AttributeValue, AttributeValueId, Component, ComponentId, InputSocket,
+    OutputSocket,
+};
 
 use super::{PkgError, PkgResult};
 
 pub type FuncSpecMap = super::ChangeSetThingMap<FuncId, FuncSpec>;
 type VariantSpecMap = super::ChangeSetThingMap<SchemaVariantId, SchemaVariantSpec>;
 
+/// A callback invoked with [`ExportProgress`] events as a [`PkgExporter`] makes progress.
+pub type ExportProgressCallback = Box<dyn Fn(ExportProgress) + Send + Sync>;
+
+/// Progress events emitted by a [`PkgExporter`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExportProgress {
+    /// The export of a schema has finished.
+    SchemaFinished {
+        name: String,
+        func_count: usize,
+        elapsed: Duration,
+    },
+    /// The export of a schema has started.
+    SchemaStarted { name: String },
+    /// A schema variant has been exported.
+    VariantExported { name: String },
+}
+
+/// Exports schemas, and the funcs they use, as a [`SiPkgKind::Module`] package.
+///
+/// Workspace backups (i.e. [`SiPkgKind::WorkspaceBackup`] packages) are not supported, so an
+/// exporter can only ever produce modules.
 pub struct PkgExporter {
     name: String,
     version: String,
     description: Option<String>,
-    kind: SiPkgKind,
     created_by: String,
     schema_ids: Option<Vec<SchemaId>>,
     func_map: FuncSpecMap,
     variant_map: VariantSpecMap,
+    progress: Option<ExportProgressCallback>,
+    cancellation_token: Option<CancellationToken>,
+    with_resource_data: bool,
 }
 
 impl PkgExporter {
@@ -62,11 +94,56 @@ impl PkgExporter {
             name: name.into(),
             version: version.into(),
             description: description.map(Into::into),
-            kind: SiPkgKind::Module,
             created_by: created_by.into(),
             schema_ids: Some(schema_ids),
             func_map: FuncSpecMap::new(),
             variant_map: VariantSpecMap::new(),
+            progress: None,
+            cancellation_token: None,
+            with_resource_data: false,
+        }
+    }
+
+    /// Sets a callback to be invoked with [`ExportProgress`] events during the export.
+    ///
+    /// This is purely observational and has no effect on what is exported.
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(ExportProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    fn report_progress(&self, event: ExportProgress) {
+        if let Some(progress) = &self.progress {
+            progress(event);
+        }
+    }
+
+    /// Sets a token that aborts the export with [`PkgError::Cancelled`] once cancelled.
+    ///
+    /// Cancellation is checked before each schema is exported, so a schema that is already being
+    /// exported will finish first.
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Includes the values of exported components' resource value trees, which are stripped by
+    /// default.
+    ///
+    /// Those values can hold cloud identifiers or secrets, so modules which are shared should leave
+    /// them out, while workspace backups should opt in to them for full fidelity.
+    pub fn with_resource_data(mut self, with_resource_data: bool) -> Self {
+        self.with_resource_data = with_resource_data;
+        self
+    }
+
+    fn check_cancelled(&self) -> PkgResult<()> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => Err(PkgError::Cancelled),
+            _ => Ok(()),
         }
     }
 
@@ -85,10 +162,7 @@ impl PkgExporter {
     }
 
     pub async fn export_as_bytes(&mut self, ctx: &DalContext) -> PkgResult<Vec<u8>> {
-        match self.kind {
-            SiPkgKind::Module => info!("Building module package"),
-            SiPkgKind::WorkspaceBackup => return Err(PkgError::WorkspaceExportNotSupported()),
-        }
+        info!("Building module package");
 
         let pkg = self.export(ctx).await?;
 
@@ -97,11 +171,62 @@ impl PkgExporter {
         Ok(pkg.write_to_bytes()?)
     }
 
+    /// Exports the package into `writer`, as [`Self::export_as_bytes`] would.
+    ///
+    /// This doesn't bound memory use: every node of a package is addressed by the hash of its
+    /// children, so the whole package is built and serialized before any of it is written.
+    pub async fn export_to_writer<W>(&mut self, ctx: &DalContext, mut writer: W) -> PkgResult<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let bytes = self.export_as_bytes(ctx).await?;
+
+        info!("Writing module package");
+
+        writer.write_all(&bytes).await?;
+        writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Exports the schemas with the given names, in place of any schemas this exporter was
+    /// created with.
+    ///
+    /// Names are matched case-sensitively against [`Schema::list`], and each must match exactly
+    /// one schema.
+    pub async fn export_schema_by_name(
+        &mut self,
+        ctx: &DalContext,
+        names: Vec<String>,
+    ) -> PkgResult<SiPkg> {
+        let schemas = Schema::list(ctx).await?;
+
+        let mut schema_ids = Vec::with_capacity(names.len());
+        for name in names {
+            let mut matching = schemas.iter().filter(|schema| schema.name() == name);
+            let schema = matching
+                .next()
+                .ok_or_else(|| PkgError::SchemaNameNotFound(name.clone()))?;
+            if matching.next().is_some() {
+                return Err(PkgError::AmbiguousSchemaName(name));
+            }
+            schema_ids.push(schema.id());
+        }
+        self.schema_ids = Some(schema_ids);
+
+        self.export(ctx).await
+    }
+
     async fn export_schema(
         &mut self,
         ctx: &DalContext,
         schema: &Schema,
     ) -> PkgResult<(SchemaSpec, Vec<FuncSpec>)> {
+        let started = Instant::now();
+        self.report_progress(ExportProgress::SchemaStarted {
+            name: schema.name().to_owned(),
+        });
+
         let mut funcs = vec![];
         let schema_is_builtin = schema.is_builtin();
 
@@ -146,6 +271,12 @@ impl PkgExporter {
 
         let schema_spec = schema_spec_builder.build()?;
 
+        self.report_progress(ExportProgress::SchemaFinished {
+            name: schema.name().to_owned(),
+            func_count: funcs.len(),
+            elapsed: started.elapsed(),
+        });
+
         Ok((schema_spec, funcs))
     }
 
@@ -156,27 +287,281 @@ impl PkgExporter {
         schema_name: &str,
         overridden_asset_func_id: Option<FuncId>,
     ) -> PkgResult<(SchemaVariantSpec, Vec<FuncSpec>)> {
+        let (exporter, variant_spec) =
+            Self::standalone_variant_exporter(ctx, variant, schema_name, overridden_asset_func_id)
+                .await?;
+
+        Ok((variant_spec, exporter.exported_funcs()))
+    }
+
+    /// Exports a single schema variant like [`Self::export_variant_standalone`], along with the
+    /// given components of that variant and the connections between them. The functions returned
+    /// include those referenced by the components' own attribute prototypes.
+    ///
+    /// The components' resource data is stripped, as for any module which is shared.
+    pub async fn export_variant_standalone_with_components(
+        ctx: &DalContext,
+        variant: &SchemaVariant,
+        schema_name: &str,
+        overridden_asset_func_id: Option<FuncId>,
+        component_ids: &[ComponentId],
+    ) -> PkgResult<(
+        SchemaVariantSpec,
+        Vec<FuncSpec>,
+        Vec<ComponentSpec>,
+        Vec<EdgeSpec>,
+    )> {
+        let (exporter, variant_spec) =
+            Self::standalone_variant_exporter(ctx, variant, schema_name, overridden_asset_func_id)
+                .await?;
+
+        exporter
+            .export_components_for_variant(ctx, variant, variant_spec, component_ids)
+            .await
+    }
+
+    /// Exports a single schema variant and the given components of it like
+    /// [`Self::export_variant_standalone_with_components`], but with this exporter's options, such
+    /// as [`Self::with_resource_data`].
+    pub async fn export_variant_with_components(
+        mut self,
+        ctx: &DalContext,
+        variant: &SchemaVariant,
+        overridden_asset_func_id: Option<FuncId>,
+        component_ids: &[ComponentId],
+    ) -> PkgResult<(
+        SchemaVariantSpec,
+        Vec<FuncSpec>,
+        Vec<ComponentSpec>,
+        Vec<EdgeSpec>,
+    )> {
+        let variant_spec = self
+            .export_standalone_variant(ctx, variant, overridden_asset_func_id)
+            .await?;
+
+        self.export_components_for_variant(ctx, variant, variant_spec, component_ids)
+            .await
+    }
+
+    async fn export_components_for_variant(
+        mut self,
+        ctx: &DalContext,
+        variant: &SchemaVariant,
+        variant_spec: SchemaVariantSpec,
+        component_ids: &[ComponentId],
+    ) -> PkgResult<(
+        SchemaVariantSpec,
+        Vec<FuncSpec>,
+        Vec<ComponentSpec>,
+        Vec<EdgeSpec>,
+    )> {
+        let variant_unique_id = variant.id().to_string();
+        let mut component_specs = vec![];
+        for &component_id in component_ids {
+            let component_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+            if component_variant_id != variant.id() {
+                return Err(PkgError::ComponentNotForVariant(component_id, variant.id()));
+            }
+
+            let component = Component::get_by_id(ctx, component_id).await?;
+            component_specs.push(
+                self.export_component(ctx, &component, &variant_unique_id)
+                    .await?,
+            );
+        }
+
+        let edge_specs = Self::export_edges(ctx, component_ids).await?;
+
+        Ok((
+            variant_spec,
+            self.exported_funcs(),
+            component_specs,
+            edge_specs,
+        ))
+    }
+
+    async fn standalone_variant_exporter(
+        ctx: &DalContext,
+        variant: &SchemaVariant,
+        schema_name: &str,
+        overridden_asset_func_id: Option<FuncId>,
+    ) -> PkgResult<(Self, SchemaVariantSpec)> {
         let mut exporter = Self::new_standalone_variant_exporter(schema_name);
         let email = ctx.history_actor().email(ctx).await?;
         exporter.created_by = email;
 
-        exporter
-            .export_funcs_for_variant(ctx, variant.id(), overridden_asset_func_id)
+        let variant_spec = exporter
+            .export_standalone_variant(ctx, variant, overridden_asset_func_id)
             .await?;
 
-        exporter.export_intrinsics(ctx).await?;
-        let variant_spec = exporter
-            .export_variant(ctx, variant, false, overridden_asset_func_id)
+        Ok((exporter, variant_spec))
+    }
+
+    async fn export_standalone_variant(
+        &mut self,
+        ctx: &DalContext,
+        variant: &SchemaVariant,
+        overridden_asset_func_id: Option<FuncId>,
+    ) -> PkgResult<SchemaVariantSpec> {
+        self.export_funcs_for_variant(ctx, variant.id(), overridden_asset_func_id)
             .await?;
 
-        let funcs = exporter
-            .func_map
+        self.export_intrinsics(ctx).await?;
+        self.export_variant(ctx, variant, false, overridden_asset_func_id)
+            .await
+    }
+
+    fn exported_funcs(&self) -> Vec<FuncSpec> {
+        self.func_map
             .inner
             .values()
             .map(ToOwned::to_owned)
-            .collect();
+            .collect()
+    }
+
+    /// Exports a component along with the attribute values that are set on the component itself,
+    /// adding the functions of their prototypes to the exported funcs. Values which come from the
+    /// variant's prototypes are left out, since they are recomputed once the component exists.
+    ///
+    /// Resource data is stripped unless [`Self::with_resource_data`] was set.
+    async fn export_component(
+        &mut self,
+        ctx: &DalContext,
+        component: &Component,
+        variant_unique_id: &str,
+    ) -> PkgResult<ComponentSpec> {
+        let default_view_id = View::get_id_for_default(ctx)
+            .await
+            .map_err(|err| PkgError::Diagram(Box::new(err)))?;
+        let geometry = component.geometry(ctx, default_view_id).await?;
+
+        let mut component_spec_builder = ComponentSpec::builder();
+        component_spec_builder
+            .name(component.name(ctx).await?)
+            .position(
+                PositionSpec::builder()
+                    .x(geometry.x().to_string())
+                    .y(geometry.y().to_string())
+                    .width(geometry.width().map(|width| width.to_string()))
+                    .height(geometry.height().map(|height| height.to_string()))
+                    .build()?,
+            )
+            .variant(ComponentSpecVariant::WorkspaceVariant {
+                variant_unique_id: variant_unique_id.to_owned(),
+            })
+            .needs_destroy(component.to_delete())
+            .deletion_user_pk(None::<String>)
+            .unique_id(component.id().to_string())
+            .deleted(false);
+
+        let root_attribute_value_id =
+            Component::root_attribute_value_id(ctx, component.id()).await?;
+        let mut work_queue = VecDeque::from([root_attribute_value_id]);
+        while let Some(attribute_value_id) = work_queue.pop_front() {
+            work_queue
+                .extend(AttributeValue::get_child_av_ids_in_order(ctx, attribute_value_id).await?);
+
+            if let Some(prototype_id) =
+                AttributeValue::component_prototype_id(ctx, attribute_value_id).await?
+            {
+                component_spec_builder.attribute(
+                    self.export_component_attribute_value(ctx, attribute_value_id, prototype_id)
+                        .await?,
+                );
+            }
+        }
+
+        let mut component_spec = component_spec_builder.build()?;
+        if !self.with_resource_data {
+            component_spec.strip_resource_data();
+        }
+
+        Ok(component_spec)
+    }
+
+    async fn export_component_attribute_value(
+        &mut self,
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+        prototype_id: AttributePrototypeId,
+    ) -> PkgResult<AttributeValueSpec> {
+        let attribute_value = AttributeValue::get_by_id(ctx, attribute_value_id).await?;
+        let prop_id = AttributeValue::prop_id(ctx, attribute_value_id).await?;
+        let prop_path = Prop::path_by_id(ctx, prop_id).await?;
+        let key_or_index =
+            AttributeValue::get_index_or_key_of_child_entry(ctx, attribute_value_id).await?;
+
+        let func_id = AttributePrototype::func_id(ctx, prototype_id).await?;
+        let func = Func::get_by_id_or_error(ctx, func_id).await?;
+        let (func_spec, _) = self.add_func_to_map(ctx, &func).await?;
+
+        let mut builder = AttributeValueSpec::builder();
+        builder
+            .path(AttributeValuePath::Prop {
+                path: prop_path.as_str().to_owned(),
+                key_or_index,
+            })
+            .func_unique_id(func_spec.unique_id)
+            .func_binding_args(serde_json::json!({}))
+            .backend_kind(func.backend_kind)
+            .response_type(func.backend_response_type)
+            .component_specific(true);
+
+        if let Some(handler) = &func.handler {
+            builder.handler(handler);
+        }
+        if let Some(code_base64) = &func.code_base64 {
+            builder.code_base64(code_base64);
+        }
+        if let Some(value) = attribute_value.value(ctx).await? {
+            builder.value(value);
+        }
+        if let Some(unprocessed_value) = attribute_value.unprocessed_value(ctx).await? {
+            builder.unprocessed_value(unprocessed_value);
+        }
+        if let Some((_, inputs)) = self
+            .export_input_func_and_arguments(ctx, prototype_id)
+            .await?
+        {
+            builder.inputs(inputs);
+        }
 
-        Ok((variant_spec, funcs))
+        Ok(builder.build()?)
+    }
+
+    /// Exports the connections between the given components, leaving out any connection to a
+    /// component which is not one of them.
+    async fn export_edges(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+    ) -> PkgResult<Vec<EdgeSpec>> {
+        let mut edge_specs = vec![];
+        for &component_id in component_ids {
+            for connection in Component::incoming_connections_for_id(ctx, component_id).await? {
+                if !component_ids.contains(&connection.from_component_id) {
+                    continue;
+                }
+
+                let from_socket =
+                    OutputSocket::get_by_id(ctx, connection.from_output_socket_id).await?;
+                let to_socket = InputSocket::get_by_id(ctx, connection.to_input_socket_id).await?;
+                edge_specs.push(
+                    EdgeSpec::builder()
+                        .edge_kind(EdgeSpecKind::Configuration)
+                        .from_component_unique_id(connection.from_component_id.to_string())
+                        .from_socket_name(from_socket.name())
+                        .to_component_unique_id(connection.to_component_id.to_string())
+                        .to_socket_name(to_socket.name())
+                        .creation_user_pk(None::<String>)
+                        .deletion_user_pk(None::<String>)
+                        .deleted_implicitly(false)
+                        .unique_id(connection.attribute_prototype_argument_id.to_string())
+                        .build()?,
+                );
+            }
+        }
+
+        Ok(edge_specs)
     }
 
     async fn export_variant(
@@ -307,6 +692,10 @@ impl PkgExporter {
 
         let variant_spec = variant_spec_builder.build()?;
 
+        self.report_progress(ExportProgress::VariantExported {
+            name: variant.display_name().to_owned(),
+        });
+
         Ok(variant_spec)
     }
 
@@ -398,7 +787,7 @@ impl PkgExporter {
                 let func_spec = self
                     .func_map
                     .get(&leaf_func_id)
-                    .ok_or(PkgError::MissingExportedFunc(leaf_func_id))?;
+                    .ok_or(PkgError::MissingExportedFunc(leaf_func_id, "leaf funcs"))?;
 
                 let mut inputs = vec![];
                 for arg in FuncArgument::list_for_func(ctx, leaf_func_id).await? {
@@ -523,7 +912,7 @@ impl PkgExporter {
             let func_spec = self
                 .func_map
                 .get(&key)
-                .ok_or(PkgError::MissingExportedFunc(key))?;
+                .ok_or(PkgError::MissingExportedFunc(key, "management funcs"))?;
 
             let mut builder = ManagementFuncSpec::builder();
             if let Some(description) = management_proto.description() {
@@ -561,7 +950,7 @@ impl PkgExporter {
             let func_spec = self
                 .func_map
                 .get(&key)
-                .ok_or(PkgError::MissingExportedFunc(key))?;
+                .ok_or(PkgError::MissingExportedFunc(key, "action funcs"))?;
 
             let mut builder = ActionFuncSpec::builder();
 
@@ -588,7 +977,7 @@ impl PkgExporter {
             let func_spec = self
                 .func_map
                 .get(&auth_func)
-                .ok_or(PkgError::MissingExportedFunc(auth_func))?;
+                .ok_or(PkgError::MissingExportedFunc(auth_func, "auth funcs"))?;
 
             let mut builder = AuthenticationFuncSpec::builder();
 
@@ -626,18 +1015,22 @@ impl PkgExporter {
             parent_prop_id: Option<PropId>,
         }
 
-        let mut stack: Vec<(PropId, Option<PropId>)> = Vec::new();
-        for child_tree_node in Prop::direct_child_prop_ids_unordered(ctx, root_prop.id()).await? {
-            stack.push((child_tree_node, None));
+        let mut stack: Vec<(PropId, Option<PropId>, u32)> = Vec::new();
+        for (order, child_tree_node) in Self::child_prop_ids(ctx, root_prop.id())
+            .await?
+            .into_iter()
+            .enumerate()
+        {
+            stack.push((child_tree_node, None, order as u32));
         }
 
         let mut traversal_stack: Vec<TraversalStackEntry> = Vec::new();
 
-        while let Some((prop_id, parent_prop_id)) = stack.pop() {
+        while let Some((prop_id, parent_prop_id, order)) = stack.pop() {
             let child_prop = Prop::get_by_id(ctx, prop_id).await?;
             let mut builder = PropSpec::builder();
 
-            builder.unique_id(prop_id);
+            builder.unique_id(prop_id).order(order);
 
             builder
                 .name(child_prop.name)
@@ -679,9 +1072,12 @@ impl PkgExporter {
                 parent_prop_id,
             });
 
-            for child_tree_node in Prop::direct_child_prop_ids_unordered(ctx, child_prop.id).await?
+            for (order, child_tree_node) in Self::child_prop_ids(ctx, child_prop.id)
+                .await?
+                .into_iter()
+                .enumerate()
             {
-                stack.push((child_tree_node, Some(prop_id)));
+                stack.push((child_tree_node, Some(prop_id), order as u32));
             }
         }
 
@@ -791,6 +1187,17 @@ impl PkgExporter {
         Ok(())
     }
 
+    /// Returns the children of a prop in the order they are displayed, for props that keep an
+    /// order (i.e. objects), and in no particular order otherwise.
+    async fn child_prop_ids(ctx: &DalContext, prop_id: PropId) -> PkgResult<Vec<PropId>> {
+        let ordered = Prop::direct_child_prop_ids_ordered(ctx, prop_id).await?;
+        if !ordered.is_empty() {
+            return Ok(ordered);
+        }
+
+        Ok(Prop::direct_child_prop_ids_unordered(ctx, prop_id).await?)
+    }
+
     async fn export_input_func_and_arguments(
         &self,
         ctx: &DalContext,
@@ -871,7 +1278,7 @@ impl PkgExporter {
         let func_spec = self
             .func_map
             .get(&func_id)
-            .ok_or(PkgError::MissingExportedFunc(func_id))?;
+            .ok_or(PkgError::MissingExportedFunc(func_id, "attribute funcs"))?;
 
         let func_unique_id = func_spec.unique_id.to_owned();
         Ok(Some((func_unique_id, inputs)))
@@ -938,6 +1345,14 @@ impl PkgExporter {
         ctx: &DalContext,
         func: &Func,
     ) -> PkgResult<(FuncSpec, bool)> {
+        let is_intrinsic = IntrinsicFunc::maybe_from_str(&func.name).is_some();
+
+        // Funcs may be shared (intrinsics in particular), so only export each one once
+        if let Some(spec) = self.func_map.get(&func.id) {
+            let include = is_intrinsic || spec.data.is_some() || !spec.arguments.is_empty();
+            return Ok((spec.to_owned(), include));
+        }
+
         let (spec, include) = match IntrinsicFunc::maybe_from_str(&func.name) {
             Some(intrinsic) => {
                 let spec = intrinsic.to_spec()?;
@@ -1006,12 +1421,16 @@ impl PkgExporter {
         }
 
         for schema in &schemas {
+            self.check_cancelled()?;
             let (schema_spec, funcs) = self.export_schema(ctx, schema).await?;
 
             func_specs.extend_from_slice(&funcs);
             schema_specs.push(schema_spec);
         }
 
+        let mut seen_unique_ids = HashSet::new();
+        func_specs.retain(|spec| seen_unique_ids.insert(spec.unique_id.clone()));
+
         Ok((
             func_specs,
             head_funcs,
@@ -1025,7 +1444,7 @@ impl PkgExporter {
         let mut pkg_spec_builder = PkgSpec::builder();
         pkg_spec_builder
             .name(&self.name)
-            .kind(self.kind)
+            .kind(SiPkgKind::Module)
             .version(&self.version)
             .created_by(&self.created_by);
 
@@ -1041,16 +1460,73 @@ impl PkgExporter {
             pkg_spec_builder.description(description);
         }
 
-        match self.kind {
-            SiPkgKind::Module => {
-                let (funcs, _, schemas, _, _) = self.export_change_set(ctx).await?;
-                pkg_spec_builder.funcs(funcs);
-                pkg_spec_builder.schemas(schemas);
+        let (funcs, _, schemas, _, _) = self.export_change_set(ctx).await?;
+        pkg_spec_builder.funcs(funcs);
+        pkg_spec_builder.schemas(schemas);
+
+        let spec = pkg_spec_builder.build()?;
+        Self::validate_spec(&spec)?;
+        spec.validate().map_err(PkgError::InvalidPkgSpec)?;
+
+        Ok(spec)
+    }
+
+    /// Checks that every func referenced by the leaf, action, authentication, management, and si
+    /// prop funcs of the spec's variants is declared in the spec's funcs, so that a broken export is caught
+    /// here rather than when the package is imported.
+    pub fn validate_spec(spec: &PkgSpec) -> PkgResult<()> {
+        let func_unique_ids: HashSet<&str> = spec
+            .funcs
+            .iter()
+            .map(|func| func.unique_id.as_str())
+            .collect();
+
+        for schema in &spec.schemas {
+            for variant in &schema.variants {
+                let references = variant
+                    .leaf_functions
+                    .iter()
+                    .map(|func| ("leaf function", func.func_unique_id.as_str()))
+                    .chain(
+                        variant
+                            .action_funcs
+                            .iter()
+                            .map(|func| ("action func", func.func_unique_id.as_str())),
+                    )
+                    .chain(
+                        variant
+                            .auth_funcs
+                            .iter()
+                            .map(|func| ("authentication func", func.func_unique_id.as_str())),
+                    )
+                    .chain(
+                        variant
+                            .management_funcs
+                            .iter()
+                            .map(|func| ("management func", func.func_unique_id.as_str())),
+                    )
+                    .chain(
+                        variant
+                            .si_prop_funcs
+                            .iter()
+                            .map(|func| ("si prop func", func.func_unique_id.as_str())),
+                    );
+
+                for (kind, unique_id) in references {
+                    if !func_unique_ids.contains(unique_id) {
+                        return Err(PkgError::DanglingFuncReference {
+                            unique_id: unique_id.to_owned(),
+                            referenced_by: format!(
+                                "{kind} of {} variant {}",
+                                schema.name, variant.version
+                            ),
+                        });
+                    }
+                }
             }
-            SiPkgKind::WorkspaceBackup => return Err(PkgError::WorkspaceExportNotSupported()),
         }
 
-        Ok(pkg_spec_builder.build()?)
+        Ok(())
     }
 
     pub async fn export(&mut self, ctx: &DalContext) -> PkgResult<SiPkg> {
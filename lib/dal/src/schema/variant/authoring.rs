@@ -109,6 +109,58 @@ pub enum VariantAuthoringError {
 
 type VariantAuthoringResult<T> = Result<T, VariantAuthoringError>;
 
+impl VariantAuthoringError {
+    /// Returns structured diagnostics if this error was caused by the asset func failing to
+    /// compile or execute.
+    pub fn diagnostics(&self) -> Option<Vec<AssetFuncDiagnostic>> {
+        match self {
+            Self::FuncExecutionFailure(message) => {
+                Some(vec![AssetFuncDiagnostic::from_error_message(message)])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A problem reported while compiling or executing an asset func, located in its code if possible.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetFuncDiagnostic {
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+impl AssetFuncDiagnostic {
+    /// Builds a diagnostic from an asset func error message, using its first non-empty line as
+    /// the message and the first `<source>:<line>:<column>` location in it (if any).
+    pub fn from_error_message(error_message: &str) -> Self {
+        let message = error_message
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or(error_message)
+            .to_string();
+        let location = error_message.lines().find_map(parse_source_location);
+
+        Self {
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message,
+        }
+    }
+}
+
+fn parse_source_location(text: &str) -> Option<(u32, u32)> {
+    let mut parts = text.trim().trim_end_matches(')').rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    // There must be a source before the line and column for this to be a location
+    parts.next().filter(|source| !source.is_empty())?;
+
+    Some((line, column))
+}
+
 const DEFAULT_ASSET_CODE: &str = r#"function main() {
   const asset = new AssetBuilder();
   return asset.build();
@@ -936,3 +988,38 @@ fn generate_scaffold_func_name(name: impl AsRef<str>) -> String {
     let generated_name = format!("{}Scaffold_{}", name.as_ref().to_case(Case::Camel), version);
     generated_name
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_from_error_message_with_location() {
+        let diagnostic = AssetFuncDiagnostic::from_error_message(
+            "ReferenceError: AssetBuildr is not defined\n    at main (file:///tmp/asset.ts:2:17)",
+        );
+
+        assert_eq!(
+            AssetFuncDiagnostic {
+                line: Some(2),
+                column: Some(17),
+                message: "ReferenceError: AssetBuildr is not defined".to_string(),
+            },
+            diagnostic
+        );
+    }
+
+    #[test]
+    fn diagnostic_from_error_message_without_location() {
+        let diagnostic = AssetFuncDiagnostic::from_error_message("definition cannot be undefined");
+
+        assert_eq!(
+            AssetFuncDiagnostic {
+                line: None,
+                column: None,
+                message: "definition cannot be undefined".to_string(),
+            },
+            diagnostic
+        );
+    }
+}
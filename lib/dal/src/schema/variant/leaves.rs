@@ -147,13 +147,7 @@ impl From<PkgLeafInputLocation> for LeafInputLocation {
 
 impl LeafInputLocation {
     pub fn arg_name(&self) -> &'static str {
-        match self {
-            LeafInputLocation::Code => "code",
-            LeafInputLocation::Domain => "domain",
-            LeafInputLocation::Resource => "resource",
-            LeafInputLocation::DeletedAt => "deleted_at",
-            LeafInputLocation::Secrets => "secrets",
-        }
+        si_frontend_types::LeafInputLocation::from(*self).arg_name()
     }
 
     pub fn prop_path(&self) -> PropPath {
@@ -161,14 +155,7 @@ impl LeafInputLocation {
     }
 
     pub fn maybe_from_arg_name(arg_name: impl AsRef<str>) -> Option<Self> {
-        Some(match arg_name.as_ref() {
-            "domain" => LeafInputLocation::Domain,
-            "code" => LeafInputLocation::Code,
-            "resource" => LeafInputLocation::Resource,
-            "deleted_at" => LeafInputLocation::DeletedAt,
-            "secrets" => LeafInputLocation::Secrets,
-            _ => return None,
-        })
+        si_frontend_types::LeafInputLocation::maybe_from_arg_name(arg_name).map(Into::into)
     }
 
     pub fn arg_kind(&self) -> FuncArgumentKind {
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -12,14 +13,25 @@ use crate::{
     Timestamp, TransactionsError, WorkspacePk,
 };
 
+pub mod session;
+
 const USER_GET_BY_PK: &str = include_str!("queries/user/get_by_pk.sql");
-const USER_LIST_FOR_WORKSPACE: &str = include_str!("queries/user/list_members_for_workspace.sql");
+const USER_LIST_FOR_WORKSPACE_PAGINATED: &str =
+    include_str!("queries/user/list_members_for_workspace_paginated.sql");
+
+/// The minimum number of seconds between two updates of a user's last activity.
+pub const LAST_ACTIVE_THROTTLE_SECONDS: i64 = 5 * 60;
+
+/// The most members [`User::list_members_for_workspace`] will return for a single workspace.
+pub const MAX_WORKSPACE_MEMBERS: i64 = 100_000;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum UserError {
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("invalid pagination: limit {0} and offset {1} must not be negative")]
+    InvalidPagination(i64, i64),
     #[error("failed to join long lived async task; bug!")]
     Join(#[from] JoinError),
     #[error("nats txn error: {0}")]
@@ -32,6 +44,8 @@ pub enum UserError {
     Pg(#[from] PgError),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
 }
@@ -40,6 +54,18 @@ pub type UserResult<T> = Result<T, UserError>;
 
 pub use si_id::UserPk;
 
+/// The role a [`User`] has in a workspace, as reported by the auth API.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, strum::Display, strum::EnumString,
+)]
+#[strum(serialize_all = "UPPERCASE")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WorkspaceRole {
+    Approver,
+    Editor,
+    Owner,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct User {
     pk: UserPk,
@@ -47,6 +73,8 @@ pub struct User {
     email: String,
     // TODO: should be serialized in api as camelCase
     picture_url: Option<String>,
+    #[serde(default)]
+    last_active_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -59,6 +87,23 @@ impl User {
     standard_model_accessor_ro!(name, String);
     standard_model_accessor_ro!(email, String);
 
+    /// Returns when the user last authenticated, if ever recorded.
+    pub fn last_active_at(&self) -> Option<DateTime<Utc>> {
+        self.last_active_at
+    }
+
+    /// Indicates whether the user's last activity is older than
+    /// [`LAST_ACTIVE_THROTTLE_SECONDS`] (or was never recorded), i.e. whether it is worth
+    /// recording again.
+    pub fn is_activity_stale(&self) -> bool {
+        self.last_active_at.map_or(true, |last_active_at| {
+            Utc::now()
+                .signed_duration_since(last_active_at)
+                .num_seconds()
+                >= LAST_ACTIVE_THROTTLE_SECONDS
+        })
+    }
+
     pub async fn new(
         ctx: &DalContext,
         pk: UserPk,
@@ -137,6 +182,36 @@ impl User {
         Ok(())
     }
 
+    /// Records that the user has just authenticated. Updates are throttled to at most one every
+    /// [`LAST_ACTIVE_THROTTLE_SECONDS`]; check [`Self::is_activity_stale`] first to avoid the
+    /// write (and commit) entirely.
+    ///
+    /// Returns the updated timestamp if a write happened.
+    pub async fn record_activity(
+        ctx: &DalContext,
+        pk: UserPk,
+    ) -> UserResult<Option<DateTime<Utc>>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE users SET last_active_at = CLOCK_TIMESTAMP()
+                    WHERE pk = $1 AND (
+                        last_active_at IS NULL
+                        OR last_active_at < CLOCK_TIMESTAMP() - make_interval(secs => $2)
+                    )
+                    RETURNING last_active_at",
+                &[&pk, &(LAST_ACTIVE_THROTTLE_SECONDS as f64)],
+            )
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(row.try_get("last_active_at")?)),
+            None => Ok(None),
+        }
+    }
+
     pub async fn is_first_user(&self, ctx: &DalContext) -> UserResult<bool> {
         let row = ctx
             .txns()
@@ -170,25 +245,94 @@ impl User {
         Ok(())
     }
 
+    /// Lists the members of a workspace, ordered by their pk, up to [`MAX_WORKSPACE_MEMBERS`].
     pub async fn list_members_for_workspace(
         ctx: &DalContext,
         workspace_pk: String,
     ) -> UserResult<Vec<Self>> {
+        let (users, has_more) =
+            Self::list_members_for_workspace_paginated(ctx, workspace_pk, MAX_WORKSPACE_MEMBERS, 0)
+                .await?;
+        if has_more {
+            warn!(
+                "workspace has more than {} members, only the first were listed",
+                MAX_WORKSPACE_MEMBERS
+            );
+        }
+
+        Ok(users)
+    }
+
+    /// Lists a page of at most `limit` members of a workspace, ordered by their pk, along with
+    /// whether any members remain after the page. Negative limits and offsets are rejected.
+    pub async fn list_members_for_workspace_paginated(
+        ctx: &DalContext,
+        workspace_pk: String,
+        limit: i64,
+        offset: i64,
+    ) -> UserResult<(Vec<Self>, bool)> {
+        let (members, has_more) =
+            Self::list_members_with_roles_for_workspace(ctx, workspace_pk, limit, offset).await?;
+
+        Ok((
+            members.into_iter().map(|(user, _)| user).collect(),
+            has_more,
+        ))
+    }
+
+    /// Lists a page of members of a workspace like [`Self::list_members_for_workspace_paginated`],
+    /// along with each member's role in it (if known).
+    pub async fn list_members_with_roles_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: String,
+        limit: i64,
+        offset: i64,
+    ) -> UserResult<(Vec<(Self, Option<WorkspaceRole>)>, bool)> {
+        if limit < 0 || offset < 0 {
+            return Err(UserError::InvalidPagination(limit, offset));
+        }
+
+        // Fetch one past the page to find out whether there is another page
         let rows = ctx
             .txns()
             .await?
             .pg()
-            .query(USER_LIST_FOR_WORKSPACE, &[&workspace_pk])
+            .query(
+                USER_LIST_FOR_WORKSPACE_PAGINATED,
+                &[&workspace_pk, &(limit + 1), &offset],
+            )
             .await?;
 
-        let mut users: Vec<User> = Vec::new();
-        for row in rows.into_iter() {
+        let has_more = rows.len() as i64 > limit;
+        let mut members = Vec::new();
+        for row in rows.into_iter().take(limit as usize) {
             let json: serde_json::Value = row.try_get("object")?;
-            let object = serde_json::from_value(json)?;
-            users.push(object);
+            let user = serde_json::from_value(json)?;
+            let role: Option<String> = row.try_get("role")?;
+            let role = role.as_deref().map(str::parse).transpose()?;
+            members.push((user, role));
         }
 
-        Ok(users)
+        Ok((members, has_more))
+    }
+
+    /// Sets the role of a member of a workspace.
+    pub async fn set_workspace_role(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+        role: WorkspaceRole,
+    ) -> UserResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE user_belongs_to_workspaces SET role = $3, updated_at = CLOCK_TIMESTAMP()
+                    WHERE user_pk = $1 AND workspace_pk = $2",
+                &[&user_pk, &workspace_pk, &role.to_string()],
+            )
+            .await?;
+        Ok(())
     }
 }
 
@@ -0,0 +1,243 @@
+//! This module contains the ability to track and revoke the sessions (i.e. access tokens) that a
+//! [`User`](crate::User) has used to talk to SI.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_id::{UserPk, UserSessionId, WorkspacePk};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, TransactionsError};
+
+/// The minimum number of seconds between two updates of a session's "last seen" timestamp. This
+/// keeps us from writing to the database on every single request.
+pub const LAST_SEEN_THROTTLE_SECONDS: i64 = 60;
+
+#[allow(missing_docs)]
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum UserSessionError {
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("session not found for user {1}: {0}")]
+    SessionNotFound(UserSessionId, UserPk),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+type Result<T> = std::result::Result<T, UserSessionError>;
+
+/// A session corresponds to a single access token that a [`User`](crate::User) has used for a
+/// given workspace. The raw token is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSession {
+    id: UserSessionId,
+    created_at: DateTime<Utc>,
+    user_pk: UserPk,
+    workspace_pk: WorkspacePk,
+    token_hash: String,
+    issued_at: Option<DateTime<Utc>>,
+    last_seen_at: DateTime<Utc>,
+    client: Option<String>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for UserSession {
+    type Error = UserSessionError;
+
+    fn try_from(value: PgRow) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.try_get("id")?,
+            created_at: value.try_get("created_at")?,
+            user_pk: value.try_get("user_pk")?,
+            workspace_pk: value.try_get("workspace_pk")?,
+            token_hash: value.try_get("token_hash")?,
+            issued_at: value.try_get("issued_at")?,
+            last_seen_at: value.try_get("last_seen_at")?,
+            client: value.try_get("client")?,
+            revoked_at: value.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl UserSession {
+    /// Hashes a raw access token so that it can be used to find its corresponding session.
+    pub fn hash_token(raw_token: impl AsRef<str>) -> String {
+        blake3::hash(raw_token.as_ref().as_bytes())
+            .to_hex()
+            .to_string()
+    }
+
+    /// Records that a raw access token was used to log in, creating its session if it does not
+    /// exist yet.
+    ///
+    /// The "last seen" timestamp and client are only refreshed if the session was last seen more
+    /// than [`LAST_SEEN_THROTTLE_SECONDS`] ago.
+    #[instrument(name = "user.session.record", level = "debug", skip_all)]
+    pub async fn record(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+        raw_token: impl AsRef<str>,
+        issued_at: Option<DateTime<Utc>>,
+        client: Option<String>,
+    ) -> Result<Self> {
+        let token_hash = Self::hash_token(raw_token);
+
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .execute(
+                "INSERT INTO user_sessions (user_pk, workspace_pk, token_hash, issued_at, client)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (token_hash) DO UPDATE
+                    SET last_seen_at = CLOCK_TIMESTAMP(), client = EXCLUDED.client
+                    WHERE user_sessions.last_seen_at
+                        < CLOCK_TIMESTAMP() - make_interval(secs => $6)",
+                &[
+                    &user_pk,
+                    &workspace_pk,
+                    &token_hash,
+                    &issued_at,
+                    &client,
+                    &(LAST_SEEN_THROTTLE_SECONDS as f64),
+                ],
+            )
+            .await?;
+        let row = txns
+            .pg()
+            .query_one(
+                "SELECT * FROM user_sessions WHERE token_hash = $1",
+                &[&token_hash],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Indicates whether the session for a raw access token has been revoked. Tokens without a
+    /// recorded session (i.e. never used to log in) are not revoked.
+    #[instrument(name = "user.session.is_token_revoked", level = "debug", skip_all)]
+    pub async fn is_token_revoked(ctx: &DalContext, raw_token: impl AsRef<str>) -> Result<bool> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT revoked_at IS NOT NULL AS revoked FROM user_sessions WHERE token_hash = $1",
+                &[&Self::hash_token(raw_token)],
+            )
+            .await?;
+
+        match maybe_row {
+            Some(row) => Ok(row.try_get("revoked")?),
+            None => Ok(false),
+        }
+    }
+
+    /// Refreshes the "last seen" timestamp of the session for a raw access token, if it has one.
+    /// Callers are expected to throttle this themselves.
+    #[instrument(name = "user.session.touch", level = "debug", skip_all)]
+    pub async fn touch(ctx: &DalContext, raw_token: impl AsRef<str>) -> Result<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE user_sessions SET last_seen_at = CLOCK_TIMESTAMP()
+                    WHERE token_hash = $1 AND revoked_at IS NULL",
+                &[&Self::hash_token(raw_token)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Lists all sessions that have not been revoked for a given user in a given workspace, most
+    /// recently seen first.
+    #[instrument(name = "user.session.list_active", level = "debug", skip_all)]
+    pub async fn list_active(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> Result<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM user_sessions
+                    WHERE user_pk = $1 AND workspace_pk = $2 AND revoked_at IS NULL
+                    ORDER BY last_seen_at DESC",
+                &[&user_pk, &workspace_pk],
+            )
+            .await?;
+
+        let mut sessions = Vec::with_capacity(rows.len());
+        for row in rows {
+            sessions.push(Self::try_from(row)?);
+        }
+        Ok(sessions)
+    }
+
+    /// Revokes a session belonging to the given user. Requests made with the corresponding token
+    /// will be rejected afterwards.
+    #[instrument(name = "user.session.revoke", level = "info", skip_all)]
+    pub async fn revoke(ctx: &DalContext, user_pk: UserPk, id: UserSessionId) -> Result<Self> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE user_sessions SET revoked_at = COALESCE(revoked_at, CLOCK_TIMESTAMP())
+                    WHERE id = $1 AND user_pk = $2
+                    RETURNING *",
+                &[&id, &user_pk],
+            )
+            .await?;
+
+        match maybe_row {
+            Some(row) => Self::try_from(row),
+            None => Err(UserSessionError::SessionNotFound(id, user_pk)),
+        }
+    }
+
+    /// Returns the ID of the session.
+    pub fn id(&self) -> UserSessionId {
+        self.id
+    }
+
+    /// Returns the user that the session belongs to.
+    pub fn user_pk(&self) -> UserPk {
+        self.user_pk
+    }
+
+    /// Returns the workspace that the session was used for.
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    /// Returns when the token was issued, if known.
+    pub fn issued_at(&self) -> Option<DateTime<Utc>> {
+        self.issued_at
+    }
+
+    /// Returns when the session was last seen (precise to
+    /// [`LAST_ACTIVE_THROTTLE_SECONDS`](crate::user::LAST_ACTIVE_THROTTLE_SECONDS)).
+    pub fn last_seen_at(&self) -> DateTime<Utc> {
+        self.last_seen_at
+    }
+
+    /// Returns the approximate client (e.g. the user agent) that last used the session.
+    pub fn client(&self) -> Option<&str> {
+        self.client.as_deref()
+    }
+
+    /// Returns when the session was revoked, if it was.
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    /// Indicates whether the session has been revoked.
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
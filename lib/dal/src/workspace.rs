@@ -25,9 +25,10 @@ use crate::workspace_integrations::{WorkspaceIntegration, WorkspaceIntegrationsE
 use crate::workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants;
 use crate::workspace_snapshot::WorkspaceSnapshotError;
 use crate::{
-    standard_model, standard_model_accessor_ro, BuiltinsError, DalContext, HistoryActor,
-    HistoryEvent, HistoryEventError, KeyPairError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, User, UserError, UserPk, WorkspaceSnapshot, WorkspaceSnapshotGraph,
+    standard_model, standard_model_accessor_ro, BuiltinsError, ChangeSetStatus, DalContext,
+    HistoryActor, HistoryEvent, HistoryEventError, KeyPairError, StandardModelError, Tenancy,
+    Timestamp, TransactionsError, User, UserError, UserPk, WorkspaceSnapshot,
+    WorkspaceSnapshotGraph,
 };
 
 pub use si_id::WorkspaceId;
@@ -57,6 +58,10 @@ pub enum WorkspaceError {
     ChangeSet(#[from] ChangeSetError),
     #[error("could not find default change set {1} for workspace {0}")]
     DefaultChangeSetNotFound(WorkspacePk, ChangeSetId),
+    #[error("cannot delete workspace {0} with pending change sets: {1:?}")]
+    DeleteWithPendingChangeSets(WorkspacePk, Vec<ChangeSetId>),
+    #[error("cannot delete workspace {0} that has not been archived")]
+    DeleteWithoutArchive(WorkspacePk),
     #[error("Trying to export from system actor. This can only be done by a user actor")]
     ExportingFromSystemActor,
     #[error(transparent)]
@@ -106,6 +111,10 @@ pub struct Workspace {
     token: Option<String>,
     snapshot_version: WorkspaceSnapshotGraphDiscriminants,
     component_concurrency_limit: Option<i32>,
+    #[serde(default)]
+    archived_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    description: Option<String>,
 }
 
 impl TryFrom<PgRow> for Workspace {
@@ -124,6 +133,8 @@ impl TryFrom<PgRow> for Workspace {
             token: row.try_get("token")?,
             snapshot_version: WorkspaceSnapshotGraphDiscriminants::from_str(&snapshot_version)?,
             component_concurrency_limit: row.try_get("component_concurrency_limit")?,
+            archived_at: row.try_get("archived_at")?,
+            description: row.try_get("description")?,
         })
     }
 }
@@ -742,4 +753,133 @@ impl Workspace {
     pub fn timestamp(&self) -> &Timestamp {
         &self.timestamp
     }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Sets (or clears, if `None`) the description of the [`Workspace`].
+    pub async fn set_description(
+        &mut self,
+        ctx: &DalContext,
+        description: Option<String>,
+    ) -> WorkspaceResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE workspaces SET description = $2, updated_at = CLOCK_TIMESTAMP() WHERE pk = $1",
+                &[&self.pk, &description],
+            )
+            .await?;
+        self.description = description;
+
+        Ok(())
+    }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// Archives the [`Workspace`], which is a prerequisite for [deleting](Self::delete) it.
+    pub async fn archive(&mut self, ctx: &DalContext) -> WorkspaceResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "UPDATE workspaces SET archived_at = COALESCE(archived_at, CLOCK_TIMESTAMP())
+                    WHERE pk = $1
+                    RETURNING archived_at",
+                &[&self.pk],
+            )
+            .await?;
+
+        self.archived_at = row.try_get("archived_at")?;
+
+        Ok(())
+    }
+
+    /// Deletes the [`Workspace`] along with its change sets (and their idempotency keys), members,
+    /// integrations, sessions and key pairs. Changes are made in the context's transaction, so
+    /// nothing is removed until it is committed.
+    ///
+    /// Refuses to delete the workspace unless it has been [archived](Self::archive) and it has no
+    /// pending change sets besides its default one.
+    #[instrument(name = "workspace.delete", level = "info", skip(ctx))]
+    pub async fn delete(ctx: &DalContext, pk: WorkspacePk) -> WorkspaceResult<()> {
+        let workspace = Self::get_by_pk_or_error(ctx, pk).await?;
+        if !workspace.is_archived() {
+            return Err(WorkspaceError::DeleteWithoutArchive(pk));
+        }
+
+        let txns = ctx.txns().await?;
+
+        let pending_change_set_ids: Vec<ChangeSetId> = txns
+            .pg()
+            .query(
+                "SELECT id FROM change_set_pointers
+                    WHERE workspace_id = $1 AND id != $2 AND status IN ($3, $4, $5, $6, $7)",
+                &[
+                    &pk,
+                    &workspace.default_change_set_id,
+                    &ChangeSetStatus::Open.to_string(),
+                    &ChangeSetStatus::NeedsApproval.to_string(),
+                    &ChangeSetStatus::NeedsAbandonApproval.to_string(),
+                    &ChangeSetStatus::Approved.to_string(),
+                    &ChangeSetStatus::Rejected.to_string(),
+                ],
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.try_get("id"))
+            .collect::<Result<_, _>>()?;
+        if !pending_change_set_ids.is_empty() {
+            return Err(WorkspaceError::DeleteWithPendingChangeSets(
+                pk,
+                pending_change_set_ids,
+            ));
+        }
+
+        let pg = txns.pg();
+        pg.execute(
+            "DELETE FROM change_set_approvals WHERE change_set_id IN (
+                SELECT id FROM change_set_pointers WHERE workspace_id = $1
+            )",
+            &[&pk],
+        )
+        .await?;
+        pg.execute(
+            "DELETE FROM change_set_idempotency_keys WHERE workspace_id = $1",
+            &[&pk],
+        )
+        .await?;
+        pg.execute(
+            "DELETE FROM change_set_pointers WHERE workspace_id = $1",
+            &[&pk],
+        )
+        .await?;
+        pg.execute(
+            "DELETE FROM user_belongs_to_workspaces WHERE workspace_pk = $1",
+            &[&pk],
+        )
+        .await?;
+        pg.execute("DELETE FROM user_sessions WHERE workspace_pk = $1", &[&pk])
+            .await?;
+        pg.execute(
+            "DELETE FROM workspace_integrations WHERE workspace_pk = $1",
+            &[&pk],
+        )
+        .await?;
+        pg.execute("DELETE FROM key_pairs WHERE workspace_pk = $1", &[&pk])
+            .await?;
+        pg.execute("DELETE FROM workspaces WHERE pk = $1", &[&pk])
+            .await?;
+
+        Ok(())
+    }
 }
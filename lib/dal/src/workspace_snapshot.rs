@@ -169,6 +169,13 @@ impl WorkspaceSnapshotError {
             Self::WorkspaceSnapshotGraph(WorkspaceSnapshotGraphError::NodeWithIdNotFound(_,),)
         )
     }
+
+    pub fn is_create_graph_cycle(&self) -> bool {
+        matches!(
+            self,
+            Self::WorkspaceSnapshotGraph(WorkspaceSnapshotGraphError::CreateGraphCycle)
+        )
+    }
 }
 
 pub type WorkspaceSnapshotResult<T> = Result<T, WorkspaceSnapshotError>;
@@ -759,6 +766,9 @@ impl WorkspaceSnapshot {
         workspace_id: WorkspacePk,
         changes: &[Change],
     ) -> WorkspaceSnapshotResult<Vec<ApprovalRequirement>> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
         Ok(self
             .working_copy()
             .await
@@ -1,7 +1,9 @@
 //! This module contains graph-specific functionality related to approvals.
 
+use std::collections::HashMap;
+
 use si_events::workspace_snapshot::EntityKind;
-use si_id::EntityId;
+use si_id::{EntityId, WorkspacePk};
 
 #[derive(Debug)]
 pub struct ApprovalRequirement {
@@ -17,3 +19,86 @@ pub struct ApprovalRequirementLookupGroup {
     pub object_id: String,
     pub permission: String,
 }
+
+/// A virtual approval requirement that is not tied to a specific entity. It is used to synthesize
+/// an [`ApprovalRequirement`] for an entity when no explicit definition exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRequirementRule {
+    /// The number of approvals required.
+    pub number: usize,
+    /// The permission an approver must hold on the workspace.
+    pub permission: String,
+}
+
+impl ApprovalRequirementRule {
+    /// Creates a new rule requiring `number` approvals from users holding `permission` on the
+    /// workspace.
+    pub fn new(number: usize, permission: impl Into<String>) -> Self {
+        Self {
+            number,
+            permission: permission.into(),
+        }
+    }
+
+    /// Synthesizes an [`ApprovalRequirement`] for the given entity using this rule.
+    pub fn requirement_for(
+        &self,
+        workspace_id: WorkspacePk,
+        entity_id: EntityId,
+        entity_kind: EntityKind,
+    ) -> ApprovalRequirement {
+        ApprovalRequirement {
+            entity_id,
+            entity_kind,
+            number: self.number,
+            lookup_groups: vec![ApprovalRequirementLookupGroup {
+                object_type: "workspace".to_string(),
+                object_id: workspace_id.to_string(),
+                permission: self.permission.clone(),
+            }],
+        }
+    }
+}
+
+/// The default [`ApprovalRequirementRules`](ApprovalRequirementRule) for each [`EntityKind`],
+/// consulted when no explicit approval requirement exists for a changed entity.
+#[derive(Debug, Clone)]
+pub struct DefaultApprovalRequirements {
+    rules: HashMap<EntityKind, ApprovalRequirementRule>,
+}
+
+impl Default for DefaultApprovalRequirements {
+    /// By default, every change to a [`SchemaVariant`](EntityKind::SchemaVariant) requires one
+    /// approval from a user who can approve on the workspace.
+    fn default() -> Self {
+        Self::empty().with_rule(
+            EntityKind::SchemaVariant,
+            ApprovalRequirementRule::new(1, "approve"),
+        )
+    }
+}
+
+impl DefaultApprovalRequirements {
+    /// Creates a configuration without any default rules.
+    pub fn empty() -> Self {
+        Self {
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Sets the default rule for the given [`EntityKind`], replacing any existing rule.
+    pub fn with_rule(mut self, entity_kind: EntityKind, rule: ApprovalRequirementRule) -> Self {
+        self.rules.insert(entity_kind, rule);
+        self
+    }
+
+    /// Returns whether there are no default rules configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the default rule for the given [`EntityKind`], if one exists.
+    pub fn rule_for(&self, entity_kind: EntityKind) -> Option<&ApprovalRequirementRule> {
+        self.rules.get(&entity_kind)
+    }
+}
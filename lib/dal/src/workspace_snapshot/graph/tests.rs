@@ -7,6 +7,7 @@ use crate::{
     EdgeWeight, EdgeWeightKind, PropKind,
 };
 
+mod approval;
 mod detect_changes;
 mod detect_updates;
 mod exclusive_outgoing_edges;
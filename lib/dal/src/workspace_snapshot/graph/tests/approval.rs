@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod test {
+    use si_events::{workspace_snapshot::EntityKind, ContentHash};
+    use si_id::WorkspacePk;
+
+    use crate::workspace_snapshot::{
+        graph::{
+            approval::{ApprovalRequirementRule, DefaultApprovalRequirements},
+            detector::Change,
+        },
+        node_weight::NodeWeight,
+    };
+    use crate::WorkspaceSnapshotGraphVCurrent;
+
+    #[test]
+    fn default_requirements_for_entity_kinds() {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()
+            .expect("could not create a new graph for unit tests");
+
+        let schema_variant_id = graph.generate_ulid().expect("generate ulid");
+        let schema_variant_node_weight = NodeWeight::new_schema_variant(
+            schema_variant_id,
+            graph.generate_ulid().expect("generate ulid"),
+            false,
+            ContentHash::new(b"schema variant"),
+        );
+        let component_id = graph.generate_ulid().expect("generate ulid");
+        let component_node_weight = NodeWeight::new_component(
+            component_id,
+            graph.generate_ulid().expect("generate ulid"),
+            ContentHash::new(b"component"),
+        );
+        let changes = vec![
+            Change {
+                id: schema_variant_id,
+                merkle_tree_hash: schema_variant_node_weight.merkle_tree_hash(),
+            },
+            Change {
+                id: component_id,
+                merkle_tree_hash: component_node_weight.merkle_tree_hash(),
+            },
+        ];
+        graph
+            .add_or_replace_node(schema_variant_node_weight)
+            .expect("add schema variant node");
+        graph
+            .add_or_replace_node(component_node_weight)
+            .expect("add component node");
+
+        let workspace_id = WorkspacePk::new();
+
+        // Out of the box, only schema variants require approval.
+        let requirements = graph
+            .approval_requirements_for_changes(workspace_id, &changes)
+            .expect("get approval requirements");
+        assert_eq!(1, requirements.len());
+        assert_eq!(EntityKind::SchemaVariant, requirements[0].entity_kind);
+        assert_eq!(schema_variant_id, requirements[0].entity_id.into_inner());
+
+        // Configure a default for components as well and ensure it is synthesized.
+        let defaults = DefaultApprovalRequirements::default().with_rule(
+            EntityKind::Component,
+            ApprovalRequirementRule::new(2, "approve"),
+        );
+        let requirements = graph
+            .approval_requirements_for_changes_with_defaults(workspace_id, &changes, &defaults)
+            .expect("get approval requirements");
+        assert_eq!(2, requirements.len());
+        let component_requirement = requirements
+            .iter()
+            .find(|requirement| requirement.entity_kind == EntityKind::Component)
+            .expect("component requirement should be synthesized");
+        assert_eq!(component_id, component_requirement.entity_id.into_inner());
+        assert_eq!(2, component_requirement.number);
+        assert_eq!(
+            workspace_id.to_string(),
+            component_requirement.lookup_groups[0].object_id
+        );
+    }
+    #[test]
+    fn no_requirements_without_changes() {
+        let graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()
+            .expect("could not create a new graph for unit tests");
+
+        let requirements = graph
+            .approval_requirements_for_changes(WorkspacePk::new(), &[])
+            .expect("get approval requirements");
+        assert!(requirements.is_empty());
+    }
+
+    #[test]
+    fn repeated_changes_yield_a_requirement_each() {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests()
+            .expect("could not create a new graph for unit tests");
+
+        let schema_variant_id = graph.generate_ulid().expect("generate ulid");
+        let schema_variant_node_weight = NodeWeight::new_schema_variant(
+            schema_variant_id,
+            graph.generate_ulid().expect("generate ulid"),
+            false,
+            ContentHash::new(b"schema variant"),
+        );
+        let changes = vec![
+            Change {
+                id: schema_variant_id,
+                merkle_tree_hash: schema_variant_node_weight.merkle_tree_hash(),
+            },
+            Change {
+                id: schema_variant_id,
+                merkle_tree_hash: schema_variant_node_weight.merkle_tree_hash(),
+            },
+        ];
+        graph
+            .add_or_replace_node(schema_variant_node_weight)
+            .expect("add schema variant node");
+
+        let requirements = graph
+            .approval_requirements_for_changes(WorkspacePk::new(), &changes)
+            .expect("get approval requirements");
+        assert_eq!(2, requirements.len());
+        assert!(requirements
+            .iter()
+            .all(|requirement| requirement.entity_kind == EntityKind::SchemaVariant));
+    }
+}
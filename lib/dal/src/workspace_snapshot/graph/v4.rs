@@ -35,7 +35,7 @@ use crate::{
 };
 
 use super::{
-    approval::{ApprovalRequirement, ApprovalRequirementLookupGroup},
+    approval::{ApprovalRequirement, DefaultApprovalRequirements},
     detector::Change,
     traits::entity_kind::EntityKindExt,
 };
@@ -706,25 +706,38 @@ impl WorkspaceSnapshotGraphV4 {
         workspace_id: WorkspacePk,
         changes: &[Change],
     ) -> WorkspaceSnapshotGraphResult<Vec<ApprovalRequirement>> {
+        self.approval_requirements_for_changes_with_defaults(
+            workspace_id,
+            changes,
+            &DefaultApprovalRequirements::default(),
+        )
+    }
+
+    pub fn approval_requirements_for_changes_with_defaults(
+        &self,
+        workspace_id: WorkspacePk,
+        changes: &[Change],
+        defaults: &DefaultApprovalRequirements,
+    ) -> WorkspaceSnapshotGraphResult<Vec<ApprovalRequirement>> {
+        if changes.is_empty() || defaults.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Large change sets can contain the same entity more than once, so avoid re-walking the
+        // graph for entities we have already seen.
+        let mut entity_kinds: HashMap<EntityId, EntityKind> = HashMap::new();
         let mut requirements = Vec::new();
         for change in changes {
             let entity_id: EntityId = change.id.into();
-
-            // TODO(nick,jacob): handle more than schema variants.
-            if let EntityKind::SchemaVariant = self.get_entity_kind_for_id(entity_id)? {
-                requirements.push(ApprovalRequirement {
-                    // TODO(nick,jacob): handle more than schema variants.
-                    entity_kind: EntityKind::SchemaVariant,
-                    entity_id,
-                    // TODO(nick,jacob): remove hardcoded number requirement.
-                    number: 1,
-                    // TODO(nick,jacob): replace hardcoded relations.
-                    lookup_groups: vec![ApprovalRequirementLookupGroup {
-                        object_type: "workspace".to_string(),
-                        object_id: workspace_id.to_string(),
-                        permission: "approve".to_string(),
-                    }],
-                });
+            let entity_kind = match entity_kinds.entry(entity_id) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => *entry.insert(self.get_entity_kind_for_id(entity_id)?),
+            };
+
+            // There are no explicit approval requirement definitions yet, so every requirement is
+            // synthesized from the default rule for the entity kind.
+            if let Some(rule) = defaults.rule_for(entity_kind) {
+                requirements.push(rule.requirement_for(workspace_id, entity_id, entity_kind));
             }
         }
         Ok(requirements)
@@ -58,4 +58,11 @@ impl SiVersionedNodeWeight for ManagementPrototypeNodeWeight {
             ManagementPrototypeNodeWeight::V1(inner) => inner,
         }
     }
+
+    /// Return the version number of the stored enum variant
+    fn version(&self) -> u32 {
+        match self {
+            ManagementPrototypeNodeWeight::V1(_) => 1,
+        }
+    }
 }
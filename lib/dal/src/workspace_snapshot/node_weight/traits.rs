@@ -63,6 +63,8 @@ pub trait SiVersionedNodeWeight {
     fn inner(&self) -> &Self::Inner;
     /// Return a mutable reference to the most up to date enum variant
     fn inner_mut(&mut self) -> &mut Self::Inner;
+    /// Return the version number of the stored enum variant
+    fn version(&self) -> u32;
 
     fn id(&self) -> Ulid {
         self.inner().id()
@@ -10,8 +10,8 @@ use ulid::Ulid;
 
 use crate::audit_logging::AuditLogsPublishedPayload;
 use crate::change_set::event::{
-    ChangeSetActorPayload, ChangeSetAppliedPayload, ChangeSetMergeVotePayload,
-    ChangeSetRenamePayload, ChangeSetStateChangePayload,
+    ChangeSetAbandonRequestedPayload, ChangeSetActorPayload, ChangeSetAppliedPayload,
+    ChangeSetMergeVotePayload, ChangeSetRenamePayload, ChangeSetStateChangePayload,
 };
 use crate::component::{
     ComponentCreatedPayload, ComponentDeletedPayload, ComponentSetPositionPayload,
@@ -83,6 +83,7 @@ pub enum WsPayload {
     AsyncError(ErrorPayload),
     AsyncFinish(FinishPayload),
     AuditLogsPublished(AuditLogsPublishedPayload),
+    ChangeSetAbandonRequested(ChangeSetAbandonRequestedPayload),
     ChangeSetAbandoned(ChangeSetActorPayload),
     ChangeSetAbandonVote(ChangeSetMergeVotePayload),
     ChangeSetApplied(ChangeSetAppliedPayload),
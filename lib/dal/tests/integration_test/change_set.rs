@@ -3,14 +3,16 @@ use dal::{
     context::TransactionsErrorDiscriminants, DalContext, DalContextBuilder, HistoryActor,
     RequestContext, Workspace, WorkspacePk,
 };
-use dal::{AccessBuilder, ChangeSet, ChangeSetStatus, Component};
+use dal::{AccessBuilder, ChangeSet, ChangeSetError, ChangeSetStatus, Component};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, create_user, ChangeSetTestHelpers,
 };
 use dal_test::test;
 use itertools::Itertools;
 use pretty_assertions_sorted::assert_eq;
+use si_events::ContentHash;
 use std::collections::HashSet;
+use std::time::Duration;
 
 mod approval;
 
@@ -149,6 +151,144 @@ async fn abandon_change_set_and_check_open_change_sets(ctx: &mut DalContext) {
     assert!(!change_set_names.contains(&change_set_name))
 }
 
+#[test]
+async fn fork_from_open_change_set(ctx: &mut DalContext) {
+    let base_change_set_id = ctx.change_set_id();
+
+    let change_set = ChangeSet::fork_from(ctx, base_change_set_id, "branch of a branch")
+        .await
+        .expect("could not fork change set");
+    assert_eq!(Some(base_change_set_id), change_set.base_change_set_id);
+    assert_eq!(ChangeSetStatus::Open, change_set.status);
+
+    ChangeSetTestHelpers::abandon_change_set(ctx)
+        .await
+        .expect("could not abandon change set");
+
+    let result =
+        ChangeSet::fork_from(ctx, base_change_set_id, "branch of an abandoned branch").await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::BaseChangeSetNotOpen(change_set_id, ChangeSetStatus::Abandoned))
+            if change_set_id == base_change_set_id
+    ));
+}
+
+#[test]
+async fn find_change_set_by_idempotency_key(ctx: &mut DalContext) {
+    let ttl = Duration::from_secs(60);
+    let request_hash = ContentHash::new(b"request");
+    assert!(
+        ChangeSet::find_by_idempotency_key(ctx, "retry-me", request_hash, ttl)
+            .await
+            .expect("could not find change set")
+            .is_none()
+    );
+
+    let change_set = ChangeSet::fork_head(ctx, "created once")
+        .await
+        .expect("could not fork change set");
+    change_set
+        .record_idempotency_key(ctx, "retry-me", request_hash, ttl)
+        .await
+        .expect("could not record idempotency key");
+
+    let found = ChangeSet::find_by_idempotency_key(ctx, "retry-me", request_hash, ttl)
+        .await
+        .expect("could not find change set")
+        .expect("change set should be found by its idempotency key");
+    assert_eq!(change_set.id, found.id);
+
+    // Keys are only remembered for the given window
+    assert!(
+        ChangeSet::find_by_idempotency_key(ctx, "retry-me", request_hash, Duration::ZERO)
+            .await
+            .expect("could not find change set")
+            .is_none()
+    );
+
+    // ...after which the key can be taken over by another change set
+    let retried = ChangeSet::fork_head(ctx, "created again")
+        .await
+        .expect("could not fork change set");
+    retried
+        .record_idempotency_key(ctx, "retry-me", request_hash, Duration::ZERO)
+        .await
+        .expect("could not take over expired idempotency key");
+    let found = ChangeSet::find_by_idempotency_key(ctx, "retry-me", request_hash, ttl)
+        .await
+        .expect("could not find change set")
+        .expect("change set should be found by its idempotency key");
+    assert_eq!(retried.id, found.id);
+}
+
+#[test]
+async fn find_change_set_by_idempotency_key_for_different_request(ctx: &mut DalContext) {
+    let ttl = Duration::from_secs(60);
+    let change_set = ChangeSet::fork_head(ctx, "created once")
+        .await
+        .expect("could not fork change set");
+    change_set
+        .record_idempotency_key(ctx, "retry-me", ContentHash::new(b"request"), ttl)
+        .await
+        .expect("could not record idempotency key");
+
+    let result = ChangeSet::find_by_idempotency_key(
+        ctx,
+        "retry-me",
+        ContentHash::new(b"another request"),
+        ttl,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::IdempotencyKeyReused(key)) if key == "retry-me"
+    ));
+}
+
+#[test]
+async fn concurrent_requests_with_same_idempotency_key_create_one_change_set(
+    ctx: &mut DalContext,
+    ctx_builder: DalContextBuilder,
+) {
+    let ttl = Duration::from_secs(60);
+    let request_hash = ContentHash::new(b"request");
+
+    // Mirrors the create change set handler: look the key up, and only fork if nothing was found
+    let find_or_fork = |name: &'static str| {
+        let access_builder = AccessBuilder::new(*ctx.tenancy(), ctx.history_actor().clone(), None);
+        let ctx_builder = ctx_builder.clone();
+        async move {
+            let ctx = ctx_builder
+                .build_head(access_builder)
+                .await
+                .expect("could not build dal context");
+            let change_set =
+                match ChangeSet::find_by_idempotency_key(&ctx, "same-key", request_hash, ttl)
+                    .await
+                    .expect("could not find change set")
+                {
+                    Some(change_set) => change_set,
+                    None => {
+                        let change_set = ChangeSet::fork_head(&ctx, name)
+                            .await
+                            .expect("could not fork change set");
+                        change_set
+                            .record_idempotency_key(&ctx, "same-key", request_hash, ttl)
+                            .await
+                            .expect("could not record idempotency key");
+                        change_set
+                    }
+                };
+            ctx.commit_no_rebase().await.expect("could not commit");
+            change_set.id
+        }
+    };
+
+    let (first, second) = tokio::join!(find_or_fork("first"), find_or_fork("second"));
+    assert_eq!(first, second);
+}
+
 #[test]
 async fn build_from_request_context_limits_to_workspaces_user_has_access_to(
     ctx: &mut DalContext,
@@ -384,6 +524,45 @@ async fn build_from_request_context_allows_change_set_from_workspace_with_access
     assert!(builder_result.is_ok());
 }
 
+#[test]
+async fn restart_abandon_approval_flow(ctx: &mut DalContext) {
+    let new_change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork head");
+    let mut change_set = ChangeSet::get_by_id(ctx, new_change_set.id)
+        .await
+        .expect("could not find change set");
+
+    // There's no flow to restart until one has begun
+    let result = change_set.restart_abandon_approval_flow(ctx).await;
+    assert!(matches!(
+        result,
+        Err(ChangeSetError::AbandonApprovalFlowNotActive(change_set_id))
+            if change_set_id == new_change_set.id
+    ));
+
+    change_set
+        .begin_abandon_approval_flow(ctx)
+        .await
+        .expect("could not begin abandon approval flow");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let old_status = change_set
+        .restart_abandon_approval_flow(ctx)
+        .await
+        .expect("could not restart abandon approval flow");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    assert_eq!(ChangeSetStatus::NeedsAbandonApproval, old_status);
+    let change_set = ChangeSet::get_by_id(ctx, new_change_set.id)
+        .await
+        .expect("could not find change set");
+    assert_eq!(ChangeSetStatus::NeedsAbandonApproval, change_set.status);
+}
+
 #[test]
 async fn change_set_approval_flow(ctx: &mut DalContext) {
     // create a new change set
@@ -3539,3 +3539,120 @@ impl DiagramByKey {
         all
     }
 }
+
+#[test]
+async fn upsert_parents_moves_many_children(ctx: &mut DalContext) {
+    let first_frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "first_frame",
+        ComponentType::ConfigurationFrameUp,
+    )
+    .await
+    .expect("could not create component");
+    let second_frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "second_frame",
+        ComponentType::ConfigurationFrameUp,
+    )
+    .await
+    .expect("could not create component");
+    let first_child = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "first_child",
+    )
+    .await
+    .expect("could not create component");
+    let second_child = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "second_child",
+    )
+    .await
+    .expect("could not create component");
+
+    Frame::upsert_parent(ctx, first_child.id(), first_frame.id())
+        .await
+        .expect("upserted parent");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    // Move one child to a new frame and attach the other, unchanged moves being skipped.
+    Frame::upsert_parents(
+        ctx,
+        vec![
+            (first_child.id(), second_frame.id()),
+            (second_child.id(), second_frame.id()),
+            (second_child.id(), second_frame.id()),
+        ],
+    )
+    .await
+    .expect("upserted parents");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    for child_id in [first_child.id(), second_child.id()] {
+        assert_eq!(
+            Some(second_frame.id()),
+            Component::get_parent_by_id(ctx, child_id)
+                .await
+                .expect("could not get parent")
+        );
+    }
+
+    // A component which isn't a frame can't be a parent.
+    let result = Frame::upsert_parents(ctx, vec![(second_child.id(), first_child.id())]).await;
+    assert!(matches!(result, Err(FrameError::ParentIsNotAFrame(..))));
+}
+
+#[test]
+async fn attaching_an_ancestor_to_its_descendant_is_rejected(ctx: &mut DalContext) {
+    let outer_frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "outer_frame",
+        ComponentType::ConfigurationFrameUp,
+    )
+    .await
+    .expect("could not create component");
+    let inner_frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "inner_frame",
+        ComponentType::ConfigurationFrameUp,
+    )
+    .await
+    .expect("could not create component");
+
+    Frame::upsert_parent(ctx, inner_frame.id(), outer_frame.id())
+        .await
+        .expect("upserted parent");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit");
+
+    let result = Frame::upsert_parent(ctx, outer_frame.id(), inner_frame.id()).await;
+    assert!(matches!(
+        result,
+        Err(FrameError::WouldCreateCycle { child_id, parent_id })
+            if child_id == outer_frame.id() && parent_id == inner_frame.id()
+    ));
+
+    // The graph is left as it was.
+    assert_eq!(
+        Some(outer_frame.id()),
+        Component::get_parent_by_id(ctx, inner_frame.id())
+            .await
+            .expect("could not get parent")
+    );
+    assert_eq!(
+        None,
+        Component::get_parent_by_id(ctx, outer_frame.id())
+            .await
+            .expect("could not get parent")
+    );
+}
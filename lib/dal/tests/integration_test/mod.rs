@@ -24,6 +24,7 @@ mod rebaser;
 mod resource_metadata;
 mod schema;
 mod secret;
+mod user;
 mod validations;
 mod view;
 mod workspace;
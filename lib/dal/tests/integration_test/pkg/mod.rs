@@ -1,9 +1,22 @@
-use dal::pkg::export::PkgExporter;
-use dal::pkg::{import_pkg_from_pkg, ImportOptions};
+use std::sync::{Arc, Mutex};
+
+use dal::pkg::export::{ExportProgress, PkgExporter};
+use dal::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
+use dal::prop::PropPath;
 use dal::schema::variant::authoring::VariantAuthoringClient;
-use dal::{DalContext, FuncBackendKind, FuncBackendResponseType};
+use dal::{
+    AttributeValue, Component, DalContext, FuncBackendKind, FuncBackendResponseType, Prop, Schema,
+    SchemaVariant,
+};
+use dal_test::helpers::{
+    connect_components_with_socket_names, create_component_for_default_schema_name_in_default_view,
+};
 use dal_test::test;
-use si_pkg::{FuncSpec, FuncSpecData, PkgSpec, SchemaSpec, SchemaSpecData, SiPkg};
+use si_pkg::{
+    AttributeValuePath, ComponentSpec, ComponentSpecVariant, FuncSpec, FuncSpecData,
+    ManagementFuncSpec, PkgSpec, SchemaSpec, SchemaSpecData, SiPkg,
+};
+use tokio_util::sync::CancellationToken;
 
 #[test]
 async fn import_pkg_from_pkg_set_latest_default(ctx: &mut DalContext) {
@@ -111,3 +124,501 @@ async fn import_pkg_from_pkg_set_latest_default(ctx: &mut DalContext) {
         Some(variants.pop().expect("should pop"))
     );
 }
+
+#[test]
+async fn export_schema_by_name(ctx: &mut DalContext) {
+    let mut exporter = PkgExporter::new("dummy", "2024-01-01", None::<String>, "tester", vec![]);
+    let pkg = exporter
+        .export_schema_by_name(ctx, vec!["dummy-secret".to_string()])
+        .await
+        .expect("should export schema by name");
+
+    let schemas = pkg.schemas().expect("should get pkg schemas");
+    assert_eq!(1, schemas.len());
+    assert_eq!("dummy-secret", schemas[0].name());
+
+    let mut exporter = PkgExporter::new("dummy", "2024-01-01", None::<String>, "tester", vec![]);
+    let result = exporter
+        .export_schema_by_name(ctx, vec!["Dummy-Secret".to_string()])
+        .await;
+    assert!(matches!(
+        result,
+        Err(PkgError::SchemaNameNotFound(name)) if name == "Dummy-Secret"
+    ));
+}
+
+#[test]
+async fn validate_spec_reports_dangling_func_references(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "dummy-secret")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id());
+    let mut spec = exporter
+        .export_as_spec(ctx)
+        .await
+        .expect("should export a valid spec");
+    PkgExporter::validate_spec(&spec).expect("exported spec should be valid");
+
+    let auth_func_unique_id = spec.schemas[0].variants[0].auth_funcs[0]
+        .func_unique_id
+        .clone();
+    spec.funcs
+        .retain(|func| func.unique_id != auth_func_unique_id);
+
+    let result = PkgExporter::validate_spec(&spec);
+    assert!(matches!(
+        result,
+        Err(PkgError::DanglingFuncReference { unique_id, referenced_by })
+            if unique_id == auth_func_unique_id && referenced_by.starts_with("authentication func")
+    ));
+}
+
+#[test]
+async fn validate_spec_reports_dangling_management_func_references(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "dummy-secret")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id());
+    let mut spec = exporter
+        .export_as_spec(ctx)
+        .await
+        .expect("should export a valid spec");
+
+    let missing_unique_id = "not-a-func".to_string();
+    spec.schemas[0].variants[0].management_funcs.push(
+        ManagementFuncSpec::builder()
+            .func_unique_id(&missing_unique_id)
+            .name("dangling")
+            .build()
+            .expect("should build management func spec"),
+    );
+
+    let result = PkgExporter::validate_spec(&spec);
+    assert!(matches!(
+        result,
+        Err(PkgError::DanglingFuncReference { unique_id, referenced_by })
+            if unique_id == missing_unique_id && referenced_by.starts_with("management func")
+    ));
+}
+
+#[test]
+async fn export_with_progress(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "dummy-secret")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id())
+            .with_progress(move |event| recorded.lock().expect("poisoned").push(event));
+    let with_progress = exporter
+        .export_as_spec(ctx)
+        .await
+        .expect("should export with progress");
+
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id());
+    let without_progress = exporter
+        .export_as_spec(ctx)
+        .await
+        .expect("should export without progress");
+
+    // Progress reporting must not affect what is exported
+    let func_unique_ids = |spec: &PkgSpec| {
+        let mut unique_ids: Vec<String> = spec.funcs.iter().map(|f| f.unique_id.clone()).collect();
+        unique_ids.sort();
+        unique_ids
+    };
+    assert_eq!(
+        func_unique_ids(&without_progress),
+        func_unique_ids(&with_progress)
+    );
+    assert_eq!(without_progress.schemas.len(), with_progress.schemas.len());
+
+    let events = events.lock().expect("poisoned");
+    assert_eq!(3, events.len());
+    assert_eq!(
+        ExportProgress::SchemaStarted {
+            name: "dummy-secret".to_string()
+        },
+        events[0]
+    );
+    assert!(matches!(events[1], ExportProgress::VariantExported { .. }));
+    assert!(matches!(
+        &events[2],
+        ExportProgress::SchemaFinished { name, func_count, .. }
+            if name == "dummy-secret" && *func_count > 0
+    ));
+}
+
+#[test]
+async fn export_deduplicates_shared_funcs(ctx: &mut DalContext) {
+    let mut schema_ids = vec![];
+    for name in ["starfield", "fallout"] {
+        let schema = Schema::find_by_name(ctx, name)
+            .await
+            .expect("unable to get schema")
+            .expect("schema not found");
+        schema_ids.push(schema.id());
+    }
+
+    let mut exporter = PkgExporter::new("dummy", "1", None::<String>, "tester", schema_ids);
+    let spec = exporter
+        .export_as_spec(ctx)
+        .await
+        .expect("should export schemas");
+    assert_eq!(2, spec.schemas.len());
+
+    let identity_specs = spec
+        .funcs
+        .iter()
+        .filter(|func| func.name == "si:identity")
+        .count();
+    assert_eq!(1, identity_specs);
+
+    let mut unique_ids: Vec<&str> = spec.funcs.iter().map(|f| f.unique_id.as_str()).collect();
+    let total = unique_ids.len();
+    unique_ids.sort();
+    unique_ids.dedup();
+    assert_eq!(total, unique_ids.len());
+}
+
+#[test]
+async fn export_cancelled_between_schemas(ctx: &mut DalContext) {
+    let mut schema_ids = vec![];
+    for name in ["starfield", "fallout"] {
+        let schema = Schema::find_by_name(ctx, name)
+            .await
+            .expect("unable to get schema")
+            .expect("schema not found");
+        schema_ids.push(schema.id());
+    }
+
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let mut exporter = PkgExporter::new("dummy", "1", None::<String>, "tester", schema_ids)
+        .with_cancellation_token(token)
+        .with_progress(move |event| {
+            // Cancel as soon as the first schema has been exported
+            if matches!(event, ExportProgress::SchemaFinished { .. }) {
+                cancel.cancel();
+            }
+            recorded.lock().expect("poisoned").push(event);
+        });
+
+    let result = exporter.export(ctx).await;
+    assert!(matches!(result, Err(PkgError::Cancelled)));
+
+    let events = events.lock().expect("poisoned");
+    let started = events
+        .iter()
+        .filter(|event| matches!(event, ExportProgress::SchemaStarted { .. }))
+        .count();
+    assert_eq!(1, started);
+}
+
+#[test]
+async fn export_variant_with_management_funcs(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "small odd lego")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+    let variant_id = schema
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("unable to get default schema variant id");
+    let variant = SchemaVariant::get_by_id_or_error(ctx, variant_id)
+        .await
+        .expect("unable to get schema variant");
+
+    let (variant_spec, variant_funcs) =
+        PkgExporter::export_variant_standalone(ctx, &variant, schema.name(), None)
+            .await
+            .expect("should export variant");
+
+    let import_func = variant_funcs
+        .iter()
+        .find(|func| func.name == "test:importManagementSmallLego")
+        .expect("management func should be exported");
+    let management_func = variant_spec
+        .management_funcs
+        .iter()
+        .find(|management_func| management_func.name == "Import small odd lego")
+        .expect("management func spec should be exported");
+    assert_eq!(import_func.unique_id, management_func.func_unique_id);
+
+    // Every management func must be exported alongside the variant so that it round-trips
+    for management_func in &variant_spec.management_funcs {
+        assert!(variant_funcs
+            .iter()
+            .any(|func| func.unique_id == management_func.func_unique_id));
+    }
+}
+
+#[test]
+async fn export_to_writer_matches_export_as_bytes(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "dummy-secret")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id());
+    let bytes = exporter
+        .export_as_bytes(ctx)
+        .await
+        .expect("should export as bytes");
+
+    let mut exporter =
+        PkgExporter::new_for_module_contribution("dummy", "1", "tester", schema.id());
+    let mut streamed = Vec::new();
+    exporter
+        .export_to_writer(ctx, &mut streamed)
+        .await
+        .expect("should export to writer");
+
+    let mut from_bytes = SiPkg::load_from_bytes(&bytes)
+        .expect("should load exported bytes")
+        .to_spec()
+        .await
+        .expect("should convert to spec");
+    let from_stream = SiPkg::load_from_bytes(&streamed)
+        .expect("should load streamed bytes")
+        .to_spec()
+        .await
+        .expect("should convert to spec");
+
+    // Each export is stamped with the time it was built, which is the only thing that may differ
+    from_bytes.created_at = from_stream.created_at;
+    assert_eq!(
+        serde_json::to_value(from_bytes).expect("should serialize spec"),
+        serde_json::to_value(from_stream).expect("should serialize spec")
+    );
+}
+
+#[test]
+async fn export_preserves_prop_order(ctx: &mut DalContext) {
+    let schema = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("unable to get schema")
+        .expect("schema not found");
+    let variant_id = schema
+        .get_default_schema_variant_id_or_error(ctx)
+        .await
+        .expect("unable to get default schema variant id");
+    let variant = SchemaVariant::get_by_id_or_error(ctx, variant_id)
+        .await
+        .expect("unable to get schema variant");
+
+    let domain_prop_id =
+        Prop::find_prop_id_by_path(ctx, variant_id, &PropPath::new(["root", "domain"]))
+            .await
+            .expect("unable to find domain prop");
+    let expected: Vec<String> = Prop::direct_child_props_ordered(ctx, domain_prop_id)
+        .await
+        .expect("unable to get domain children")
+        .into_iter()
+        .map(|prop| prop.name)
+        .collect();
+    assert!(expected.len() > 1);
+
+    let (variant_spec, _) =
+        PkgExporter::export_variant_standalone(ctx, &variant, schema.name(), None)
+            .await
+            .expect("should export variant");
+
+    let entries = variant_spec.domain.direct_children();
+    let names: Vec<String> = entries
+        .iter()
+        .map(|entry| entry.name().to_owned())
+        .collect();
+    assert_eq!(expected, names);
+    for (order, entry) in entries.into_iter().enumerate() {
+        assert_eq!(Some(order as u32), entry.data().and_then(|data| data.order));
+    }
+}
+
+#[test]
+async fn export_variant_standalone_with_components(ctx: &mut DalContext) {
+    let first =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "first")
+            .await
+            .expect("could not create component");
+    let second =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "second")
+            .await
+            .expect("could not create component");
+    let other =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "other")
+            .await
+            .expect("could not create component");
+    connect_components_with_socket_names(ctx, first.id(), "two", second.id(), "one")
+        .await
+        .expect("could not connect components");
+    connect_components_with_socket_names(ctx, other.id(), "one", second.id(), "one")
+        .await
+        .expect("could not connect components");
+
+    let variant = first
+        .schema_variant(ctx)
+        .await
+        .expect("unable to get schema variant");
+    let schema = first.schema(ctx).await.expect("unable to get schema");
+
+    let (_, funcs, components, edges) = PkgExporter::export_variant_standalone_with_components(
+        ctx,
+        &variant,
+        schema.name(),
+        None,
+        &[first.id(), second.id()],
+    )
+    .await
+    .expect("should export variant with components");
+
+    assert_eq!(2, components.len());
+    let variant_unique_id = variant.id().to_string();
+    for (component, name) in components.iter().zip(["first", "second"]) {
+        assert_eq!(name, component.name);
+        assert!(matches!(
+            &component.variant,
+            ComponentSpecVariant::WorkspaceVariant { variant_unique_id: unique_id }
+                if unique_id == &variant_unique_id
+        ));
+
+        // The funcs must cover every attribute the component sets itself
+        let name_attribute = component
+            .attributes
+            .iter()
+            .find(|attribute| {
+                matches!(&attribute.path, AttributeValuePath::Prop { path, .. }
+                    if path == PropPath::new(["root", "si", "name"]).as_str())
+            })
+            .expect("name attribute should be exported");
+        assert_eq!(Some(serde_json::json!(name)), name_attribute.value);
+        for attribute in &component.attributes {
+            assert!(funcs
+                .iter()
+                .any(|func| func.unique_id == attribute.func_unique_id));
+        }
+    }
+
+    // Only the connection between the exported components is kept
+    assert_eq!(1, edges.len());
+    assert_eq!(first.id().to_string(), edges[0].from_component_unique_id);
+    assert_eq!("two", edges[0].from_socket_name);
+    assert_eq!(second.id().to_string(), edges[0].to_component_unique_id);
+    assert_eq!("one", edges[0].to_socket_name);
+
+    let result = PkgExporter::export_variant_standalone_with_components(
+        ctx,
+        &variant,
+        schema.name(),
+        None,
+        &[first.id(), other.id()],
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(PkgError::ComponentNotForVariant(component_id, variant_id))
+            if component_id == other.id() && variant_id == variant.id()
+    ));
+}
+
+#[test]
+async fn export_variant_with_components_with_resource_data(ctx: &mut DalContext) {
+    let variant_code = r#"
+    function main() {
+        return new AssetBuilder()
+            .addResourceProp(new PropBuilder()
+                .setKind("string")
+                .setName("instance_id")
+                .build())
+            .build();
+    }"#;
+    let variant = VariantAuthoringClient::create_schema_and_variant_from_code(
+        ctx,
+        "cloudy",
+        None,
+        None,
+        "Integration Tests",
+        "#00b0b0",
+        variant_code,
+    )
+    .await
+    .expect("unable to create schema and variant");
+    let schema = variant.schema(ctx).await.expect("unable to get schema");
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, schema.name(), "cloudy")
+            .await
+            .expect("could not create component");
+
+    let instance_id_path = PropPath::new(["root", "resource_value", "instance_id"]);
+    let instance_id_prop_id = Prop::find_prop_id_by_path(ctx, variant.id(), &instance_id_path)
+        .await
+        .expect("unable to find instance id prop");
+    let instance_id_value_id =
+        Component::attribute_values_for_prop_id(ctx, component.id(), instance_id_prop_id)
+            .await
+            .expect("unable to get instance id attribute values")
+            .first()
+            .copied()
+            .expect("instance id attribute value should exist");
+    AttributeValue::update(
+        ctx,
+        instance_id_value_id,
+        Some(serde_json::json!("i-1234567890abcdef0")),
+    )
+    .await
+    .expect("could not set instance id");
+
+    let instance_id = |components: &[ComponentSpec]| {
+        components[0]
+            .attributes
+            .iter()
+            .find(|attribute| {
+                matches!(&attribute.path, AttributeValuePath::Prop { path, .. }
+                    if path == instance_id_path.as_str())
+            })
+            .expect("instance id attribute should be exported")
+            .value
+            .clone()
+    };
+
+    // Resource data is stripped by default
+    let (_, _, components, _) = PkgExporter::export_variant_standalone_with_components(
+        ctx,
+        &variant,
+        schema.name(),
+        None,
+        &[component.id()],
+    )
+    .await
+    .expect("should export variant with components");
+    assert_eq!(None, instance_id(&components));
+
+    let (_, _, components, _) = PkgExporter::new(
+        "cloudy backup",
+        "1",
+        None::<String>,
+        "tester",
+        vec![schema.id()],
+    )
+    .with_resource_data(true)
+    .export_variant_with_components(ctx, &variant, None, &[component.id()])
+    .await
+    .expect("should export variant with components");
+    assert_eq!(
+        Some(serde_json::json!("i-1234567890abcdef0")),
+        instance_id(&components)
+    );
+}
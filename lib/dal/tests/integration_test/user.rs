@@ -0,0 +1,252 @@
+use dal::user::session::{UserSession, UserSessionError};
+use dal::user::WorkspaceRole;
+use dal::{DalContext, User, UserError};
+use dal_test::helpers::create_user;
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn list_and_revoke_sessions(ctx: &DalContext) {
+    let user = create_user(ctx).await.expect("could not create user");
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+
+    let laptop = UserSession::record(
+        ctx,
+        user.pk(),
+        workspace_pk,
+        "laptop-token",
+        None,
+        Some("laptop".to_string()),
+    )
+    .await
+    .expect("could not record session");
+    let phone = UserSession::record(
+        ctx,
+        user.pk(),
+        workspace_pk,
+        "phone-token",
+        None,
+        Some("phone".to_string()),
+    )
+    .await
+    .expect("could not record session");
+
+    // Recording the same token again must not create a new session.
+    let laptop_again =
+        UserSession::record(ctx, user.pk(), workspace_pk, "laptop-token", None, None)
+            .await
+            .expect("could not record session");
+    assert_eq!(
+        laptop.id(),       // expected
+        laptop_again.id()  // actual
+    );
+    assert_eq!(
+        Some("laptop"),        // expected
+        laptop_again.client()  // actual
+    );
+
+    let mut session_ids: Vec<_> = UserSession::list_active(ctx, user.pk(), workspace_pk)
+        .await
+        .expect("could not list sessions")
+        .into_iter()
+        .map(|session| session.id())
+        .collect();
+    session_ids.sort();
+    let mut expected = vec![laptop.id(), phone.id()];
+    expected.sort();
+    assert_eq!(
+        expected,    // expected
+        session_ids  // actual
+    );
+
+    let revoked = UserSession::revoke(ctx, user.pk(), phone.id())
+        .await
+        .expect("could not revoke session");
+    assert!(revoked.is_revoked());
+
+    let session_ids: Vec<_> = UserSession::list_active(ctx, user.pk(), workspace_pk)
+        .await
+        .expect("could not list sessions")
+        .into_iter()
+        .map(|session| session.id())
+        .collect();
+    assert_eq!(
+        vec![laptop.id()], // expected
+        session_ids        // actual
+    );
+
+    // Using the revoked token again keeps it revoked.
+    let phone_again = UserSession::record(ctx, user.pk(), workspace_pk, "phone-token", None, None)
+        .await
+        .expect("could not record session");
+    assert!(phone_again.is_revoked());
+
+    assert!(UserSession::is_token_revoked(ctx, "phone-token")
+        .await
+        .expect("could not check token"));
+    assert!(!UserSession::is_token_revoked(ctx, "laptop-token")
+        .await
+        .expect("could not check token"));
+    // Tokens never used to log in have no session to revoke.
+    assert!(!UserSession::is_token_revoked(ctx, "unknown-token")
+        .await
+        .expect("could not check token"));
+}
+
+#[test]
+async fn cannot_revoke_session_of_another_user(ctx: &DalContext) {
+    let owner = create_user(ctx).await.expect("could not create user");
+    let other = create_user(ctx).await.expect("could not create user");
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+
+    let session = UserSession::record(ctx, owner.pk(), workspace_pk, "token", None, None)
+        .await
+        .expect("could not record session");
+
+    let result = UserSession::revoke(ctx, other.pk(), session.id()).await;
+    assert!(matches!(result, Err(UserSessionError::SessionNotFound(..))));
+    assert!(!UserSession::list_active(ctx, owner.pk(), workspace_pk)
+        .await
+        .expect("could not list sessions")
+        .is_empty());
+}
+
+#[test]
+async fn record_activity_is_throttled(ctx: &DalContext) {
+    let user = create_user(ctx).await.expect("could not create user");
+    assert!(user.last_active_at().is_none());
+
+    let first = User::record_activity(ctx, user.pk())
+        .await
+        .expect("could not record activity")
+        .expect("first activity should always be recorded");
+
+    // Authenticating again right away must not write again.
+    assert!(User::record_activity(ctx, user.pk())
+        .await
+        .expect("could not record activity")
+        .is_none());
+
+    let user = User::get_by_pk_or_error(ctx, user.pk())
+        .await
+        .expect("could not get user");
+    assert_eq!(
+        Some(first),           // expected
+        user.last_active_at()  // actual
+    );
+    assert!(!user.is_activity_stale());
+}
+
+#[test]
+async fn list_members_with_roles_for_workspace(ctx: &DalContext) {
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+
+    let owner = create_user(ctx).await.expect("could not create user");
+    let approver = create_user(ctx).await.expect("could not create user");
+    let unknown = create_user(ctx).await.expect("could not create user");
+    for user in [&owner, &approver, &unknown] {
+        user.associate_workspace(ctx, workspace_pk)
+            .await
+            .expect("could not associate workspace");
+    }
+    User::set_workspace_role(ctx, owner.pk(), workspace_pk, WorkspaceRole::Owner)
+        .await
+        .expect("could not set role");
+    User::set_workspace_role(ctx, approver.pk(), workspace_pk, WorkspaceRole::Approver)
+        .await
+        .expect("could not set role");
+
+    let mut paged = Vec::new();
+    loop {
+        let (page, has_more) = User::list_members_with_roles_for_workspace(
+            ctx,
+            workspace_pk.to_string(),
+            2,
+            paged.len() as i64,
+        )
+        .await
+        .expect("could not list members");
+        assert!(page.len() <= 2);
+        paged.extend(page);
+        if !has_more {
+            break;
+        }
+    }
+
+    let ours = [owner.pk(), approver.pk(), unknown.pk()];
+    let mut expected = vec![
+        (owner.pk(), Some(WorkspaceRole::Owner)),
+        (approver.pk(), Some(WorkspaceRole::Approver)),
+        (unknown.pk(), None),
+    ];
+    // Members are ordered by their pk
+    expected.sort_by_key(|(pk, _)| *pk);
+    assert_eq!(
+        expected, // expected
+        paged
+            .into_iter()
+            .map(|(user, role)| (user.pk(), role))
+            .filter(|(pk, _)| ours.contains(pk))
+            .collect::<Vec<_>>()  // actual
+    );
+}
+
+#[test]
+async fn list_members_for_workspace_paginated(ctx: &DalContext) {
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+    for _ in 0..3 {
+        create_user(ctx)
+            .await
+            .expect("could not create user")
+            .associate_workspace(ctx, workspace_pk)
+            .await
+            .expect("could not associate workspace");
+    }
+
+    let all = User::list_members_for_workspace(ctx, workspace_pk.to_string())
+        .await
+        .expect("could not list members");
+    assert!(all.len() >= 3);
+
+    let mut paged = Vec::new();
+    loop {
+        let (page, has_more) = User::list_members_for_workspace_paginated(
+            ctx,
+            workspace_pk.to_string(),
+            2,
+            paged.len() as i64,
+        )
+        .await
+        .expect("could not list members");
+        assert!(page.len() <= 2);
+        assert_eq!(has_more, paged.len() + page.len() < all.len());
+        paged.extend(page);
+        if !has_more {
+            break;
+        }
+    }
+
+    assert_eq!(
+        all.iter().map(User::pk).collect::<Vec<_>>(), // expected
+        paged.iter().map(User::pk).collect::<Vec<_>>()  // actual
+    );
+}
+
+#[test]
+async fn list_members_for_workspace_paginated_rejects_negative_bounds(ctx: &DalContext) {
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+
+    for (limit, offset) in [(-1, 0), (2, -1)] {
+        let result = User::list_members_for_workspace_paginated(
+            ctx,
+            workspace_pk.to_string(),
+            limit,
+            offset,
+        )
+        .await;
+        assert!(
+            matches!(result, Err(UserError::InvalidPagination(l, o)) if l == limit && o == offset),
+            "unexpected result for limit {limit} and offset {offset}: {result:?}"
+        );
+    }
+}
@@ -1,12 +1,14 @@
 use dal::change_set::view::OpenChangeSetsView;
 use dal::diagram::Diagram;
-use dal::{DalContext, Workspace};
+use dal::{ChangeSet, DalContext, Tenancy, Workspace, WorkspaceError, WorkspacePk};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
     PropEditorTestView,
 };
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
+use si_events::ContentHash;
+use std::time::Duration;
 
 #[test]
 async fn export_import_loop(ctx: &mut DalContext) {
@@ -111,3 +113,138 @@ async fn export_import_loop(ctx: &mut DalContext) {
             .expect("get value for domain/name")
     );
 }
+
+#[test]
+async fn delete_requires_archive(ctx: &mut DalContext) {
+    let workspace =
+        Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "unarchived", "token")
+            .await
+            .expect("could not create workspace");
+
+    let result = Workspace::delete(ctx, *workspace.pk()).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::DeleteWithoutArchive(pk)) if pk == *workspace.pk()
+    ));
+    assert!(Workspace::get_by_pk(ctx, workspace.pk())
+        .await
+        .expect("could not get workspace")
+        .is_some());
+}
+
+#[test]
+async fn delete_requires_no_pending_change_sets(ctx: &mut DalContext) {
+    let mut workspace =
+        Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "pending", "token")
+            .await
+            .expect("could not create workspace");
+    let change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    workspace
+        .archive(ctx)
+        .await
+        .expect("could not archive workspace");
+
+    let result = Workspace::delete(ctx, *workspace.pk()).await;
+    assert!(matches!(
+        result,
+        Err(WorkspaceError::DeleteWithPendingChangeSets(pk, ref ids))
+            if pk == *workspace.pk() && ids == &vec![change_set.id]
+    ));
+}
+
+#[test]
+async fn delete_empty_archived_workspace(ctx: &mut DalContext) {
+    let mut workspace = Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "empty", "token")
+        .await
+        .expect("could not create workspace");
+    workspace
+        .archive(ctx)
+        .await
+        .expect("could not archive workspace");
+    assert!(workspace.is_archived());
+
+    Workspace::delete(ctx, *workspace.pk())
+        .await
+        .expect("could not delete workspace");
+
+    assert!(Workspace::get_by_pk(ctx, workspace.pk())
+        .await
+        .expect("could not get workspace")
+        .is_none());
+}
+
+#[test]
+async fn delete_removes_change_set_idempotency_keys(ctx: &mut DalContext) {
+    let mut workspace = Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "keyed", "token")
+        .await
+        .expect("could not create workspace");
+    let workspace_ctx = ctx.clone_with_new_tenancy(Tenancy::new(*workspace.pk()));
+    let default_change_set =
+        ChangeSet::get_by_id_across_workspaces(ctx, workspace.default_change_set_id())
+            .await
+            .expect("could not get default change set");
+    default_change_set
+        .record_idempotency_key(
+            &workspace_ctx,
+            "retry-me",
+            ContentHash::new(b"request"),
+            Duration::from_secs(60),
+        )
+        .await
+        .expect("could not record idempotency key");
+    workspace
+        .archive(ctx)
+        .await
+        .expect("could not archive workspace");
+
+    Workspace::delete(ctx, *workspace.pk())
+        .await
+        .expect("could not delete workspace");
+
+    let row = ctx
+        .txns()
+        .await
+        .expect("could not get transactions")
+        .pg()
+        .query_one(
+            "SELECT COUNT(*) AS count FROM change_set_idempotency_keys WHERE workspace_id = $1",
+            &[workspace.pk()],
+        )
+        .await
+        .expect("could not count idempotency keys");
+    let count: i64 = row.get("count");
+    assert_eq!(0, count);
+}
+
+#[test]
+async fn set_description(ctx: &mut DalContext) {
+    let mut workspace =
+        Workspace::new_from_builtin(ctx, WorkspacePk::generate(), "described", "token")
+            .await
+            .expect("could not create workspace");
+    assert_eq!(None, workspace.description());
+
+    workspace
+        .set_description(ctx, Some("where the pirates live".to_string()))
+        .await
+        .expect("could not set description");
+
+    let found = Workspace::get_by_pk_or_error(ctx, *workspace.pk())
+        .await
+        .expect("could not get workspace");
+    assert_eq!(
+        Some("where the pirates live"), // expected
+        found.description()             // actual
+    );
+
+    workspace
+        .set_description(ctx, None)
+        .await
+        .expect("could not clear description");
+    let found = Workspace::get_by_pk_or_error(ctx, *workspace.pk())
+        .await
+        .expect("could not get workspace");
+    assert_eq!(None, found.description());
+}
@@ -22,6 +22,30 @@ pub enum ModuleIndexClientError {
     UrlParse(#[from] url::ParseError),
 }
 
+impl ModuleIndexClientError {
+    /// Returns the HTTP status code the module index responded with, if the error came from an
+    /// unsuccessful response.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Request(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the error is likely transient, i.e. a timeout, a failure to connect, or a
+    /// server error response, such that retrying the same call may succeed.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Request(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().is_some_and(|status| status.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
 pub type ModuleIndexClientResult<T> = Result<T, ModuleIndexClientError>;
 
 #[derive(Debug, Clone)]
@@ -99,4 +99,10 @@ impl LatestModuleResponse {
             .as_deref()
             .and_then(|schema_id| Ulid::from_string(schema_id).ok())
     }
+
+    /// Returns whether this is a different, later build of the module than the installed one
+    /// described by the given hash and creation time.
+    pub fn is_newer_than(&self, installed_hash: &str, installed_created_at: DateTime<Utc>) -> bool {
+        self.latest_hash != installed_hash && self.latest_hash_created_at > installed_created_at
+    }
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use asset_sprayer::AssetSprayer;
 use audit_database::AuditDatabaseContext;
@@ -14,7 +14,7 @@ use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
 
 use crate::{
-    routes::routes, AppState, ApplicationRuntimeMode, WorkspacePermissions,
+    routes::routes, AppState, ApplicationRuntimeMode, ModuleIndexRetryConfig, WorkspacePermissions,
     WorkspacePermissionsMode,
 };
 
@@ -38,6 +38,9 @@ impl AxumApp {
         shutdown_token: CancellationToken,
         spicedb_client: Option<SpiceDbClient>,
         audit_database_context: AuditDatabaseContext,
+        module_index_retry: ModuleIndexRetryConfig,
+        change_set_idempotency_key_ttl: Duration,
+        max_components_per_variant_refresh: usize,
     ) -> Self {
         Self::inner_from_services(
             services_context,
@@ -54,6 +57,9 @@ impl AxumApp {
             shutdown_token,
             spicedb_client,
             audit_database_context,
+            module_index_retry,
+            change_set_idempotency_key_ttl,
+            max_components_per_variant_refresh,
         )
     }
 
@@ -78,6 +84,9 @@ impl AxumApp {
         shutdown_token: CancellationToken,
         spicedb_client: SpiceDbClient,
         audit_database_context: AuditDatabaseContext,
+        module_index_retry: ModuleIndexRetryConfig,
+        change_set_idempotency_key_ttl: Duration,
+        max_components_per_variant_refresh: usize,
     ) -> Self {
         Self::inner_from_services(
             services_context,
@@ -94,6 +103,9 @@ impl AxumApp {
             shutdown_token,
             Some(spicedb_client),
             audit_database_context,
+            module_index_retry,
+            change_set_idempotency_key_ttl,
+            max_components_per_variant_refresh,
         )
     }
 
@@ -117,6 +129,9 @@ impl AxumApp {
         shutdown_token: CancellationToken,
         spicedb_client: Option<SpiceDbClient>,
         audit_database_context: AuditDatabaseContext,
+        module_index_retry: ModuleIndexRetryConfig,
+        change_set_idempotency_key_ttl: Duration,
+        max_components_per_variant_refresh: usize,
     ) -> Self {
         let state = AppState::new(
             services_context,
@@ -133,6 +148,9 @@ impl AxumApp {
             shutdown_token,
             spicedb_client,
             audit_database_context,
+            module_index_retry,
+            change_set_idempotency_key_ttl,
+            max_components_per_variant_refresh,
         );
 
         let path_filter = Box::new(|path: &str| match path {
@@ -1,4 +1,4 @@
-use std::{ops::Deref, sync::Arc};
+use std::{ops::Deref, sync::Arc, time::Duration};
 
 use asset_sprayer::AssetSprayer;
 use audit_database::AuditDatabaseContext;
@@ -12,7 +12,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
     nats_multiplexer::NatsMultiplexerClients, service::ws::crdt::BroadcastGroups,
-    WorkspacePermissions, WorkspacePermissionsMode,
+    ModuleIndexRetryConfig, WorkspacePermissions, WorkspacePermissionsMode,
 };
 
 #[remain::sorted]
@@ -38,6 +38,9 @@ pub struct AppState {
     shutdown_token: CancellationToken,
     spicedb_client: Option<SpiceDbClient>,
     audit_database_context: AuditDatabaseContext,
+    module_index_retry: ModuleIndexRetryConfig,
+    change_set_idempotency_key_ttl: Duration,
+    max_components_per_variant_refresh: usize,
 }
 
 impl AppState {
@@ -57,6 +60,9 @@ impl AppState {
         shutdown_token: CancellationToken,
         spicedb_client: Option<SpiceDbClient>,
         audit_database_context: AuditDatabaseContext,
+        module_index_retry: ModuleIndexRetryConfig,
+        change_set_idempotency_key_ttl: Duration,
+        max_components_per_variant_refresh: usize,
     ) -> Self {
         let nats_multiplexer_clients = NatsMultiplexerClients {
             ws: Arc::new(Mutex::new(ws_multiplexer_client)),
@@ -78,6 +84,9 @@ impl AppState {
             shutdown_token,
             spicedb_client,
             audit_database_context,
+            module_index_retry,
+            change_set_idempotency_key_ttl,
+            max_components_per_variant_refresh,
         }
     }
 
@@ -128,6 +137,18 @@ impl AppState {
     pub fn audit_database_context(&self) -> &AuditDatabaseContext {
         &self.audit_database_context
     }
+
+    pub fn module_index_retry(&self) -> ModuleIndexRetryConfig {
+        self.module_index_retry
+    }
+
+    pub fn change_set_idempotency_key_ttl(&self) -> Duration {
+        self.change_set_idempotency_key_ttl
+    }
+
+    pub fn max_components_per_variant_refresh(&self) -> usize {
+        self.max_components_per_variant_refresh
+    }
 }
 
 #[derive(Clone, Debug, FromRef)]
@@ -10,6 +10,7 @@ use std::{
     env,
     net::{SocketAddr, ToSocketAddrs},
     path::{Path, PathBuf},
+    time::Duration,
 };
 use strum::{Display, EnumString, VariantNames};
 use ulid::Ulid;
@@ -62,6 +63,25 @@ impl WorkspacePermissionsMode {
 
 pub type WorkspacePermissions = String;
 
+/// How calls to the module index are retried when they fail with a transient error.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ModuleIndexRetryConfig {
+    /// The total number of attempts made, including the first.
+    pub max_attempts: u32,
+    /// The delay before the first retry, which doubles for every retry after it.
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for ModuleIndexRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 250,
+        }
+    }
+}
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -103,6 +123,15 @@ pub struct Config {
     #[builder(default = "default_module_index_url()")]
     module_index_url: String,
 
+    #[builder(default)]
+    module_index_retry: ModuleIndexRetryConfig,
+
+    #[builder(default = "default_change_set_idempotency_key_ttl_secs()")]
+    change_set_idempotency_key_ttl_secs: u64,
+
+    #[builder(default = "default_max_components_per_variant_refresh()")]
+    max_components_per_variant_refresh: usize,
+
     #[builder(default = "default_auth_api_url()")]
     auth_api_url: String,
 
@@ -226,6 +255,24 @@ impl Config {
         &self.module_index_url
     }
 
+    /// Retry policy for calls to the module index
+    #[must_use]
+    pub fn module_index_retry(&self) -> ModuleIndexRetryConfig {
+        self.module_index_retry
+    }
+
+    /// How long an idempotency key given when creating a change set is remembered for
+    #[must_use]
+    pub fn change_set_idempotency_key_ttl(&self) -> Duration {
+        Duration::from_secs(self.change_set_idempotency_key_ttl_secs)
+    }
+
+    /// The most components a schema variant can have for all of them to be refreshed at once
+    #[must_use]
+    pub fn max_components_per_variant_refresh(&self) -> usize {
+        self.max_components_per_variant_refresh
+    }
+
     /// URL to the auth API
     #[must_use]
     pub fn auth_api_url(&self) -> &str {
@@ -316,6 +363,12 @@ pub struct ConfigFile {
     layer_db_config: LayerDbConfig,
     #[serde(default)]
     pub module_index_url: String,
+    #[serde(default)]
+    pub module_index_retry: ModuleIndexRetryConfig,
+    #[serde(default = "default_change_set_idempotency_key_ttl_secs")]
+    pub change_set_idempotency_key_ttl_secs: u64,
+    #[serde(default = "default_max_components_per_variant_refresh")]
+    pub max_components_per_variant_refresh: usize,
     #[serde(default = "default_auth_api_url")]
     pub auth_api_url: String,
     #[serde(default)]
@@ -350,6 +403,9 @@ impl Default for ConfigFile {
             posthog: Default::default(),
             layer_db_config: default_layer_db_config(),
             module_index_url: default_module_index_url(),
+            module_index_retry: Default::default(),
+            change_set_idempotency_key_ttl_secs: default_change_set_idempotency_key_ttl_secs(),
+            max_components_per_variant_refresh: default_max_components_per_variant_refresh(),
             auth_api_url: default_auth_api_url(),
             openai: Default::default(),
             asset_sprayer: Default::default(),
@@ -386,6 +442,9 @@ impl TryFrom<ConfigFile> for Config {
             pkgs_path: value.pkgs_path.try_into()?,
             posthog: value.posthog,
             module_index_url: value.module_index_url,
+            module_index_retry: value.module_index_retry,
+            change_set_idempotency_key_ttl_secs: value.change_set_idempotency_key_ttl_secs,
+            max_components_per_variant_refresh: value.max_components_per_variant_refresh,
             auth_api_url: value.auth_api_url,
             openai: value.openai,
             asset_sprayer: value.asset_sprayer,
@@ -450,6 +509,14 @@ fn default_module_index_url() -> String {
     DEFAULT_MODULE_INDEX_URL.into()
 }
 
+fn default_change_set_idempotency_key_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_max_components_per_variant_refresh() -> usize {
+    500
+}
+
 fn default_auth_api_url() -> String {
     DEFAULT_AUTH_API_URL.into()
 }
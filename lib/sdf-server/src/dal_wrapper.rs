@@ -33,7 +33,10 @@ use std::collections::HashMap;
 
 use dal::{
     change_set::approval::ChangeSetApproval,
-    workspace_snapshot::{graph::approval::ApprovalRequirement, EntityKindExt},
+    workspace_snapshot::{
+        graph::{approval::ApprovalRequirement, detector::Change},
+        EntityKindExt,
+    },
     DalContext, HistoryActor, WorkspacePk,
 };
 use permissions::{Permission, PermissionBuilder};
@@ -251,6 +254,70 @@ pub async fn determine_approving_ids(
     determine_approving_ids_inner(ctx, spicedb_client, workspace_id, &requirements).await
 }
 
+/// Determines which approval requirements for the given changes can never be satisfied because
+/// the approvers they resolve to are empty, yet at least one approval is required.
+///
+/// This allows callers to warn users before approval is requested for a change set that would
+/// otherwise become permanently unapprovable.
+pub async fn unsatisfiable_approval_requirements(
+    ctx: &DalContext,
+    spicedb_client: &mut si_data_spicedb::Client,
+    changes: &[Change],
+) -> Result<Vec<ApprovalRequirement>> {
+    let workspace_id = ctx.workspace_pk()?;
+    let requirements = ctx
+        .workspace_snapshot()?
+        .approval_requirements_for_changes(workspace_id, changes)
+        .await?;
+
+    let mut unsatisfiable_requirements = Vec::new();
+    let mut cache: HashMap<String, bool> = HashMap::new();
+
+    for requirement in requirements {
+        if requirement.number == 0 {
+            continue;
+        }
+
+        // The requirement can be satisfied if any of its lookup groups resolves to at least one
+        // user.
+        let mut has_approvers = false;
+        for lookup_group in &requirement.lookup_groups {
+            let lookup_group_key = format!(
+                "{}#{}#{}",
+                lookup_group.object_type, lookup_group.object_id, lookup_group.permission
+            );
+            let lookup_group_has_approvers = match cache.get(&lookup_group_key) {
+                Some(lookup_group_has_approvers) => *lookup_group_has_approvers,
+                None => {
+                    let member_ids = spicedb_client
+                        .lookup_subjects(
+                            lookup_group.object_type.to_owned(),
+                            lookup_group.object_id.to_owned(),
+                            lookup_group.permission.to_owned(),
+                            "user".to_owned(),
+                        )
+                        .await
+                        .map_err(DalWrapperError::SpiceDBLookupSubjects)?;
+                    let lookup_group_has_approvers = !member_ids.is_empty();
+                    cache.insert(lookup_group_key, lookup_group_has_approvers);
+                    lookup_group_has_approvers
+                }
+            };
+
+            if lookup_group_has_approvers {
+                has_approvers = true;
+                break;
+            }
+        }
+
+        if !has_approvers {
+            unsatisfiable_requirements.push(requirement);
+        }
+    }
+
+    Ok(unsatisfiable_requirements)
+}
+
 async fn determine_approving_ids_inner(
     ctx: &DalContext,
     spicedb_client: &mut si_data_spicedb::Client,
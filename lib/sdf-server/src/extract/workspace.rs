@@ -4,7 +4,7 @@ use axum::{
     http::{header::HeaderMap, request::Parts},
     RequestPartsExt as _,
 };
-use dal::{DalContext, User, UserPk, WorkspacePk};
+use dal::{user::session::UserSession, DalContext, User, UserPk, WorkspacePk};
 use derive_more::{Deref, Into};
 use serde::Deserialize;
 use si_jwt_public_key::SiJwtClaimRole;
@@ -14,7 +14,7 @@ use crate::app_state::AppState;
 
 use super::{
     bad_request, internal_error,
-    request::{RequestUlidFromHeader, ValidatedToken},
+    request::{RawAccessToken, RequestUlidFromHeader, ValidatedToken},
     services::HandlerContext,
     unauthorized_error, ErrorResponse,
 };
@@ -100,6 +100,35 @@ impl FromRequestParts<AppState> for WorkspaceAuthorization {
             .find(|m| m.pk() == user_id)
             .ok_or_else(|| unauthorized_error("User not a member of the workspace"))?;
 
+        // Reject the token if the user has revoked its session (sessions are recorded at login)
+        let RawAccessToken(raw_token) = parts.extract_with_state(state).await?;
+        if UserSession::is_token_revoked(&ctx, &raw_token)
+            .await
+            .map_err(internal_error)?
+        {
+            return Err(unauthorized_error("Session has been revoked"));
+        }
+
+        // Only write the user's activity (and the session's last use) once it has gone stale, so
+        // that most requests don't write at all. This uses its own context so that committing it
+        // does not affect the one we hand out.
+        if user.is_activity_stale() {
+            let activity_ctx = builder
+                .build_without_workspace(user_id.into(), request_ulid)
+                .await
+                .map_err(internal_error)?;
+            User::record_activity(&activity_ctx, user_id)
+                .await
+                .map_err(internal_error)?;
+            UserSession::touch(&activity_ctx, &raw_token)
+                .await
+                .map_err(internal_error)?;
+            activity_ctx
+                .commit_no_rebase()
+                .await
+                .map_err(internal_error)?;
+        }
+
         Ok(Self {
             ctx,
             user,
@@ -40,7 +40,8 @@ pub use self::{
     app_state::ApplicationRuntimeMode,
     config::{
         Config, ConfigBuilder, ConfigError, ConfigFile, IncomingStream, MigrationMode,
-        StandardConfig, StandardConfigFile, WorkspacePermissions, WorkspacePermissionsMode,
+        ModuleIndexRetryConfig, StandardConfig, StandardConfigFile, WorkspacePermissions,
+        WorkspacePermissionsMode,
     },
     migrations::Migrator,
     nats_multiplexer::CRDT_MULTIPLEXER_SUBJECT,
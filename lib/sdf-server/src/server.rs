@@ -1,4 +1,6 @@
-use std::{fmt, future::IntoFuture as _, net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{
+    fmt, future::IntoFuture as _, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration,
+};
 
 use asset_sprayer::AssetSprayer;
 use audit_database::AuditDatabaseContext;
@@ -23,8 +25,8 @@ use crate::{
     nats_multiplexer::{CRDT_MULTIPLEXER_SUBJECT, WS_MULTIPLEXER_SUBJECT},
     runnable::Runnable,
     uds::UdsIncomingStream,
-    ApplicationRuntimeMode, AxumApp, Config, IncomingStream, Migrator, ServerError, ServerResult,
-    WorkspacePermissions, WorkspacePermissionsMode,
+    ApplicationRuntimeMode, AxumApp, Config, IncomingStream, Migrator, ModuleIndexRetryConfig,
+    ServerError, ServerResult, WorkspacePermissions, WorkspacePermissionsMode,
 };
 
 /// Server metadata, used with telemetry.
@@ -149,6 +151,9 @@ impl Server {
             token,
             spicedb_client,
             audit_database_context,
+            config.module_index_retry(),
+            config.change_set_idempotency_key_ttl(),
+            config.max_components_per_variant_refresh(),
         )
         .await
     }
@@ -171,6 +176,9 @@ impl Server {
         token: CancellationToken,
         spicedb_client: Option<SpiceDbClient>,
         audit_database_context: AuditDatabaseContext,
+        module_index_retry: ModuleIndexRetryConfig,
+        change_set_idempotency_key_ttl: Duration,
+        max_components_per_variant_refresh: usize,
     ) -> ServerResult<Self> {
         let app = AxumApp::from_services(
             services_context.clone(),
@@ -187,6 +195,9 @@ impl Server {
             spicedb_client,
             // TODO(nick): split the migrator context and the reader-only context (should be read-only pg pool).
             audit_database_context.clone(),
+            module_index_retry,
+            change_set_idempotency_key_ttl,
+            max_components_per_variant_refresh,
         )
         .into_inner();
 
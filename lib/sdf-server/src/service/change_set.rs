@@ -58,6 +58,8 @@ pub enum ChangeSetError {
     Schema(#[from] SchemaError),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] SchemaVariantError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
@@ -78,9 +80,16 @@ impl IntoResponse for ChangeSetError {
             ChangeSetError::ActionAlreadyEnqueued(_) => {
                 (StatusCode::NOT_MODIFIED, self.to_string())
             }
+            ChangeSetError::DalChangeSet(
+                DalChangeSetError::AbandonApprovalFlowNotActive(..)
+                | DalChangeSetError::BaseChangeSetNotOpen(..),
+            ) => (StatusCode::CONFLICT, self.to_string()),
             ChangeSetError::DalChangeSet(DalChangeSetError::ChangeSetNotFound(..)) => {
                 (StatusCode::NOT_FOUND, self.to_string())
             }
+            ChangeSetError::DalChangeSet(DalChangeSetError::IdempotencyKeyReused(..)) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
+            }
             ChangeSetError::DalChangeSetApply(_) => (StatusCode::CONFLICT, self.to_string()),
             ChangeSetError::DvuRootsNotEmpty(_) => (
                 StatusCode::PRECONDITION_REQUIRED,
@@ -130,6 +139,10 @@ pub fn routes() -> Router<AppState> {
             "/cancel_abandon_approval_process",
             post(begin_abandon_approval_process::cancel_abandon_approval_process),
         )
+        .route(
+            "/restart_abandon_approval_process",
+            post(begin_abandon_approval_process::restart_abandon_approval_process),
+        )
         .route("/abandon_vote", post(abandon_vote::abandon_vote))
         .route("/rebase_on_base", post(rebase_on_base::rebase_on_base))
         .route(
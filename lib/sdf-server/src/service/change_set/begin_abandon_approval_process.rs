@@ -2,11 +2,14 @@ use axum::{
     extract::{Host, OriginalUri},
     Json,
 };
-use dal::{ChangeSet, Visibility};
+use dal::{ChangeSet, ChangeSetStatus, DalContext, User, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
 
 use crate::{
-    extract::{v1::AccessBuilder, HandlerContext, PosthogClient},
+    extract::{
+        v1::AccessBuilder, workspace::WorkspaceAuthorization, HandlerContext, PosthogClient,
+    },
     service::change_set::{ChangeSetError, ChangeSetResult},
     track,
 };
@@ -25,12 +28,20 @@ pub struct CancelAbandonFlow {
     pub visibility: Visibility,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartAbandonFlow {
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
 pub async fn begin_abandon_approval_process(
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    WorkspaceAuthorization { user, .. }: WorkspaceAuthorization,
     Json(request): Json<BeginAbandonFlow>,
 ) -> ChangeSetResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -39,8 +50,10 @@ pub async fn begin_abandon_approval_process(
         return Err(ChangeSetError::CannotAbandonHead);
     }
     let mut change_set = ChangeSet::get_by_id(&ctx, ctx.visibility().change_set_id).await?;
+    let old_status = change_set.status;
 
     change_set.begin_abandon_approval_flow(&ctx).await?;
+    record_abandon_requested(&ctx, &change_set, &user, old_status).await?;
 
     track(
         &posthog_client,
@@ -51,6 +64,8 @@ pub async fn begin_abandon_approval_process(
         serde_json::json!({
             "how": "/change_set/begin_abandon_approval_process",
             "change_set_id": ctx.visibility().change_set_id,
+            "user_pk": user.pk(),
+            "user_email": user.email(),
         }),
     );
     ctx.commit_no_rebase().await?;
@@ -86,3 +101,62 @@ pub async fn cancel_abandon_approval_process(
 
     Ok(Json(()))
 }
+
+/// Restarts the abandon approval flow with the change set locked, so that other users can't begin
+/// or cancel the flow in between. Clients see the flow begin again, without the cancellation.
+pub async fn restart_abandon_approval_process(
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    PosthogClient(posthog_client): PosthogClient,
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    WorkspaceAuthorization { user, .. }: WorkspaceAuthorization,
+    Json(request): Json<RestartAbandonFlow>,
+) -> ChangeSetResult<Json<()>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut change_set = ChangeSet::get_by_id(&ctx, ctx.change_set_id()).await?;
+    let old_status = change_set.restart_abandon_approval_flow(&ctx).await?;
+    record_abandon_requested(&ctx, &change_set, &user, old_status).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "restart_abandon_approval_process",
+        serde_json::json!({
+            "how": "/change_set/restart_abandon_approval_process",
+            "change_set_id": ctx.visibility().change_set_id,
+            "user_pk": user.pk(),
+            "user_email": user.email(),
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(()))
+}
+
+/// Publishes who requested the change set be abandoned and writes the request to the audit log.
+async fn record_abandon_requested(
+    ctx: &DalContext,
+    change_set: &ChangeSet,
+    user: &User,
+    old_status: ChangeSetStatus,
+) -> ChangeSetResult<()> {
+    WsEvent::change_set_abandon_requested(ctx, change_set.id, user.pk(), user.email().clone())
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::RequestChangeSetAbandon {
+            from_status: old_status.into(),
+        },
+        change_set.name.clone(),
+    )
+    .await?;
+
+    Ok(())
+}
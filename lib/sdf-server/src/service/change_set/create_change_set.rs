@@ -1,29 +1,63 @@
-use axum::extract::{Host, OriginalUri};
+use axum::extract::{Host, OriginalUri, State};
+use axum::http::HeaderMap;
 use axum::Json;
 use dal::change_set::ChangeSet;
 use dal::WsEvent;
-use si_events::audit_log::AuditLogKind;
+use si_events::{audit_log::AuditLogKind, ContentHash};
 use si_frontend_types::{CreateChangeSetRequest, CreateChangeSetResponse};
 
 use super::ChangeSetResult;
 use crate::{
     extract::{v1::AccessBuilder, HandlerContext, PosthogClient},
-    track,
+    track, AppState,
 };
 
+/// Lets a client retry a request to create a change set without creating it twice.
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
 pub async fn create_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<CreateChangeSetRequest>,
 ) -> ChangeSetResult<Json<CreateChangeSetResponse>> {
     let ctx = builder.build_head(access_builder).await?;
 
+    let idempotency_key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    // A key is only replayed for the request it was first sent with
+    let request_hash = ContentHash::new(&serde_json::to_vec(&request)?);
+    // Looking the key up locks it until this transaction commits, so a concurrent retry waits for
+    // the change set created here rather than creating another
+    if let Some(idempotency_key) = idempotency_key {
+        if let Some(change_set) = ChangeSet::find_by_idempotency_key(
+            &ctx,
+            idempotency_key,
+            request_hash,
+            state.change_set_idempotency_key_ttl(),
+        )
+        .await?
+        {
+            let change_set = change_set.into_frontend_type(&ctx).await?;
+            return Ok(Json(CreateChangeSetResponse { change_set }));
+        }
+    }
+
     let change_set_name = &request.change_set_name;
 
-    let change_set = ChangeSet::fork_head(&ctx, change_set_name).await?;
+    let change_set = match request.base_change_set_id {
+        Some(base_change_set_id) => {
+            ChangeSet::fork_from(&ctx, base_change_set_id, change_set_name).await?
+        }
+        None => ChangeSet::fork_head(&ctx, change_set_name).await?,
+    };
 
     track(
         &posthog_client,
@@ -33,9 +67,21 @@ pub async fn create_change_set(
         "create_change_set",
         serde_json::json!({
                     "change_set_name": change_set_name.clone(),
+                    "base_change_set_id": request.base_change_set_id,
         }),
     );
 
+    if let Some(idempotency_key) = idempotency_key {
+        change_set
+            .record_idempotency_key(
+                &ctx,
+                idempotency_key,
+                request_hash,
+                state.change_set_idempotency_key_ttl(),
+            )
+            .await?;
+    }
+
     ctx.write_audit_log(AuditLogKind::CreateChangeSet, change_set_name.to_string())
         .await?;
 
@@ -35,6 +35,7 @@ pub mod get_code;
 pub mod get_diff;
 pub mod get_property_editor_schema;
 pub mod get_property_editor_values;
+pub mod get_property_editor_values_batch;
 pub mod get_resource;
 pub mod insert_property_editor_value;
 pub mod json;
@@ -106,6 +107,8 @@ pub enum ComponentError {
     SlowRuntime(#[from] SlowRuntimeError),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
+    #[error("too many components to refresh: {count} (max: {max_components})")]
+    TooManyComponentsToRefresh { count: usize, max_components: usize },
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
     #[error("component upgrade skipped due to running or dispatched actions")]
@@ -141,7 +144,8 @@ impl IntoResponse for ComponentError {
             ComponentError::SchemaVariantUpgradeSkipped => {
                 (StatusCode::NOT_MODIFIED, self.to_string())
             }
-            ComponentError::KeyAlreadyExists(_) => {
+            ComponentError::KeyAlreadyExists(_)
+            | ComponentError::TooManyComponentsToRefresh { .. } => {
                 (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
             }
             ComponentError::DalComponent(err) => match err {
@@ -166,6 +170,10 @@ pub fn routes() -> Router<AppState> {
             "/get_property_editor_values",
             get(get_property_editor_values::get_property_editor_values),
         )
+        .route(
+            "/get_property_editor_values_batch",
+            post(get_property_editor_values_batch::get_property_editor_values_batch),
+        )
         .route(
             "/list_qualifications",
             get(list_qualifications::list_qualifications),
@@ -193,6 +201,10 @@ pub fn routes() -> Router<AppState> {
         .route("/set_name", post(set_name::set_name))
         .route("/set_resource_id", post(set_resource_id::set_resource_id))
         .route("/refresh", post(refresh::refresh))
+        .route(
+            "/refresh_for_schema_variant",
+            post(refresh::refresh_for_schema_variant),
+        )
         .route("/debug", get(debug::debug_component))
         .route("/json", get(json::json))
         .route("/upgrade_component", post(upgrade::upgrade))
@@ -1,6 +1,13 @@
-use axum::{extract::Query, Json};
+use axum::{
+    extract::Query,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 use dal::{property_editor::values::PropertyEditorValues, ComponentId, Visibility};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_events::ContentHash;
 
 use super::ComponentResult;
 use crate::extract::{v1::AccessBuilder, HandlerContext};
@@ -18,13 +25,87 @@ pub type GetPropertyEditorValuesResponse = PropertyEditorValues;
 pub async fn get_property_editor_values(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    headers: HeaderMap,
     Query(request): Query<GetPropertyEditorValuesRequest>,
-) -> ComponentResult<Json<serde_json::Value>> {
+) -> ComponentResult<Response> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let prop_edit_values = PropertyEditorValues::assemble(&ctx, request.component_id).await?;
 
-    let prop_edit_values = serde_json::to_value(prop_edit_values)?;
+    let mut prop_edit_values = serde_json::to_value(prop_edit_values)?;
+    // The values are assembled into hash maps, so sort them to get a stable content hash
+    sort_object_keys(&mut prop_edit_values);
+    let etag = format!(
+        "\"{}\"",
+        ContentHash::new(&serde_json::to_vec(&prop_edit_values)?)
+    );
 
-    Ok(Json(prop_edit_values))
+    if if_none_match(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    Ok(([(header::ETAG, etag)], Json(prop_edit_values)).into_response())
+}
+
+fn sort_object_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut entry) in entries {
+                sort_object_keys(&mut entry);
+                map.insert(key, entry);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_object_keys),
+        _ => {}
+    }
+}
+
+/// Returns whether the request's `If-None-Match` header matches the given entity tag.
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn sorted_values_serialize_identically() {
+        let mut first = serde_json::json!({ "b": { "d": 1, "c": [{ "f": 2, "e": 3 }] }, "a": 0 });
+        let mut second = serde_json::json!({ "a": 0, "b": { "c": [{ "e": 3, "f": 2 }], "d": 1 } });
+
+        sort_object_keys(&mut first);
+        sort_object_keys(&mut second);
+
+        assert_eq!(
+            serde_json::to_string(&first).expect("failed to serialize"),
+            serde_json::to_string(&second).expect("failed to serialize")
+        );
+    }
+
+    #[test]
+    fn if_none_match_compares_entity_tags() {
+        let mut headers = HeaderMap::new();
+        assert!(!if_none_match(&headers, "\"abc\""));
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"xyz\", W/\"abc\""),
+        );
+        assert!(if_none_match(&headers, "\"abc\""));
+        assert!(!if_none_match(&headers, "\"def\""));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(if_none_match(&headers, "\"def\""));
+    }
 }
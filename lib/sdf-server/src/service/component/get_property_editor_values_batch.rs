@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use axum::Json;
+use dal::{property_editor::values::PropertyEditorValues, ComponentId, Visibility};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use super::ComponentResult;
+use crate::extract::{v1::AccessBuilder, HandlerContext};
+
+/// The maximum number of components whose property editor values are assembled concurrently.
+const MAX_CONCURRENT_ASSEMBLIES: usize = 8;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropertyEditorValuesBatchRequest {
+    pub component_ids: Vec<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyEditorValuesError {
+    pub component_id: ComponentId,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPropertyEditorValuesBatchResponse {
+    pub values: HashMap<ComponentId, PropertyEditorValues>,
+    pub errors: Vec<PropertyEditorValuesError>,
+}
+
+pub async fn get_property_editor_values_batch(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Json(request): Json<GetPropertyEditorValuesBatchRequest>,
+) -> ComponentResult<Json<GetPropertyEditorValuesBatchResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut component_ids = request.component_ids;
+    component_ids.sort();
+    component_ids.dedup();
+
+    let assembled = stream::iter(component_ids)
+        .map(|component_id| {
+            let ctx = &ctx;
+            async move {
+                (
+                    component_id,
+                    PropertyEditorValues::assemble(ctx, component_id).await,
+                )
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_ASSEMBLIES)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut values = HashMap::new();
+    let mut errors = Vec::new();
+    for (component_id, result) in assembled {
+        match result {
+            Ok(component_values) => {
+                values.insert(component_id, component_values);
+            }
+            Err(err) => errors.push(PropertyEditorValuesError {
+                component_id,
+                error: err.to_string(),
+            }),
+        }
+    }
+
+    Ok(Json(GetPropertyEditorValuesBatchResponse {
+        values,
+        errors,
+    }))
+}
@@ -1,31 +1,61 @@
 use axum::{
-    extract::{Host, OriginalUri},
+    extract::{Host, OriginalUri, State},
     Json,
 };
 use dal::{
-    action::prototype::ActionKind, action::prototype::ActionPrototype, action::Action, Component,
-    ComponentId, Visibility,
+    action::prototype::ActionKind, action::prototype::ActionPrototype, action::Action,
+    action::ActionState, Component, ComponentId, DalContext, SchemaVariant, SchemaVariantId,
+    Visibility,
 };
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
 
-use super::ComponentResult;
+use super::{ComponentError, ComponentResult};
 use crate::{
     extract::{v1::AccessBuilder, HandlerContext, PosthogClient},
-    track,
+    track, AppState,
 };
 
+/// The maximum number of components whose refresh actions are enqueued concurrently.
+const MAX_CONCURRENT_REFRESHES: usize = 8;
+
+/// Refresh actions in these states are still pending, so refreshing again would only queue a
+/// redundant duplicate.
+const IN_FLIGHT_ACTION_STATES: [ActionState; 4] = [
+    ActionState::Dispatched,
+    ActionState::OnHold,
+    ActionState::Queued,
+    ActionState::Running,
+];
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshRequest {
-    pub component_id: ComponentId,
+    /// A single component to refresh, kept for clients which predate `component_ids`.
+    #[serde(default)]
+    pub component_id: Option<ComponentId>,
+    #[serde(default)]
+    pub component_ids: Option<Vec<ComponentId>>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct RefreshResponse {
+pub struct ComponentRefreshResult {
+    pub component_id: ComponentId,
     pub success: bool,
+    /// True if a new refresh action was enqueued, false if one was already in flight.
+    pub enqueued: bool,
+    /// The number of new refresh actions enqueued for the component.
+    pub enqueued_count: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResponse {
+    pub results: Vec<ComponentRefreshResult>,
 }
 
 pub async fn refresh(
@@ -38,7 +68,13 @@ pub async fn refresh(
 ) -> ComponentResult<Json<RefreshResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
-    let component_ids = vec![request.component_id];
+    let mut component_ids: Vec<ComponentId> = request
+        .component_id
+        .into_iter()
+        .chain(request.component_ids.into_iter().flatten())
+        .collect();
+    component_ids.sort();
+    component_ids.dedup();
 
     track(
         &posthog_client,
@@ -52,19 +88,131 @@ pub async fn refresh(
     );
 
     // Parallelizes resource refreshing
-    for component_id in component_ids {
-        let variant = Component::schema_variant_for_component_id(&ctx, component_id).await?;
-
-        let all_prototypes_for_variant: Vec<ActionPrototype> =
-            ActionPrototype::for_variant(&ctx, variant.id()).await?;
-        for prototype in all_prototypes_for_variant {
-            if prototype.kind == ActionKind::Refresh {
-                Action::new(&ctx, prototype.id(), Some(component_id)).await?;
-            }
-        }
+    let results = refresh_components(&ctx, component_ids).await;
+
+    ctx.commit().await?;
+
+    Ok(Json(RefreshResponse { results }))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshForSchemaVariantRequest {
+    pub schema_variant_id: SchemaVariantId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshForSchemaVariantResponse {
+    /// The number of new refresh actions enqueued across all components.
+    pub enqueued_count: usize,
+    pub results: Vec<ComponentRefreshResult>,
+}
+
+/// Refreshes every component of a schema variant, e.g. after an upstream change. Refuses to when
+/// the variant has more components than the server's configured maximum.
+pub async fn refresh_for_schema_variant(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    State(state): State<AppState>,
+    Json(request): Json<RefreshForSchemaVariantRequest>,
+) -> ComponentResult<Json<RefreshForSchemaVariantResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let component_ids = SchemaVariant::list_component_ids(&ctx, request.schema_variant_id).await?;
+    let max_components = state.max_components_per_variant_refresh();
+    if component_ids.len() > max_components {
+        return Err(ComponentError::TooManyComponentsToRefresh {
+            count: component_ids.len(),
+            max_components,
+        });
     }
 
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "refresh_resources_for_schema_variant",
+        serde_json::json!({
+            "schema_variant_id": request.schema_variant_id,
+            "component_count": component_ids.len(),
+        }),
+    );
+
+    let results = refresh_components(&ctx, component_ids).await;
+    let enqueued_count = results.iter().map(|result| result.enqueued_count).sum();
+
     ctx.commit().await?;
 
-    Ok(Json(RefreshResponse { success: true }))
+    Ok(Json(RefreshForSchemaVariantResponse {
+        enqueued_count,
+        results,
+    }))
+}
+
+/// Enqueues the refresh actions for each component concurrently, collecting a result per
+/// component rather than failing on the first error.
+///
+/// A component which fails partway still reports the actions enqueued before the failure, since
+/// they are committed along with everything else.
+async fn refresh_components(
+    ctx: &DalContext,
+    component_ids: Vec<ComponentId>,
+) -> Vec<ComponentRefreshResult> {
+    stream::iter(component_ids)
+        .map(|component_id| async move {
+            let mut enqueued_count = 0;
+            let result = enqueue_refresh_actions(ctx, component_id, &mut enqueued_count).await;
+            ComponentRefreshResult {
+                component_id,
+                success: result.is_ok(),
+                enqueued: enqueued_count > 0,
+                enqueued_count,
+                error: result.err().map(|err| err.to_string()),
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REFRESHES)
+        .collect::<Vec<_>>()
+        .await
+}
+
+/// Enqueues the refresh actions for a component, skipping any prototype which already has a
+/// refresh action in flight. Each new action is counted in `enqueued_count` as it is created, so
+/// the count is accurate even if a later action fails.
+async fn enqueue_refresh_actions(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    enqueued_count: &mut usize,
+) -> ComponentResult<()> {
+    let variant = Component::schema_variant_for_component_id(ctx, component_id).await?;
+
+    let mut in_flight_prototype_ids = Vec::new();
+    for action_id in Action::find_for_states_and_component_id(
+        ctx,
+        component_id,
+        IN_FLIGHT_ACTION_STATES.to_vec(),
+    )
+    .await?
+    {
+        in_flight_prototype_ids.push(Action::prototype_id(ctx, action_id).await?);
+    }
+
+    let all_prototypes_for_variant: Vec<ActionPrototype> =
+        ActionPrototype::for_variant(ctx, variant.id()).await?;
+    for prototype in all_prototypes_for_variant {
+        if prototype.kind == ActionKind::Refresh
+            && !in_flight_prototype_ids.contains(&prototype.id())
+        {
+            Action::new(ctx, prototype.id(), Some(component_id)).await?;
+            *enqueued_count += 1;
+        }
+    }
+
+    Ok(())
 }
@@ -137,6 +137,9 @@ impl IntoResponse for DiagramError {
             | DiagramError::FrameSocketNotFound(_)
             | DiagramError::EdgeNotFound
             | DiagramError::SocketNotFound => StatusCode::NOT_FOUND,
+            DiagramError::DalFrame(dal::component::frame::FrameError::WouldCreateCycle {
+                ..
+            }) => StatusCode::BAD_REQUEST,
             DiagramError::Component(ComponentError::ComponentAlreadyInView(_, _)) => {
                 StatusCode::UNPROCESSABLE_ENTITY
             }
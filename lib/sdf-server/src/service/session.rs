@@ -5,8 +5,9 @@ use axum::{
     Router,
 };
 use dal::{
-    workspace_integrations::WorkspaceIntegrationsError, KeyPairError, StandardModelError,
-    TransactionsError, UserError, UserPk, WorkspaceError, WorkspacePk,
+    user::session::UserSessionError, workspace_integrations::WorkspaceIntegrationsError,
+    KeyPairError, StandardModelError, TransactionsError, UserError, UserPk, WorkspaceError,
+    WorkspacePk,
 };
 use serde::{Deserialize, Serialize};
 use si_data_spicedb::SpiceDbError;
@@ -20,6 +21,7 @@ pub mod auth_connect;
 pub mod load_workspaces;
 mod refresh_workspace_members;
 pub mod restore_authentication;
+pub mod sessions;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -52,6 +54,8 @@ pub enum SessionError {
     StandardModel(#[from] StandardModelError),
     #[error("user error: {0}")]
     User(#[from] UserError),
+    #[error("user session error: {0}")]
+    UserSession(#[from] UserSessionError),
     #[error(transparent)]
     Workspace(#[from] WorkspaceError),
     #[error(transparent)]
@@ -77,6 +81,9 @@ impl IntoResponse for SessionError {
             SessionError::InvalidWorkspace(_) => (StatusCode::CONFLICT, self.to_string()),
             SessionError::WorkspacePermission(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
             SessionError::AuthApiError(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            SessionError::UserSession(UserSessionError::SessionNotFound(..)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -97,4 +104,6 @@ pub fn routes() -> Router<AppState> {
             "/refresh_workspace_members",
             post(refresh_workspace_members::refresh_workspace_members),
         )
+        .route("/sessions", get(sessions::list_sessions))
+        .route("/revoke_session", post(sessions::revoke_session))
 }
@@ -1,10 +1,11 @@
 use axum::{
     extract::{Host, OriginalUri, State},
+    http::{header::USER_AGENT, HeaderMap},
     Json,
 };
 use dal::{
-    workspace_integrations::WorkspaceIntegration, DalContext, HistoryActor, KeyPair, Tenancy, User,
-    UserPk, Workspace, WorkspacePk, WorkspaceSnapshotGraph,
+    user::session::UserSession, workspace_integrations::WorkspaceIntegration, DalContext,
+    HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk, WorkspaceSnapshotGraph,
 };
 use hyper::Uri;
 use permissions::{Relation, RelationBuilder};
@@ -100,6 +101,8 @@ async fn find_or_create_user_and_workspace(
     create_workspace_allowlist: &[String],
     on_demand_assets: bool,
     spicedb_client: Option<&mut SpiceDbClient>,
+    session_token: &str,
+    client: Option<String>,
 ) -> SessionResult<(User, Workspace)> {
     // lookup user or create if we've never seen it before
     let maybe_user = User::get_by_pk(&ctx, auth_api_user.id).await?;
@@ -207,6 +210,17 @@ async fn find_or_create_user_and_workspace(
     // ensure workspace is associated to user
     user.associate_workspace(&ctx, *workspace.pk()).await?;
 
+    // Track the session for the token being logged in with, so the user can list and revoke it
+    UserSession::record(
+        &ctx,
+        user.pk(),
+        *workspace.pk(),
+        session_token,
+        None,
+        client,
+    )
+    .await?;
+
     // Check the workspace integration
     // We need to ensure that the integrations row is available for older workspaces
     if WorkspaceIntegration::get_integrations_for_workspace_pk(&ctx)
@@ -231,6 +245,7 @@ pub async fn auth_connect(
     HandlerContext(builder): HandlerContext,
     RequestUlidFromHeader(request_ulid): RequestUlidFromHeader,
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<AuthConnectRequest>,
 ) -> SessionResult<Json<AuthConnectResponse>> {
     let client = reqwest::Client::new();
@@ -265,6 +280,8 @@ pub async fn auth_connect(
         state.create_workspace_allowlist(),
         request.on_demand_assets.unwrap_or(false),
         state.spicedb_client_clone().as_mut(),
+        &res_body.token,
+        user_agent(&headers),
     )
     .await?;
 
@@ -283,6 +300,7 @@ pub async fn auth_reconnect(
     RequestUlidFromHeader(request_ulid): RequestUlidFromHeader,
     RawAccessToken(raw_access_token): RawAccessToken,
     State(state): State<AppState>,
+    headers: HeaderMap,
 ) -> SessionResult<Json<AuthReconnectResponse>> {
     let client = reqwest::Client::new();
     let auth_response = client
@@ -315,12 +333,21 @@ pub async fn auth_reconnect(
         state.create_workspace_allowlist(),
         auth_response_body.on_demand_assets.unwrap_or(false),
         state.spicedb_client_clone().as_mut(),
+        &raw_access_token,
+        user_agent(&headers),
     )
     .await?;
 
     Ok(Json(AuthReconnectResponse { user, workspace }))
 }
 
+fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(USER_AGENT)
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
 pub async fn user_has_permission_to_create_workspace(
     ctx: &DalContext,
     user: &User,
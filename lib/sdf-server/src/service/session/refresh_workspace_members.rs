@@ -3,11 +3,10 @@ use axum::{
     http::uri::Uri,
     Json,
 };
-use dal::{DalContext, User};
+use dal::{user::WorkspaceRole, DalContext, User, UserPk, WorkspacePk};
 use permissions::{ObjectType, Relation, RelationBuilder};
 use serde::{Deserialize, Serialize};
 use si_data_spicedb::SpiceDbClient;
-use strum::{Display, EnumString};
 
 use super::{SessionError, SessionResult};
 use crate::{
@@ -31,15 +30,6 @@ pub struct WorkspaceMember {
     pub role: WorkspaceRole,
 }
 
-#[derive(Clone, Display, Debug, Deserialize, EnumString, Serialize)]
-#[strum(serialize_all = "UPPERCASE")]
-#[serde(rename_all = "UPPERCASE")]
-pub enum WorkspaceRole {
-    Approver,
-    Editor,
-    Owner,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshWorkspaceMembersResponse {
@@ -102,6 +92,15 @@ pub async fn refresh_workspace_members(
     }
 
     let members = User::list_members_for_workspace(&ctx, request.workspace_id.clone()).await?;
+
+    if let Ok(workspace_pk) = request.workspace_id.parse::<WorkspacePk>() {
+        for member in &workspace_members {
+            if let Ok(user_pk) = member.user_id.parse::<UserPk>() {
+                User::set_workspace_role(&ctx, user_pk, workspace_pk, member.role).await?;
+            }
+        }
+    }
+
     let member_ids: Vec<_> = workspace_members.into_iter().map(|w| w.user_id).collect();
     let users_to_remove: Vec<_> = members
         .into_iter()
@@ -1,30 +1,73 @@
-use axum::Json;
+use axum::{
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
 use dal::{User, Workspace};
 use serde::{Deserialize, Serialize};
+use si_jwt_public_key::SiJwtClaimRole;
 
 use super::{SessionError, SessionResult};
-use crate::extract::{v1::AccessBuilder, workspace::WorkspaceAuthorization, HandlerContext};
+use crate::extract::{
+    request::ValidatedToken, v1::AccessBuilder, workspace::WorkspaceAuthorization, HandlerContext,
+};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RestoreAuthenticationResponse {
     pub user: User,
     pub workspace: Workspace,
+    /// The role granted by the session's token.
+    pub authorized_role: SiJwtClaimRole,
+    /// Every workspace the user is a member of, including [`Self::workspace`].
+    pub workspaces: Vec<Workspace>,
 }
 
 pub async fn restore_authentication(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
+    ValidatedToken(token): ValidatedToken,
     WorkspaceAuthorization {
         user, workspace_id, ..
     }: WorkspaceAuthorization,
-) -> SessionResult<Json<RestoreAuthenticationResponse>> {
+) -> SessionResult<Response> {
     let ctx = builder.build_head(access_builder).await?;
 
     let workspace = Workspace::get_by_pk(&ctx, &workspace_id)
         .await?
         .ok_or(SessionError::InvalidWorkspace(workspace_id))?;
-    let reply = RestoreAuthenticationResponse { user, workspace };
+    let workspaces = Workspace::list_for_user(&ctx).await?;
+    let reply = RestoreAuthenticationResponse {
+        user,
+        workspace,
+        authorized_role: token.custom.role(),
+        workspaces,
+    };
 
-    Ok(Json(reply))
+    Ok(with_no_store(Json(reply)))
+}
+
+/// Ensures clients never cache the session, so that they always revalidate it and never show stale
+/// user or workspace data (e.g. after a workspace rename).
+fn with_no_store(response: impl IntoResponse) -> Response {
+    (
+        [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        response,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_is_not_cacheable() {
+        let response = with_no_store(Json(serde_json::json!({})));
+
+        assert_eq!(
+            Some(&HeaderValue::from_static("no-store")),
+            response.headers().get(header::CACHE_CONTROL)
+        );
+    }
 }
@@ -0,0 +1,68 @@
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::user::session::UserSession;
+use serde::{Deserialize, Serialize};
+use si_id::UserSessionId;
+
+use super::SessionResult;
+use crate::extract::workspace::WorkspaceAuthorization;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionView {
+    pub id: UserSessionId,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub last_seen_at: DateTime<Utc>,
+    pub client: Option<String>,
+}
+
+impl From<UserSession> for SessionView {
+    fn from(session: UserSession) -> Self {
+        Self {
+            id: session.id(),
+            issued_at: session.issued_at(),
+            last_seen_at: session.last_seen_at(),
+            client: session.client().map(ToOwned::to_owned),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<SessionView>,
+}
+
+pub async fn list_sessions(
+    WorkspaceAuthorization {
+        ctx,
+        user,
+        workspace_id,
+        ..
+    }: WorkspaceAuthorization,
+) -> SessionResult<Json<ListSessionsResponse>> {
+    let sessions = UserSession::list_active(&ctx, user.pk(), workspace_id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ListSessionsResponse { sessions }))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeSessionRequest {
+    pub session_id: UserSessionId,
+}
+
+pub async fn revoke_session(
+    WorkspaceAuthorization { ctx, user, .. }: WorkspaceAuthorization,
+    Json(request): Json<RevokeSessionRequest>,
+) -> SessionResult<Json<SessionView>> {
+    let session = UserSession::revoke(&ctx, user.pk(), request.session_id).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(session.into()))
+}
@@ -9,7 +9,7 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use dal::{
-    cached_module::CachedModuleError, func::runner::FuncRunnerError,
+    cached_module::CachedModuleError, func::runner::FuncRunnerError, user::WorkspaceRole,
     workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants, ChangeSet, ChangeSetId,
     ChangeSetStatus, User, UserPk, Workspace, WorkspacePk, WorkspaceSnapshotAddress,
 };
@@ -135,6 +135,8 @@ pub struct AdminUser {
     pub id: UserPk,
     pub name: String,
     pub email: String,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub role: Option<WorkspaceRole>,
 }
 
 impl From<User> for AdminUser {
@@ -143,6 +145,8 @@ impl From<User> for AdminUser {
             id: value.pk(),
             name: value.name().to_owned(),
             email: value.email().to_owned(),
+            last_active_at: value.last_active_at(),
+            role: None,
         }
     }
 }
@@ -156,6 +160,7 @@ impl IntoResponse for AdminAPIError {
             AdminAPIError::FuncRunner(FuncRunnerError::DoNotHavePermissionToKillExecution) => {
                 StatusCode::UNAUTHORIZED
             }
+            AdminAPIError::User(dal::UserError::InvalidPagination(..)) => StatusCode::BAD_REQUEST,
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
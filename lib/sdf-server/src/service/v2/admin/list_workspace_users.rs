@@ -1,28 +1,50 @@
-use axum::{extract::Path, Json};
-use dal::{Tenancy, User, WorkspacePk};
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use dal::{user::MAX_WORKSPACE_MEMBERS, Tenancy, User, WorkspacePk};
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
 use crate::service::v2::admin::{AdminAPIResult, AdminUser, AdminUserContext};
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUsersForWorkspaceRequest {
+    limit: Option<i64>,
+    #[serde(default)]
+    offset: i64,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListUsersForWorkspaceResponse {
     users: Vec<AdminUser>,
+    has_more: bool,
 }
 
 #[instrument(name = "admin.list_users_for_workspace", skip_all)]
 pub async fn list_workspace_users(
     AdminUserContext(mut ctx): AdminUserContext,
     Path(workspace_id): Path<WorkspacePk>,
+    Query(request): Query<ListUsersForWorkspaceRequest>,
 ) -> AdminAPIResult<Json<ListUsersForWorkspaceResponse>> {
     ctx.update_tenancy(Tenancy::new(workspace_id));
 
-    let users = User::list_members_for_workspace(&ctx, workspace_id.to_string())
-        .await?
+    let (members, has_more) = User::list_members_with_roles_for_workspace(
+        &ctx,
+        workspace_id.to_string(),
+        request.limit.unwrap_or(MAX_WORKSPACE_MEMBERS),
+        request.offset,
+    )
+    .await?;
+    let users = members
         .into_iter()
-        .map(Into::into)
+        .map(|(user, role)| AdminUser {
+            role,
+            ..user.into()
+        })
         .collect();
 
-    Ok(Json(ListUsersForWorkspaceResponse { users }))
+    Ok(Json(ListUsersForWorkspaceResponse { users, has_more }))
 }
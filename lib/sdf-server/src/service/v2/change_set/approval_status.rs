@@ -5,7 +5,9 @@ use axum::{
 use dal::{ChangeSetId, WorkspacePk};
 
 use crate::{
-    dal_wrapper::ChangeSetApprovalCalculator, extract::HandlerContext, service::v2::AccessBuilder,
+    dal_wrapper::{self, ChangeSetApprovalCalculator},
+    extract::HandlerContext,
+    service::v2::AccessBuilder,
     AppState,
 };
 
@@ -27,10 +29,24 @@ pub async fn approval_status(
 
     let calculator = ChangeSetApprovalCalculator::new(&ctx, spicedb_client).await?;
 
+    // Surface requirements that nobody can approve so that the user can be warned before
+    // requesting approval for a change set that could never be applied.
+    let changes = ctx
+        .workspace_snapshot()?
+        .detect_changes_from_head(&ctx)
+        .await?;
+    let unsatisfiable_requirement_ids =
+        dal_wrapper::unsatisfiable_approval_requirements(&ctx, spicedb_client, &changes)
+            .await?
+            .into_iter()
+            .map(|requirement| requirement.entity_id)
+            .collect();
+
     Ok(Json(si_frontend_types::ChangeSetApprovals {
         requirements: calculator
             .frontend_requirements(&ctx, spicedb_client)
             .await?,
         latest_approvals: calculator.frontend_latest_approvals(),
+        unsatisfiable_requirement_ids,
     }))
 }
@@ -1,3 +1,5 @@
+use std::{future::Future, time::Duration};
+
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -5,18 +7,20 @@ use axum::{
     Router,
 };
 use dal::UserError;
+use module_index_client::ModuleIndexClientError;
 use si_frontend_types as frontend_types;
 use si_pkg::SiPkgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::{service::ApiError, AppState};
+use crate::{service::ApiError, AppState, ModuleIndexRetryConfig};
 
 mod builtins;
 mod contribute;
 mod list;
 mod module_by_hash;
 mod module_by_id;
+mod module_by_name;
 mod sync;
 
 pub type ModuleAPIResult<T> = Result<T, ModulesAPIError>;
@@ -26,22 +30,34 @@ pub type ModuleAPIResult<T> = Result<T, ModulesAPIError>;
 pub enum ModulesAPIError {
     #[error("axum http error: {0}")]
     AxumHttp(#[from] axum::http::Error),
-    #[error("module not contributed: {0:?}")]
-    ContributionFailure(frontend_types::ModuleContributeRequest),
+    #[error("module export failed for contribution {0:?}: {1}")]
+    ContributionExportFailure(
+        frontend_types::ModuleContributeRequest,
+        #[source] Box<dal::pkg::PkgError>,
+    ),
+    #[error("module upload failed for contribution {0:?}: {1}")]
+    ContributionUploadFailure(
+        frontend_types::ModuleContributeRequest,
+        #[source] ModuleIndexClientError,
+    ),
     #[error("module error: {0}")]
     Module(#[from] dal::module::ModuleError),
     #[error("Module hash not be found: {0}")]
     ModuleHashNotFound(String),
     #[error("module index client error: {0}")]
-    ModuleIndexClient(#[from] module_index_client::ModuleIndexClientError),
+    ModuleIndexClient(#[from] ModuleIndexClientError),
     #[error("module index not configured")]
     ModuleIndexNotConfigured,
+    #[error("Module could not be found by name: {0}")]
+    ModuleNameNotFound(String),
     #[error("schema error: {0}")]
     SchemaVariant(#[from] dal::SchemaVariantError),
     #[error("si pkg error: {0}")]
     SiPkg(#[from] SiPkgError),
     #[error("transactions error: {0}")]
     Transactions(#[from] dal::TransactionsError),
+    #[error("ulid decode error: {0}")]
+    UlidDecode(#[from] ulid::DecodeError),
     #[error("url parse error: {0}")]
     UrlParse(#[from] url::ParseError),
     #[error("user error: {0}")]
@@ -59,8 +75,14 @@ impl IntoResponse for ModulesAPIError {
                 StatusCode::NOT_FOUND
             }
             Self::Module(dal::module::ModuleError::EmptyMetadata(_, _)) => StatusCode::BAD_REQUEST,
-            Self::ContributionFailure(_) => StatusCode::BAD_REQUEST,
-            Self::ModuleHashNotFound(_) => StatusCode::NOT_FOUND,
+            Self::ContributionExportFailure(_, _) => StatusCode::BAD_REQUEST,
+            Self::ContributionUploadFailure(_, err) => {
+                module_index_client_status_code(err).unwrap_or(StatusCode::BAD_GATEWAY)
+            }
+            Self::ModuleHashNotFound(_) | Self::ModuleNameNotFound(_) => StatusCode::NOT_FOUND,
+            Self::ModuleIndexClient(err) => {
+                module_index_client_status_code(err).unwrap_or(ApiError::DEFAULT_ERROR_STATUS_CODE)
+            }
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
@@ -68,6 +90,47 @@ impl IntoResponse for ModulesAPIError {
     }
 }
 
+/// Maps the client error statuses the module index responds with onto our own, since they are
+/// caused by the request rather than by us.
+fn module_index_client_status_code(err: &ModuleIndexClientError) -> Option<StatusCode> {
+    match err.status()?.as_u16() {
+        404 => Some(StatusCode::NOT_FOUND),
+        401 | 403 => Some(StatusCode::FORBIDDEN),
+        429 => Some(StatusCode::TOO_MANY_REQUESTS),
+        _ => None,
+    }
+}
+
+/// Makes a call to the module index, retrying it with exponential backoff for as long as it fails
+/// with a transient error and attempts remain. Any other error is returned immediately.
+async fn with_retry<T, F, Fut>(
+    retry_config: ModuleIndexRetryConfig,
+    mut call: F,
+) -> Result<T, ModuleIndexClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ModuleIndexClientError>>,
+{
+    let mut backoff = Duration::from_millis(retry_config.initial_backoff_ms);
+    let mut attempt = 1;
+    loop {
+        match call().await {
+            Err(err) if err.is_transient() && attempt < retry_config.max_attempts => {
+                warn!(
+                    si.error.message = ?err,
+                    attempt,
+                    ?backoff,
+                    "transient module index error, retrying",
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 pub fn v2_routes() -> Router<AppState> {
     Router::new()
         .route("/contribute", post(contribute::contribute))
@@ -77,4 +140,50 @@ pub fn v2_routes() -> Router<AppState> {
         .route("/:module_id/builtins/promote", post(builtins::promote))
         .route("/module_by_hash", get(module_by_hash::module_by_hash))
         .route("/module_by_id", get(module_by_id::remote_module_by_id))
+        .route(
+            "/module_by_name",
+            get(module_by_name::remote_module_by_name),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    const RETRY_CONFIG: ModuleIndexRetryConfig = ModuleIndexRetryConfig {
+        max_attempts: 3,
+        initial_backoff_ms: 1,
+    };
+
+    #[tokio::test]
+    async fn with_retry_does_not_retry_permanent_errors() {
+        let attempts = &AtomicU32::new(0);
+
+        let result: Result<(), _> = with_retry(RETRY_CONFIG, move || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(ModuleIndexClientError::UrlParse(url::ParseError::EmptyHost))
+        })
+        .await;
+
+        assert!(matches!(result, Err(ModuleIndexClientError::UrlParse(_))));
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn with_retry_retries_transient_errors() {
+        let attempts = &AtomicU32::new(0);
+
+        let result: Result<(), _> = with_retry(RETRY_CONFIG, move || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            // Nothing listens on port 1, so connecting is refused
+            reqwest::get("http://127.0.0.1:1").await?;
+            Ok(())
+        })
+        .await;
+
+        assert!(matches!(result, Err(err) if err.is_transient()));
+        assert_eq!(RETRY_CONFIG.max_attempts, attempts.load(Ordering::SeqCst));
+    }
 }
@@ -1,12 +1,18 @@
 use axum::{
-    extract::{Host, OriginalUri, Path},
-    response::IntoResponse,
+    extract::{Host, OriginalUri, Path, Query},
+    response::{IntoResponse, Response},
     Json,
 };
-use dal::{module::Module, ChangeSetId, WorkspacePk};
+use dal::{
+    module::{Module, ModuleError},
+    pkg::PkgError,
+    ChangeSetId, SchemaId, SchemaVariantId, WorkspacePk,
+};
 use module_index_client::ModuleIndexClient;
+use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 use si_frontend_types as frontend_types;
+use si_pkg::SiPkg;
 
 use super::ModulesAPIError;
 use crate::{
@@ -15,6 +21,28 @@ use crate::{
     track,
 };
 
+#[derive(Deserialize, Debug, Default)]
+pub struct ContributeQuery {
+    /// When set, the module is exported and validated, but not uploaded to the module index.
+    #[serde(default)]
+    pub validate_only: bool,
+}
+
+/// The metadata a contribution would be uploaded with, returned when only validating.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributeValidationResponse {
+    pub name: String,
+    pub version: String,
+    pub based_on_hash: Option<String>,
+    pub schema_id: Option<SchemaId>,
+    pub schema_variant_id: SchemaVariantId,
+    pub schema_variant_version: String,
+    pub created_by_name: String,
+    pub created_by_email: String,
+    pub payload_size: usize,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn contribute(
     HandlerContext(builder): HandlerContext,
@@ -24,19 +52,13 @@ pub async fn contribute(
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(query): Query<ContributeQuery>,
     Json(request): Json<frontend_types::ModuleContributeRequest>,
-) -> Result<impl IntoResponse, ModulesAPIError> {
+) -> Result<Response, ModulesAPIError> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
 
-    // Prepare a module index client. We'll re-use it for every request.
-    let module_index_url = match ctx.module_index_url() {
-        Some(url) => url,
-        None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
-    };
-    let index_client = ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
-
     let (
         name,
         version,
@@ -52,7 +74,37 @@ pub async fn contribute(
         request.version.as_str(),
         request.schema_variant_id,
     )
-    .await?;
+    .await
+    .map_err(|err| match err {
+        ModuleError::Pkg(err) if is_export_failure(&err) => {
+            ModulesAPIError::ContributionExportFailure(request.clone(), err)
+        }
+        err => err.into(),
+    })?;
+
+    if query.validate_only {
+        validate_payload(&request, &payload).await?;
+
+        return Ok(Json(ContributeValidationResponse {
+            name,
+            version,
+            based_on_hash,
+            schema_id,
+            schema_variant_id: request.schema_variant_id,
+            schema_variant_version,
+            created_by_name,
+            created_by_email,
+            payload_size: payload.len(),
+        })
+        .into_response());
+    }
+
+    // Prepare a module index client. We'll re-use it for every request.
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
+    };
+    let index_client = ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
 
     let response = index_client
         .upload_module(
@@ -64,7 +116,8 @@ pub async fn contribute(
             Some(request.schema_variant_id.to_string()),
             Some(schema_variant_version.clone()),
         )
-        .await?;
+        .await
+        .map_err(|err| ModulesAPIError::ContributionUploadFailure(request.clone(), err))?;
 
     ctx.write_audit_log(
         AuditLogKind::ContributeModule {
@@ -96,5 +149,41 @@ pub async fn contribute(
     );
     ctx.commit().await?;
 
-    Ok(axum::response::Response::builder().body(axum::body::Empty::new())?)
+    Ok(axum::response::Response::builder()
+        .body(axum::body::Empty::new())?
+        .into_response())
+}
+
+/// Reads the exported payload back as a package and checks the integrity of its schemas and
+/// funcs, as the module index would on upload.
+async fn validate_payload(
+    request: &frontend_types::ModuleContributeRequest,
+    payload: &[u8],
+) -> Result<(), ModulesAPIError> {
+    let export_failure =
+        |err: PkgError| ModulesAPIError::ContributionExportFailure(request.clone(), Box::new(err));
+
+    let spec = SiPkg::load_from_bytes(payload)
+        .map_err(|err| export_failure(err.into()))?
+        .to_spec()
+        .await
+        .map_err(|err| export_failure(err.into()))?;
+    spec.validate()
+        .map_err(|errors| export_failure(PkgError::InvalidPkgSpec(errors)))
+}
+
+/// Whether a module could not be exported for contribution because of what it contains, as
+/// opposed to the variant not being found or the export failing for reasons of our own.
+fn is_export_failure(err: &PkgError) -> bool {
+    matches!(
+        err,
+        PkgError::DanglingFuncReference { .. }
+            | PkgError::InvalidPkgSpec(_)
+            | PkgError::MissingAttributePrototypeFunc(..)
+            | PkgError::MissingExportedFunc(..)
+            | PkgError::MissingFuncUniqueId(..)
+            | PkgError::MissingIntrinsicFunc(_)
+            | PkgError::PkgSpec(_)
+            | PkgError::PropSpecChildrenInvalid(_)
+    )
 }
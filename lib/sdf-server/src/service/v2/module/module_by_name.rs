@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Host, OriginalUri, Path, Query},
+    Json,
+};
+use dal::{ChangeSetId, WorkspacePk};
+use module_index_client::ModuleIndexClient;
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use ulid::Ulid;
+
+use crate::{
+    extract::{request::RawAccessToken, HandlerContext, PosthogClient},
+    service::v2::AccessBuilder,
+    track,
+};
+
+use super::{module_by_id::RemoteModuleDetailsResponse, ModuleAPIResult, ModulesAPIError};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRemoteModuleByNameRequest {
+    pub name: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn remote_module_by_name(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(request): Query<GetRemoteModuleByNameRequest>,
+) -> ModuleAPIResult<Json<RemoteModuleDetailsResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(ModulesAPIError::ModuleIndexNotConfigured),
+    };
+
+    let module_index_client =
+        ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
+
+    // The module index may hold several modules with the same name, so pick the one with the
+    // most recently published hash
+    let latest_module = module_index_client
+        .list_module_details()
+        .await?
+        .modules
+        .into_iter()
+        .filter(|module| module.name == request.name)
+        .max_by_key(|module| module.latest_hash_created_at)
+        .ok_or_else(|| ModulesAPIError::ModuleNameNotFound(request.name.clone()))?;
+
+    let pkg_data = module_index_client
+        .download_module(Ulid::from_string(&latest_module.id)?)
+        .await?;
+
+    let pkg = SiPkg::load_from_bytes(&pkg_data)?;
+    let spec = pkg.to_spec().await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "remote_module_spec_by_name",
+        serde_json::json!({
+            "pkg_name": &spec.name,
+        }),
+    );
+
+    Ok(Json(spec))
+}
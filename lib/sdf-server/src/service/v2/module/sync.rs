@@ -1,16 +1,16 @@
 use axum::{
-    extract::{Host, OriginalUri, Path},
+    extract::{Host, OriginalUri, Path, State},
     Json,
 };
 use dal::{module::Module, ChangeSetId, WorkspacePk};
 use module_index_client::ModuleIndexClient;
 use si_frontend_types as frontend_types;
 
-use super::ModulesAPIError;
+use super::{with_retry, ModulesAPIError};
 use crate::{
     extract::{request::RawAccessToken, HandlerContext, PosthogClient},
     service::v2::AccessBuilder,
-    track,
+    track, ModuleIndexRetryConfig,
 };
 
 pub async fn sync(
@@ -20,6 +20,7 @@ pub async fn sync(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    State(retry_config): State<ModuleIndexRetryConfig>,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
 ) -> Result<Json<frontend_types::SyncedModules>, ModulesAPIError> {
     let ctx = builder
@@ -32,20 +33,22 @@ pub async fn sync(
             .ok_or(ModulesAPIError::ModuleIndexNotConfigured)?;
         let module_index_client =
             ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token);
+        let client = &module_index_client;
         (
-            module_index_client.list_latest_modules().await?,
-            module_index_client.list_builtins().await?,
-            module_index_client.list_module_details().await?,
+            with_retry(retry_config, move || client.list_latest_modules()).await?,
+            with_retry(retry_config, move || client.list_builtins()).await?,
+            with_retry(retry_config, move || client.list_module_details()).await?,
         )
     };
 
-    let synced_modules = Module::sync(
+    let mut synced_modules = Module::sync(
         &ctx,
         latest_modules.modules,
         module_details.modules,
         all_modules.modules,
     )
     .await?;
+    synced_modules.summary = synced_modules.summarize();
 
     track(
         &posthog_client,
@@ -53,7 +56,11 @@ pub async fn sync(
         &original_uri,
         &host_name,
         "sync",
-        serde_json::json!({}),
+        serde_json::json!({
+            "installable_count": synced_modules.summary.installable,
+            "upgradeable_count": synced_modules.summary.upgradeable,
+            "contributable_count": synced_modules.summary.contributable,
+        }),
     );
 
     Ok(Json(synced_modules))
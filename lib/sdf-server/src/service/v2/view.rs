@@ -88,6 +88,9 @@ impl IntoResponse for ViewError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
             ViewError::NameAlreadyInUse(_) => (StatusCode::CONFLICT, self.to_string()),
+            ViewError::Frame(FrameError::WouldCreateCycle { .. }) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
             ViewError::DalDiagram(
                 dal::diagram::DiagramError::DeletingLastGeometryForComponent(_, _),
             )
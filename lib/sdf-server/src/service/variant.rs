@@ -2,11 +2,13 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::post,
-    Router,
+    Json, Router,
 };
 use dal::{
-    pkg::PkgError, schema::variant::authoring::VariantAuthoringError, ChangeSetError, FuncError,
-    FuncId, SchemaError, SchemaId, SchemaVariantId, TransactionsError, WsEventError,
+    pkg::PkgError,
+    schema::variant::authoring::{AssetFuncDiagnostic, VariantAuthoringError},
+    ChangeSetError, FuncError, FuncId, SchemaError, SchemaId, SchemaVariantId, TransactionsError,
+    WsEventError,
 };
 use si_pkg::{SiPkgError, SpecError};
 use telemetry::prelude::*;
@@ -24,6 +26,8 @@ pub mod save_variant;
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum SchemaVariantError {
+    #[error("asset func failed with {} diagnostic(s)", .0.len())]
+    AssetFuncDiagnostics(Vec<AssetFuncDiagnostic>),
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
     #[error("trying to create unlocked copy for schema variant that's not the default: {0}")]
@@ -74,8 +78,35 @@ pub enum SchemaVariantError {
 
 pub type SchemaVariantResult<T> = Result<T, SchemaVariantError>;
 
+impl SchemaVariantError {
+    /// Converts a variant authoring error, surfacing asset func failures as diagnostics.
+    pub fn from_variant_authoring(err: VariantAuthoringError) -> Self {
+        match err.diagnostics() {
+            Some(diagnostics) => Self::AssetFuncDiagnostics(diagnostics),
+            None => Self::VariantAuthoring(err),
+        }
+    }
+}
+
 impl IntoResponse for SchemaVariantError {
     fn into_response(self) -> Response {
+        if let SchemaVariantError::AssetFuncDiagnostics(diagnostics) = &self {
+            let status_code = StatusCode::BAD_REQUEST;
+            warn!(err = ?self, ?status_code, "asset func failed with diagnostics");
+
+            return (
+                status_code,
+                Json(serde_json::json!({
+                    "error": {
+                        "message": self.to_string(),
+                        "statusCode": status_code.as_u16(),
+                        "diagnostics": diagnostics,
+                    },
+                })),
+            )
+                .into_response();
+        }
+
         let (status_code, error_message) = match self {
             SchemaVariantError::FuncNotFound(_)
             | SchemaVariantError::NoDefaultSchemaVariantFoundForSchema(_)
@@ -1,17 +1,23 @@
 use axum::{
-    extract::{Host, OriginalUri},
+    extract::{Host, OriginalUri, Query},
+    response::{IntoResponse, Response},
     Json,
 };
 use dal::{
-    schema::variant::authoring::VariantAuthoringClient, ChangeSet, SchemaVariant, SchemaVariantId,
-    Visibility, WsEvent,
+    schema::variant::authoring::{
+        AssetFuncDiagnostic, VariantAuthoringClient, VariantAuthoringError,
+    },
+    ChangeSet, DalContext, SchemaVariant, SchemaVariantId, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 use si_events::audit_log::AuditLogKind;
 
 use crate::{
     extract::{v1::AccessBuilder, HandlerContext, PosthogClient},
-    service::{force_change_set_response::ForceChangeSetResponse, variant::SchemaVariantResult},
+    service::{
+        force_change_set_response::ForceChangeSetResponse,
+        variant::{SchemaVariantError, SchemaVariantResult},
+    },
     track,
 };
 
@@ -25,26 +31,48 @@ pub struct RegenerateVariantRequest {
     pub visibility: Visibility,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct RegenerateVariantParams {
+    /// When set, only validates the asset code and metadata without forking a change set or
+    /// applying the update.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RegenerateVariantResponse {
     pub schema_variant_id: SchemaVariantId,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateVariantResponse {
+    pub success: bool,
+    pub error: Option<String>,
+    pub diagnostics: Vec<AssetFuncDiagnostic>,
+}
+
 pub async fn regenerate_variant(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    Query(RegenerateVariantParams { dry_run }): Query<RegenerateVariantParams>,
     Json(RegenerateVariantRequest {
         variant,
         code,
         visibility,
     }): Json<RegenerateVariantRequest>,
-) -> SchemaVariantResult<ForceChangeSetResponse<RegenerateVariantResponse>> {
+) -> SchemaVariantResult<Response> {
     let mut ctx = builder.build(request_ctx.build(visibility)).await?;
 
+    if dry_run {
+        let response = validate_variant(&ctx, variant, code).await?;
+        return Ok(Json(response).into_response());
+    }
+
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
     let schema_variant_id = variant.schema_variant_id;
@@ -64,7 +92,9 @@ pub async fn regenerate_variant(
     .await?;
 
     let updated_schema_variant_id =
-        VariantAuthoringClient::regenerate_variant(&ctx, schema_variant_id).await?;
+        VariantAuthoringClient::regenerate_variant(&ctx, schema_variant_id)
+            .await
+            .map_err(SchemaVariantError::from_variant_authoring)?;
 
     track(
         &posthog_client,
@@ -112,5 +142,52 @@ pub async fn regenerate_variant(
         RegenerateVariantResponse {
             schema_variant_id: updated_schema_variant_id,
         },
-    ))
+    )
+    .into_response())
+}
+
+/// Saves the variant content and runs its asset func to validate it, returning any failure as a
+/// diagnostic. The context is never committed, so none of the changes are persisted.
+async fn validate_variant(
+    ctx: &DalContext,
+    variant: si_frontend_types::SchemaVariant,
+    code: Option<String>,
+) -> SchemaVariantResult<ValidateVariantResponse> {
+    VariantAuthoringClient::save_variant_content(
+        ctx,
+        variant.schema_variant_id,
+        &variant.schema_name,
+        &variant.display_name,
+        &variant.category,
+        variant.description,
+        variant.link,
+        &variant.color,
+        variant.component_type.into(),
+        code,
+    )
+    .await?;
+
+    let asset_func = SchemaVariant::get_by_id_or_error(ctx, variant.schema_variant_id)
+        .await?
+        .get_asset_func(ctx)
+        .await?;
+
+    let response = match VariantAuthoringClient::execute_asset_func(ctx, &asset_func).await {
+        Ok(_) => ValidateVariantResponse {
+            success: true,
+            error: None,
+            diagnostics: Vec::new(),
+        },
+        Err(
+            err @ (VariantAuthoringError::AssetTypeNotReturnedForAssetFunc(_, _)
+            | VariantAuthoringError::FuncExecutionFailure(_)),
+        ) => ValidateVariantResponse {
+            success: false,
+            diagnostics: err.diagnostics().unwrap_or_default(),
+            error: Some(err.to_string()),
+        },
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(response)
 }
@@ -350,3 +350,71 @@ async fn single_user_relation_existence_and_checksum_validility_permutations(
 
     Ok(())
 }
+
+#[sdf_test]
+async fn unsatisfiable_approval_requirements(
+    ctx: &mut DalContext,
+    spicedb_client: SpiceDbClient,
+) -> Result<()> {
+    let mut spicedb_client = spicedb_client;
+
+    // FIXME(nick,jacob): see the comment attached to this function.
+    write_schema(&mut spicedb_client).await?;
+
+    let workspace_id = ctx.workspace_pk()?;
+    let user_id = match ctx.history_actor() {
+        HistoryActor::SystemInit => return Err(eyre!("invalid user")),
+        HistoryActor::User(user_id) => *user_id,
+    };
+
+    let schema = create_schema(ctx).await?;
+    let (variant, _) = SchemaVariant::new(
+        ctx,
+        schema.id(),
+        "paul mccartney",
+        "paul".to_string(),
+        "beatles",
+        "#FFFFFF",
+        ComponentType::Component,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await?;
+    let entity_id: si_id::EntityId = {
+        let raw_id: si_id::ulid::Ulid = variant.id().into();
+        raw_id.into()
+    };
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    // Without any relations in SpiceDB, the permission lookup matches no users, so the schema
+    // variant requirement can never be satisfied.
+    let changes = ctx
+        .workspace_snapshot()?
+        .detect_changes_from_head(ctx)
+        .await?;
+    let unsatisfiable =
+        dal_wrapper::unsatisfiable_approval_requirements(ctx, &mut spicedb_client, &changes)
+            .await?;
+    let unsatisfiable_entity_ids: Vec<si_id::EntityId> =
+        unsatisfiable.iter().map(|r| r.entity_id).collect();
+    assert_eq!(
+        vec![entity_id],          // expected
+        unsatisfiable_entity_ids  // actual
+    );
+
+    // Once a user can approve, the requirement is satisfiable.
+    let relation = RelationBuilder::new()
+        .object(ObjectType::Workspace, workspace_id)
+        .relation(Relation::Approver)
+        .subject(ObjectType::User, user_id);
+    relation.create(&mut spicedb_client).await?;
+
+    let unsatisfiable =
+        dal_wrapper::unsatisfiable_approval_requirements(ctx, &mut spicedb_client, &changes)
+            .await?;
+    assert!(unsatisfiable.is_empty());
+
+    Ok(())
+}
@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 
+use serde_json::Value;
 use si_std::SensitiveString;
 
 const REDACTED_TXT: &str = "[redacted]";
@@ -29,6 +30,11 @@ impl SensitiveStrings {
         self.0.extend(iter)
     }
 
+    /// Returns `true` if no "sensitive" substrings are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Returns whether or not the given string contains at least one sensitive substring.
     pub fn has_sensitive(&self, s: &str) -> bool {
         self.0
@@ -52,6 +58,59 @@ impl SensitiveStrings {
 
         redacted
     }
+
+    /// Redacts any "sensitive" substrings from every string within a JSON value, including those
+    /// nested in arrays and objects. Object keys are left as-is.
+    pub fn redact_value(&self, value: &mut Value) {
+        self.redact_value_inner(value, false);
+    }
+
+    /// Redacts a JSON value as [`redact_value`](Self::redact_value) does, returning the JSON
+    /// pointer paths (e.g. `/data/password`) of the strings which were redacted.
+    pub fn redact_value_with_paths(&self, value: &mut Value) -> Vec<String> {
+        self.redact_value_inner(value, true)
+    }
+
+    fn redact_value_inner(&self, value: &mut Value, track_paths: bool) -> Vec<String> {
+        let mut redacted_paths = Vec::new();
+
+        // Paths are only built when tracking them, to keep the default path allocation-free
+        let child_path = |parent: &str, token: &str| -> String {
+            if track_paths {
+                format!("{parent}/{}", token.replace('~', "~0").replace('/', "~1"))
+            } else {
+                String::new()
+            }
+        };
+
+        // Walk the value with a work queue rather than recursion so that pathologically deep
+        // values can't overflow the stack
+        let mut work_queue = vec![(String::new(), value)];
+        while let Some((path, work)) = work_queue.pop() {
+            match work {
+                Value::Array(values) => work_queue.extend(
+                    values
+                        .iter_mut()
+                        .enumerate()
+                        .map(|(index, v)| (child_path(&path, &index.to_string()), v)),
+                ),
+                Value::Object(object) => work_queue.extend(
+                    object
+                        .iter_mut()
+                        .map(|(key, v)| (child_path(&path, key), v)),
+                ),
+                Value::String(string) if self.has_sensitive(string) => {
+                    *string = self.redact(string);
+                    if track_paths {
+                        redacted_paths.push(path);
+                    }
+                }
+                Value::String(_) | Value::Null | Value::Number(_) | Value::Bool(_) => {}
+            }
+        }
+
+        redacted_paths
+    }
 }
 
 impl From<HashSet<SensitiveString>> for SensitiveStrings {
@@ -127,4 +186,39 @@ mod tests {
             sensitive_strings.redact("One pony said to the other pony: 'I have an apple.'")
         );
     }
+
+    #[test]
+    fn redact_value_redacts_nested_strings() {
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("hunter2");
+
+        let mut value = serde_json::json!({
+            "password": "hunter2",
+            "nested": { "list": ["ok", "the hunter2 secret"], "count": 2 },
+        });
+        sensitive_strings.redact_value(&mut value);
+
+        assert_eq!(
+            serde_json::json!({
+                "password": "[redacted]",
+                "nested": { "list": ["ok", "the [redacted] secret"], "count": 2 },
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn redact_value_with_paths_returns_json_pointers() {
+        let mut sensitive_strings = SensitiveStrings::default();
+        sensitive_strings.insert("hunter2");
+
+        let mut value = serde_json::json!({
+            "a/b": { "list": ["ok", "hunter2"] },
+            "plain": "hunter2",
+        });
+        let mut paths = sensitive_strings.redact_value_with_paths(&mut value);
+        paths.sort();
+
+        assert_eq!(vec!["/a~1b/list/1", "/plain"], paths);
+    }
 }
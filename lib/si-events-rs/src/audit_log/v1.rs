@@ -68,6 +68,10 @@ pub enum AuditLogKindV1 {
         component_id: Option<ComponentId>,
         subject_name: String,
     },
+    AttachComponentToFrame {
+        child_id: ComponentId,
+        parent_id: ComponentId,
+    },
     AttachManagementFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -172,6 +176,10 @@ pub enum AuditLogKindV1 {
     DeleteView {
         view_id: ViewId,
     },
+    DetachComponentFromFrame {
+        child_id: ComponentId,
+        former_parent_id: ComponentId,
+    },
     DetachFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -236,6 +244,13 @@ pub enum AuditLogKindV1 {
     ReopenChangeSet {
         from_status: ChangeSetStatus,
     },
+    RepairComponentFrameParents {
+        child_id: ComponentId,
+        former_parent_ids: Vec<ComponentId>,
+    },
+    RequestChangeSetAbandon {
+        from_status: ChangeSetStatus,
+    },
     RequestChangeSetApproval {
         from_status: ChangeSetStatus,
     },
@@ -448,6 +463,11 @@ pub enum AuditLogMetadataV1 {
         subject_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    AttachComponentToFrame {
+        child_id: ComponentId,
+        parent_id: ComponentId,
+    },
+    #[serde(rename_all = "camelCase")]
     AttachManagementFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -561,6 +581,11 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     DeleteView { view_id: ViewId },
     #[serde(rename_all = "camelCase")]
+    DetachComponentFromFrame {
+        child_id: ComponentId,
+        former_parent_id: ComponentId,
+    },
+    #[serde(rename_all = "camelCase")]
     DetachFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -630,6 +655,13 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     ReopenChangeSet { from_status: ChangeSetStatus },
     #[serde(rename_all = "camelCase")]
+    RepairComponentFrameParents {
+        child_id: ComponentId,
+        former_parent_ids: Vec<ComponentId>,
+    },
+    #[serde(rename_all = "camelCase")]
+    RequestChangeSetAbandon { from_status: ChangeSetStatus },
+    #[serde(rename_all = "camelCase")]
     RequestChangeSetApproval { from_status: ChangeSetStatus },
     #[serde(rename_all = "camelCase")]
     RetryAction {
@@ -806,6 +838,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::AttachAttributeFunc => ("Attached", Some("Attribute Function")),
             MetadataDiscrim::AttachAuthFunc => ("Attached", Some("Authentication Function")),
             MetadataDiscrim::AttachCodeGenFunc => ("Attached", Some("Code Generation Function")),
+            MetadataDiscrim::AttachComponentToFrame => ("Attached", Some("Component to Frame")),
             MetadataDiscrim::AttachManagementFunc => ("Attached", Some("Management Function")),
             MetadataDiscrim::AttachQualificationFunc => {
                 ("Attached", Some("Qualification Function"))
@@ -827,6 +860,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::DeleteSchemaVariant => ("Deleted", Some("Schema Variant")),
             MetadataDiscrim::DeleteSecret => ("Deleted", Some("Secret")),
             MetadataDiscrim::DeleteView => ("Deleted", Some("View")),
+            MetadataDiscrim::DetachComponentFromFrame => ("Detached", Some("Component from Frame")),
             MetadataDiscrim::DetachFunc => ("Detached", Some("Function")),
             MetadataDiscrim::ExecuteFunc => ("Executed", Some("Function")),
             MetadataDiscrim::ExportWorkspace => ("Exported", Some("Workspace")),
@@ -843,6 +877,12 @@ impl AuditLogMetadataV1 {
                 ("Rejected Request to Apply", Some("Change Set"))
             }
             MetadataDiscrim::ReopenChangeSet => ("Reopened", Some("Change Set")),
+            MetadataDiscrim::RepairComponentFrameParents => {
+                ("Repaired", Some("Component Frame Parents"))
+            }
+            MetadataDiscrim::RequestChangeSetAbandon => {
+                ("Requested to Abandon", Some("Change Set"))
+            }
             MetadataDiscrim::RequestChangeSetApproval => ("Requested to Apply", Some("Change Set")),
             MetadataDiscrim::RetryAction => ("Retried", Some("Action")),
             MetadataDiscrim::RunAction => ("Ran", Some("Action")),
@@ -948,6 +988,13 @@ impl From<Kind> for Metadata {
                 component_id,
                 subject_name,
             },
+            Kind::AttachComponentToFrame {
+                child_id,
+                parent_id,
+            } => Self::AttachComponentToFrame {
+                child_id,
+                parent_id,
+            },
             Kind::AttachManagementFunc {
                 func_id,
                 func_display_name,
@@ -1117,6 +1164,13 @@ impl From<Kind> for Metadata {
             },
             Kind::DeleteSecret { name, secret_id } => Self::DeleteSecret { name, secret_id },
             Kind::DeleteView { view_id } => Self::DeleteView { view_id },
+            Kind::DetachComponentFromFrame {
+                child_id,
+                former_parent_id,
+            } => Self::DetachComponentFromFrame {
+                child_id,
+                former_parent_id,
+            },
             Kind::DetachFunc {
                 func_id,
                 func_display_name,
@@ -1201,6 +1255,16 @@ impl From<Kind> for Metadata {
                 Self::RejectChangeSetApply { from_status }
             }
             Kind::ReopenChangeSet { from_status } => Self::ReopenChangeSet { from_status },
+            Kind::RepairComponentFrameParents {
+                child_id,
+                former_parent_ids,
+            } => Self::RepairComponentFrameParents {
+                child_id,
+                former_parent_ids,
+            },
+            Kind::RequestChangeSetAbandon { from_status } => {
+                Self::RequestChangeSetAbandon { from_status }
+            }
             Kind::RequestChangeSetApproval { from_status } => {
                 Self::RequestChangeSetApproval { from_status }
             }
@@ -6,7 +6,7 @@ use crate::create_xxhash_type;
 create_xxhash_type!(Checksum);
 
 #[remain::sorted]
-#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub enum EntityKind {
     Action,
     ActionPrototype,
@@ -29,6 +29,9 @@ pub struct ChangeSet {
 #[serde(rename_all = "camelCase")]
 pub struct CreateChangeSetRequest {
     pub change_set_name: String,
+    /// The change set to base the new change set on, instead of HEAD.
+    #[serde(default)]
+    pub base_change_set_id: Option<ChangeSetId>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -42,6 +45,8 @@ pub struct CreateChangeSetResponse {
 pub struct ChangeSetApprovals {
     pub requirements: Vec<ChangeSetApprovalRequirement>,
     pub latest_approvals: Vec<ChangeSetApproval>,
+    // Which requirements can never be satisfied because nobody can approve them?
+    pub unsatisfiable_requirement_ids: Vec<EntityId>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
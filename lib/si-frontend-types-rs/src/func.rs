@@ -1,5 +1,8 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
+use thiserror::Error;
 
 pub use si_events::FuncKind;
 use si_events::{
@@ -38,6 +41,64 @@ pub struct FuncSummary {
     pub backend_kind: FuncBackendKind,
 }
 
+impl FuncSummary {
+    /// Returns whether the name or display name contains the query, ignoring case. An empty query
+    /// matches every func.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self
+                .display_name
+                .as_ref()
+                .is_some_and(|display_name| display_name.to_lowercase().contains(&query))
+    }
+}
+
+/// A list of [`FuncSummaries`](FuncSummary) with helpers for the filtering and sorting done when
+/// displaying them.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct FuncSummaryList(pub Vec<FuncSummary>);
+
+impl FuncSummaryList {
+    /// Keeps only the funcs of the given [`FuncKind`].
+    pub fn filter_by_kind(self, kind: FuncKind) -> Self {
+        Self(
+            self.0
+                .into_iter()
+                .filter(|summary| summary.kind == kind)
+                .collect(),
+        )
+    }
+
+    /// Keeps only the funcs matching the query (see [`FuncSummary::matches_search`]).
+    pub fn search(self, query: &str) -> Self {
+        Self(
+            self.0
+                .into_iter()
+                .filter(|summary| summary.matches_search(query))
+                .collect(),
+        )
+    }
+
+    /// Sorts the funcs by name, ignoring case.
+    pub fn sorted_by_name(mut self) -> Self {
+        self.0
+            .sort_by_cached_key(|summary| summary.name.to_lowercase());
+        self
+    }
+
+    pub fn into_inner(self) -> Vec<FuncSummary> {
+        self.0
+    }
+}
+
+impl From<Vec<FuncSummary>> for FuncSummaryList {
+    fn from(value: Vec<FuncSummary>) -> Self {
+        Self(value)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FuncArgument {
@@ -55,11 +116,103 @@ pub struct FuncCode {
     pub func_id: FuncId,
     pub code: String,
 }
+
+impl FuncCode {
+    /// Returns a copy of the code with `\n` line endings, no trailing whitespace on any line and
+    /// exactly one trailing newline. Code that is entirely whitespace normalizes to an empty string.
+    pub fn normalize(&self) -> Self {
+        let code = self.code.replace("\r\n", "\n").replace('\r', "\n");
+        let mut normalized = code
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string();
+        if !normalized.is_empty() {
+            normalized.push('\n');
+        }
+
+        Self {
+            func_id: self.func_id,
+            code: normalized,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FuncBindings {
     pub bindings: Vec<FuncBinding>,
 }
+
+impl FuncBindings {
+    /// Checks that the bindings are internally consistent with the provided [`FuncArguments`](FuncArgument),
+    /// collecting every problem found rather than stopping at the first one.
+    pub fn validate(&self, args: &[FuncArgument]) -> Result<(), FuncBindingValidationError> {
+        let known_arg_ids: HashSet<FuncArgumentId> = args.iter().filter_map(|arg| arg.id).collect();
+        let mut problems = Vec::new();
+
+        for binding in &self.bindings {
+            if let FuncBinding::Attribute {
+                attribute_prototype_id,
+                argument_bindings,
+                ..
+            } = binding
+            {
+                let mut seen_arg_ids = HashSet::new();
+                for argument_binding in argument_bindings {
+                    let func_argument_id = argument_binding.func_argument_id;
+                    if !seen_arg_ids.insert(func_argument_id) {
+                        problems.push(FuncBindingProblem::DuplicateArgumentBinding {
+                            attribute_prototype_id: *attribute_prototype_id,
+                            func_argument_id,
+                        });
+                    }
+                    if !known_arg_ids.contains(&func_argument_id) {
+                        problems.push(FuncBindingProblem::UnknownFuncArgument {
+                            attribute_prototype_id: *attribute_prototype_id,
+                            func_argument_id,
+                        });
+                    }
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(FuncBindingValidationError { problems })
+        }
+    }
+}
+
+/// The set of problems found when validating [`FuncBindings`].
+#[derive(Clone, Debug, Deserialize, Eq, Error, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[error("func bindings are invalid ({} problem(s) found)", problems.len())]
+pub struct FuncBindingValidationError {
+    pub problems: Vec<FuncBindingProblem>,
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, Error, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "problemKind")]
+pub enum FuncBindingProblem {
+    #[error("func argument {func_argument_id} is bound more than once (attribute prototype: {attribute_prototype_id:?})")]
+    #[serde(rename_all = "camelCase")]
+    DuplicateArgumentBinding {
+        attribute_prototype_id: Option<AttributePrototypeId>,
+        func_argument_id: FuncArgumentId,
+    },
+    #[error("argument binding references unknown func argument {func_argument_id} (attribute prototype: {attribute_prototype_id:?})")]
+    #[serde(rename_all = "camelCase")]
+    UnknownFuncArgument {
+        attribute_prototype_id: Option<AttributePrototypeId>,
+        func_argument_id: FuncArgumentId,
+    },
+}
+
 #[remain::sorted]
 #[derive(
     AsRefStr,
@@ -205,6 +358,38 @@ pub enum LeafInputLocation {
     Secrets,
 }
 
+impl LeafInputLocation {
+    /// Returns every [`LeafInputLocation`] variant.
+    pub fn all() -> &'static [LeafInputLocation] {
+        &[
+            LeafInputLocation::Code,
+            LeafInputLocation::DeletedAt,
+            LeafInputLocation::Domain,
+            LeafInputLocation::Resource,
+            LeafInputLocation::Secrets,
+        ]
+    }
+
+    /// Returns the canonical func argument name for the location.
+    pub fn arg_name(&self) -> &'static str {
+        match self {
+            LeafInputLocation::Code => "code",
+            LeafInputLocation::DeletedAt => "deleted_at",
+            LeafInputLocation::Domain => "domain",
+            LeafInputLocation::Resource => "resource",
+            LeafInputLocation::Secrets => "secrets",
+        }
+    }
+
+    /// Returns the location matching the canonical func argument name, if one exists.
+    pub fn maybe_from_arg_name(arg_name: impl AsRef<str>) -> Option<Self> {
+        Self::all()
+            .iter()
+            .find(|location| location.arg_name() == arg_name.as_ref())
+            .copied()
+    }
+}
+
 #[remain::sorted]
 #[derive(
     AsRefStr,
@@ -233,3 +418,221 @@ pub enum FuncArgumentKind {
     Object,
     String,
 }
+
+impl FuncArgumentKind {
+    /// Returns every [`FuncArgumentKind`] variant.
+    pub fn all() -> &'static [FuncArgumentKind] {
+        &[
+            FuncArgumentKind::Any,
+            FuncArgumentKind::Array,
+            FuncArgumentKind::Boolean,
+            FuncArgumentKind::Integer,
+            FuncArgumentKind::Json,
+            FuncArgumentKind::Map,
+            FuncArgumentKind::Object,
+            FuncArgumentKind::String,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func_argument(id: FuncArgumentId) -> FuncArgument {
+        FuncArgument {
+            id: Some(id),
+            name: "arg".to_string(),
+            kind: FuncArgumentKind::String,
+            element_kind: None,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    fn argument_binding(func_argument_id: FuncArgumentId) -> AttributeArgumentBinding {
+        AttributeArgumentBinding {
+            func_argument_id,
+            attribute_prototype_argument_id: None,
+            prop_id: None,
+            input_socket_id: None,
+            static_value: None,
+        }
+    }
+
+    fn attribute_binding(argument_bindings: Vec<AttributeArgumentBinding>) -> FuncBinding {
+        FuncBinding::Attribute {
+            func_id: None,
+            attribute_prototype_id: None,
+            component_id: None,
+            schema_variant_id: None,
+            prop_id: None,
+            output_socket_id: None,
+            argument_bindings,
+        }
+    }
+
+    #[test]
+    fn validate_func_bindings() {
+        let known = FuncArgumentId::new();
+        let unknown = FuncArgumentId::new();
+
+        let valid = FuncBindings {
+            bindings: vec![attribute_binding(vec![argument_binding(known)])],
+        };
+        assert_eq!(Ok(()), valid.validate(&[func_argument(known)]));
+
+        let invalid = FuncBindings {
+            bindings: vec![attribute_binding(vec![
+                argument_binding(known),
+                argument_binding(known),
+                argument_binding(unknown),
+            ])],
+        };
+        let error = invalid
+            .validate(&[func_argument(known)])
+            .expect_err("bindings should be invalid");
+        assert_eq!(
+            vec![
+                FuncBindingProblem::DuplicateArgumentBinding {
+                    attribute_prototype_id: None,
+                    func_argument_id: known,
+                },
+                FuncBindingProblem::UnknownFuncArgument {
+                    attribute_prototype_id: None,
+                    func_argument_id: unknown,
+                },
+            ],
+            error.problems
+        );
+
+        let serialized = serde_json::to_value(&error).expect("serialize error");
+        let deserialized: FuncBindingValidationError =
+            serde_json::from_value(serialized).expect("deserialize error");
+        assert_eq!(error, deserialized);
+    }
+
+    #[test]
+    fn leaf_input_location_arg_name_round_trip() {
+        for location in LeafInputLocation::all() {
+            assert_eq!(
+                Some(*location),
+                LeafInputLocation::maybe_from_arg_name(location.arg_name())
+            );
+        }
+        assert_eq!(
+            None,
+            LeafInputLocation::maybe_from_arg_name("not_a_location")
+        );
+    }
+
+    #[test]
+    fn func_argument_kind_all_matches_iter() {
+        use std::str::FromStr;
+
+        use strum::IntoEnumIterator;
+
+        assert_eq!(
+            FuncArgumentKind::iter().collect::<Vec<_>>(),
+            FuncArgumentKind::all()
+        );
+        for kind in FuncArgumentKind::all() {
+            assert_eq!(
+                *kind,
+                FuncArgumentKind::from_str(&kind.to_string()).expect("failed to parse")
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_func_code() {
+        let func_id = FuncId::new();
+        let normalize = |code: &str| {
+            FuncCode {
+                func_id,
+                code: code.to_string(),
+            }
+            .normalize()
+            .code
+        };
+
+        assert_eq!(
+            "function main() {\n  return 1;\n}\n",
+            normalize("function main() {  \r\n  return 1;\t\r\n}\r\n")
+        );
+        assert_eq!("a\n\nb\n", normalize("a\r\rb"));
+        assert_eq!("main();\n", normalize("main();"));
+        assert_eq!("main();\n", normalize("main();\n\n  \n"));
+        assert_eq!("", normalize(" \r\n\n"));
+
+        let normalized = FuncCode {
+            func_id,
+            code: "main();\n".to_string(),
+        };
+        assert_eq!(normalized, normalized.normalize());
+    }
+
+    fn func_summary(kind: FuncKind, name: &str, display_name: Option<&str>) -> FuncSummary {
+        FuncSummary {
+            func_id: FuncId::new(),
+            kind,
+            name: name.to_string(),
+            display_name: display_name.map(ToString::to_string),
+            description: None,
+            is_locked: false,
+            arguments: Vec::new(),
+            bindings: Vec::new(),
+            types: None,
+            backend_kind: FuncBackendKind::JsAttribute,
+        }
+    }
+
+    #[test]
+    fn filter_search_and_sort_func_summaries() {
+        let list = FuncSummaryList::from(vec![
+            func_summary(FuncKind::Attribute, "si:setString", Some("Set String")),
+            func_summary(
+                FuncKind::Action,
+                "awsEc2Create",
+                Some("Create EC2 Instance"),
+            ),
+            func_summary(FuncKind::Attribute, "awsRegionFromString", None),
+            func_summary(FuncKind::Qualification, "Check Region", None),
+        ]);
+
+        let names = |list: FuncSummaryList| {
+            list.into_inner()
+                .into_iter()
+                .map(|summary| summary.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec!["awsRegionFromString", "si:setString"],
+            names(
+                list.clone()
+                    .filter_by_kind(FuncKind::Attribute)
+                    .sorted_by_name()
+            )
+        );
+        // matches on the raw name, ignoring case
+        assert_eq!(
+            vec!["awsRegionFromString", "Check Region"],
+            names(list.clone().search("REGION"))
+        );
+        // matches on the display name
+        assert_eq!(vec!["awsEc2Create"], names(list.clone().search("ec2 inst")));
+        assert_eq!(
+            vec!["si:setString", "awsRegionFromString"],
+            names(list.clone().search("string"))
+        );
+        assert_eq!(
+            vec![
+                "awsEc2Create",
+                "awsRegionFromString",
+                "Check Region",
+                "si:setString"
+            ],
+            names(list.search("").sorted_by_name())
+        );
+    }
+}
@@ -18,15 +18,16 @@ pub use crate::component::{
 };
 pub use crate::conflict::ConflictWithHead;
 pub use crate::func::{
-    AttributeArgumentBinding, FuncArgument, FuncArgumentKind, FuncBinding, FuncBindings, FuncCode,
-    FuncKind, FuncSummary, LeafInputLocation,
+    AttributeArgumentBinding, FuncArgument, FuncArgumentKind, FuncBinding, FuncBindingProblem,
+    FuncBindingValidationError, FuncBindings, FuncCode, FuncKind, FuncSummary, FuncSummaryList,
+    LeafInputLocation,
 };
 pub use crate::module::{
     BuiltinModules, LatestModule, ModuleContributeRequest, ModuleDetails, ModuleSummary,
-    SyncedModules,
+    SyncedModules, SyncedModulesSummary, UpdateStatus,
 };
 pub use crate::schema_variant::{
-    ComponentType, InputSocket, ListVariantsResponse, OutputSocket, Prop, PropKind, SchemaVariant,
-    UninstalledVariant,
+    ComponentType, InputSocket, ListVariantsResponse, OutputSocket, Prop, PropBuilder,
+    PropBuilderError, PropKind, SchemaVariant, UninstalledVariant,
 };
 pub use crate::workspace::WorkspaceMetadata;
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 pub use module_index_types::BuiltinsDetailsResponse as BuiltinModules;
@@ -13,12 +14,56 @@ pub struct SyncedModules {
     pub upgradeable: HashMap<SchemaVariantId, LatestModule>,
     pub installable: Vec<LatestModule>,
     pub contributable: Vec<SchemaVariantId>,
+    #[serde(default)]
+    pub summary: SyncedModulesSummary,
 }
 
 impl SyncedModules {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Counts the modules in each list, so that clients don't need to.
+    pub fn summarize(&self) -> SyncedModulesSummary {
+        SyncedModulesSummary {
+            installable: self.installable.len(),
+            upgradeable: self.upgradeable.len(),
+            contributable: self.contributable.len(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Serialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedModulesSummary {
+    pub installable: usize,
+    pub upgradeable: usize,
+    pub contributable: usize,
+}
+
+/// Whether a locally installed module is behind the latest version of it in the module index.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateStatus {
+    UpToDate,
+    Upgradeable,
+    NotInstalled,
+}
+
+impl UpdateStatus {
+    /// Classifies the installed module, given as its hash and creation time, against the latest
+    /// version of it.
+    pub fn classify(latest: &LatestModule, installed: Option<(&str, DateTime<Utc>)>) -> Self {
+        match installed {
+            None => Self::NotInstalled,
+            Some((installed_hash, installed_created_at))
+                if latest.is_newer_than(installed_hash, installed_created_at) =>
+            {
+                Self::Upgradeable
+            }
+            Some(_) => Self::UpToDate,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
@@ -35,3 +80,55 @@ pub struct ModuleSummary {
     pub name: String,
     pub hash: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn latest_module(latest_hash: &str, latest_hash_created_at: DateTime<Utc>) -> LatestModule {
+        LatestModule {
+            id: "01JBJ6B2GGAMZ4YF8Z2S0WEQ2T".to_string(),
+            name: "AWS EC2 Instance".to_string(),
+            description: None,
+            owner_user_id: "owner".to_string(),
+            owner_display_name: None,
+            metadata: serde_json::Value::Null,
+            latest_hash: latest_hash.to_string(),
+            latest_hash_created_at,
+            created_at: latest_hash_created_at,
+            schema_id: None,
+        }
+    }
+
+    #[test]
+    fn classify_update_status() {
+        let earlier = Utc
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .single()
+            .expect("invalid date");
+        let later = Utc
+            .with_ymd_and_hms(2024, 6, 1, 0, 0, 0)
+            .single()
+            .expect("invalid date");
+        let latest = latest_module("new", later);
+
+        assert!(latest.is_newer_than("old", earlier));
+        assert!(!latest.is_newer_than("new", earlier));
+        assert!(!latest.is_newer_than("old", later));
+
+        assert_eq!(
+            UpdateStatus::NotInstalled,
+            UpdateStatus::classify(&latest, None)
+        );
+        assert_eq!(
+            UpdateStatus::Upgradeable,
+            UpdateStatus::classify(&latest, Some(("old", earlier)))
+        );
+        assert_eq!(
+            UpdateStatus::UpToDate,
+            UpdateStatus::classify(&latest, Some(("new", later)))
+        );
+    }
+}
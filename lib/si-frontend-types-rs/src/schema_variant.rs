@@ -3,6 +3,7 @@ use si_events::{
     FuncId, InputSocketId, OutputSocketId, PropId, SchemaId, SchemaVariantId, Timestamp,
 };
 use strum::{AsRefStr, Display, EnumIter, EnumString};
+use thiserror::Error;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -67,9 +68,24 @@ pub struct UninstalledVariant {
 )]
 #[serde(rename_all = "camelCase")]
 pub enum ComponentType {
+    // The canonical string form is kebab-case, though the camelCase serde form is parsed too (and
+    // vice versa), so that either can be used wherever a component type is given as a string.
+    #[serde(alias = "aggregation-frame")]
+    #[strum(to_string = "aggregation-frame", serialize = "aggregationFrame")]
     AggregationFrame,
+    #[strum(to_string = "component")]
     Component,
+    #[serde(alias = "configuration-frame-down")]
+    #[strum(
+        to_string = "configuration-frame-down",
+        serialize = "configurationFrameDown"
+    )]
     ConfigurationFrameDown,
+    #[serde(alias = "configuration-frame-up")]
+    #[strum(
+        to_string = "configuration-frame-up",
+        serialize = "configurationFrameUp"
+    )]
     ConfigurationFrameUp,
 }
 
@@ -101,9 +117,137 @@ pub struct Prop {
     pub eligible_to_send_data: bool,
 }
 
+impl Prop {
+    /// Creates a [`PropBuilder`] for constructing a tree of props, rooted at a prop with the given
+    /// name and kind.
+    pub fn builder(name: impl Into<String>, kind: PropKind) -> PropBuilder {
+        PropBuilder::new(name, kind)
+    }
+}
+
+/// Builds a tree of [`Props`](Prop), checking that each prop's children are consistent with its
+/// kind: an array or map has exactly one child (the type of its elements), an object has any
+/// number of children, and all other kinds have none.
+#[derive(Clone, Debug)]
+pub struct PropBuilder {
+    name: String,
+    kind: PropKind,
+    hidden: bool,
+    eligible_to_receive_data: bool,
+    eligible_to_send_data: bool,
+    children: Vec<PropBuilder>,
+}
+
+impl PropBuilder {
+    pub fn new(name: impl Into<String>, kind: PropKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            hidden: false,
+            eligible_to_receive_data: false,
+            eligible_to_send_data: false,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    pub fn eligible_to_receive_data(mut self, eligible: bool) -> Self {
+        self.eligible_to_receive_data = eligible;
+        self
+    }
+
+    pub fn eligible_to_send_data(mut self, eligible: bool) -> Self {
+        self.eligible_to_send_data = eligible;
+        self
+    }
+
+    pub fn child(mut self, child: PropBuilder) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Builds the tree, rooted at `parent_path` (such as `/root/domain`), into a list of props in
+    /// which every prop precedes its children.
+    pub fn build(self, parent_path: &str) -> Result<Vec<Prop>, PropBuilderError> {
+        let mut props = Vec::new();
+        let mut work_queue = vec![(parent_path.trim_end_matches('/').to_owned(), self)];
+        while let Some((parent_path, builder)) = work_queue.pop() {
+            let path = format!("{parent_path}/{}", builder.name);
+            let child_count = builder.children.len();
+            match builder.kind {
+                PropKind::Array | PropKind::Map if child_count != 1 => {
+                    return Err(PropBuilderError::ExpectedSingleChild {
+                        path,
+                        kind: builder.kind,
+                        child_count,
+                    });
+                }
+                PropKind::Any
+                | PropKind::Boolean
+                | PropKind::Integer
+                | PropKind::Json
+                | PropKind::String
+                    if child_count > 0 =>
+                {
+                    return Err(PropBuilderError::ChildrenNotAllowed {
+                        path,
+                        kind: builder.kind,
+                        child_count,
+                    });
+                }
+                _ => {}
+            }
+
+            // Children are pushed in reverse so that they are built in the order they were added
+            work_queue.extend(
+                builder
+                    .children
+                    .into_iter()
+                    .rev()
+                    .map(|child| (path.clone(), child)),
+            );
+            props.push(Prop {
+                id: PropId::new(),
+                kind: builder.kind,
+                name: builder.name,
+                path,
+                hidden: builder.hidden,
+                eligible_to_receive_data: builder.eligible_to_receive_data,
+                eligible_to_send_data: builder.eligible_to_send_data,
+            });
+        }
+
+        Ok(props)
+    }
+}
+
 #[remain::sorted]
-#[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum PropBuilderError {
+    #[error("{kind} prop at {path} cannot have children, but has {child_count}")]
+    ChildrenNotAllowed {
+        path: String,
+        kind: PropKind,
+        child_count: usize,
+    },
+    #[error("{kind} prop at {path} must have exactly one child, but has {child_count}")]
+    ExpectedSingleChild {
+        path: String,
+        kind: PropKind,
+        child_count: usize,
+    },
+}
+
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Debug, Deserialize, Display, EnumIter, EnumString, Eq, Serialize, PartialEq,
+)]
 #[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
 pub enum PropKind {
     Any,
     Array,
@@ -114,3 +258,136 @@ pub enum PropKind {
     Object,
     String,
 }
+
+impl PropKind {
+    /// Returns every [`PropKind`] variant.
+    pub fn all() -> &'static [PropKind] {
+        &[
+            PropKind::Any,
+            PropKind::Array,
+            PropKind::Boolean,
+            PropKind::Integer,
+            PropKind::Json,
+            PropKind::Map,
+            PropKind::Object,
+            PropKind::String,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn component_type_string_round_trip() {
+        for component_type in ComponentType::iter() {
+            assert_eq!(
+                component_type,
+                ComponentType::from_str(&component_type.to_string())
+                    .expect("failed to parse display string")
+            );
+            assert_eq!(
+                component_type,
+                serde_json::from_value(serde_json::json!(component_type.to_string()))
+                    .expect("failed to deserialize display string")
+            );
+
+            let serialized = serde_json::to_value(component_type).expect("failed to serialize");
+            assert_eq!(
+                component_type,
+                ComponentType::from_str(serialized.as_str().expect("serialized as a string"))
+                    .expect("failed to parse serde string")
+            );
+        }
+    }
+
+    #[test]
+    fn component_type_canonical_strings() {
+        assert_eq!(
+            vec![
+                "aggregation-frame",
+                "component",
+                "configuration-frame-down",
+                "configuration-frame-up",
+            ],
+            ComponentType::iter()
+                .map(|component_type| component_type.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert!(ComponentType::from_str("frame").is_err());
+    }
+
+    #[test]
+    fn prop_kind_all_matches_serde_strings() {
+        assert_eq!(PropKind::iter().collect::<Vec<_>>(), PropKind::all());
+        for kind in PropKind::all() {
+            assert_eq!(
+                serde_json::json!(kind.to_string()),
+                serde_json::to_value(kind).expect("failed to serialize")
+            );
+            assert_eq!(
+                kind,
+                &PropKind::from_str(&kind.to_string()).expect("failed to parse")
+            );
+        }
+    }
+
+    #[test]
+    fn prop_builder_builds_tree_in_order() {
+        let props = Prop::builder("tags", PropKind::Map)
+            .child(
+                Prop::builder("tag", PropKind::Object)
+                    .child(Prop::builder("key", PropKind::String))
+                    .child(Prop::builder("values", PropKind::Array).child(
+                        Prop::builder("value", PropKind::String).eligible_to_receive_data(true),
+                    )),
+            )
+            .build("/root/domain/")
+            .expect("failed to build props");
+
+        assert_eq!(
+            vec![
+                "/root/domain/tags",
+                "/root/domain/tags/tag",
+                "/root/domain/tags/tag/key",
+                "/root/domain/tags/tag/values",
+                "/root/domain/tags/tag/values/value",
+            ],
+            props
+                .iter()
+                .map(|prop| prop.path.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(props[4].eligible_to_receive_data);
+    }
+
+    #[test]
+    fn prop_builder_rejects_inconsistent_children() {
+        assert_eq!(
+            Err(PropBuilderError::ExpectedSingleChild {
+                path: "/root/domain/ports".to_owned(),
+                kind: PropKind::Array,
+                child_count: 0,
+            }),
+            Prop::builder("ports", PropKind::Array).build("/root/domain")
+        );
+        assert_eq!(
+            Err(PropBuilderError::ChildrenNotAllowed {
+                path: "/root/domain/object/name".to_owned(),
+                kind: PropKind::String,
+                child_count: 1,
+            }),
+            Prop::builder("object", PropKind::Object)
+                .child(
+                    Prop::builder("name", PropKind::String)
+                        .child(Prop::builder("oops", PropKind::String)),
+                )
+                .build("/root/domain")
+        );
+    }
+}
@@ -72,6 +72,7 @@ id_with_pg_types!(ComponentId);
 id_with_pg_types!(FuncId);
 id_with_pg_types!(FuncRunId);
 id_with_pg_types!(UserPk);
+id_with_pg_types!(UserSessionId);
 id_with_pg_types!(WorkspaceIntegrationId);
 
 // Please keep these alphabetically sorted!
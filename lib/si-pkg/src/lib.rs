@@ -75,6 +75,50 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pkg_prop_order_round_trip() {
+        let mut spec: serde_json::Value = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let entries = spec["schemas"][0]["variants"][0]["domain"]["entries"]
+            .as_array_mut()
+            .expect("domain has entries");
+        entries.reverse();
+        let mut expected = Vec::new();
+        for (order, entry) in entries.iter_mut().enumerate() {
+            let name = entry["name"].as_str().expect("entry has a name").to_owned();
+            if entry["data"].is_null() {
+                entry["data"] = serde_json::json!({ "name": name });
+            }
+            entry["data"]["order"] = order.into();
+            expected.push(name);
+        }
+        let spec: PkgSpec = serde_json::from_value(spec).expect("failed to parse spec");
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(&pkg_data).expect("failed to load pkg from bytes");
+
+        let variant_spec = read_pkg
+            .schemas()
+            .expect("get schema")
+            .pop()
+            .expect("has schema")
+            .variants()
+            .expect("get variants")
+            .pop()
+            .expect("has a variant")
+            .to_spec()
+            .await
+            .expect("convert variant to spec");
+
+        let names: Vec<&str> = variant_spec
+            .domain
+            .direct_children()
+            .into_iter()
+            .map(PropSpec::name)
+            .collect();
+        assert_eq!(expected, names);
+    }
+
     #[tokio::test]
     async fn pkg_bytes_round_trip() {
         let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
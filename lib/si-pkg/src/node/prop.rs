@@ -24,6 +24,7 @@ const KEY_HIDDEN_STR: &str = "hidden";
 const KEY_DOC_LINK_STR: &str = "doc_link";
 const KEY_DOCUMENTATION_STR: &str = "documentation";
 const KEY_VALIDATION_FORMAT_STR: &str = "validation_format";
+const KEY_ORDER_STR: &str = "order";
 const KEY_UNIQUE_ID_STR: &str = "unique_id";
 
 const PROP_TY_STRING: &str = "string";
@@ -45,6 +46,7 @@ pub struct PropNodeData {
     pub hidden: bool,
     pub documentation: Option<String>,
     pub validation_format: Option<String>,
+    pub order: Option<u32>,
 }
 
 #[remain::sorted]
@@ -172,6 +174,7 @@ impl WriteBytes for PropNode {
                 KEY_VALIDATION_FORMAT_STR,
                 data.validation_format.as_ref(),
             )?;
+            write_key_value_line_opt(writer, KEY_ORDER_STR, data.order)?;
         }
 
         if let Some(unique_id) = match &self {
@@ -236,6 +239,10 @@ impl ReadBytes for PropNode {
 
                 let documentation = read_key_value_line_opt(reader, KEY_DOCUMENTATION_STR)?;
                 let validation_format = read_key_value_line_opt(reader, KEY_VALIDATION_FORMAT_STR)?;
+                let order = read_key_value_line_opt(reader, KEY_ORDER_STR)?
+                    .map(|order| u32::from_str(&order))
+                    .transpose()
+                    .map_err(GraphError::parse)?;
 
                 Some(PropNodeData {
                     name: name.to_owned(),
@@ -247,6 +254,7 @@ impl ReadBytes for PropNode {
                     hidden,
                     documentation,
                     validation_format,
+                    order,
                 })
             }
         };
@@ -355,6 +363,7 @@ impl NodeChild for PropSpec {
                          doc_link,
                          documentation,
                          validation_format,
+                         order,
                          ..
                      }| PropNodeData {
                         name,
@@ -366,6 +375,7 @@ impl NodeChild for PropSpec {
                         doc_link,
                         documentation,
                         validation_format,
+                        order,
                     },
                 ),
                 unique_id.to_owned(),
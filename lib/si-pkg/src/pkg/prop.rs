@@ -20,6 +20,7 @@ pub struct SiPkgPropData {
     pub hidden: bool,
     pub documentation: Option<String>,
     pub validation_format: Option<String>,
+    pub order: Option<u32>,
 }
 
 #[remain::sorted]
@@ -185,6 +186,7 @@ impl<'a> SiPkgProp<'a> {
                          doc_link,
                          documentation,
                          validation_format,
+                         order,
                      }| SiPkgPropData {
                         name,
                         default_value,
@@ -195,6 +197,7 @@ impl<'a> SiPkgProp<'a> {
                         doc_link,
                         documentation,
                         validation_format,
+                        order,
                     },
                 ),
                 unique_id.to_owned(),
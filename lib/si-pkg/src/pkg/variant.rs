@@ -223,6 +223,11 @@ impl<'a> SiPkgSchemaVariant<'a> {
                             parent_info.to_owned(),
                         ));
                     }
+                    // The entries are popped off the end of the stack, so put the first prop last
+                    // to visit siblings in their original order
+                    entries.sort_by_key(|(prop, _)| {
+                        std::cmp::Reverse(prop.data().and_then(|data| data.order))
+                    });
 
                     entries
                 }
@@ -360,7 +365,8 @@ impl<'a> SiPkgSchemaVariant<'a> {
         let prop_parents = context.prop_parents.into_inner();
         let mut prop_children: HashMap<String, Vec<PropSpec>> = HashMap::new();
         for (path, mut prop) in prop_stack {
-            if let Some(children) = prop_children.get(&path) {
+            if let Some(children) = prop_children.get_mut(&path) {
+                children.sort_by_key(|child| child.data().and_then(|data| data.order));
                 match prop
                     .get_kind()
                     .ok_or(SiPkgError::prop_tree_invalid("Prop missing a kind"))?
@@ -548,6 +554,7 @@ async fn create_prop_stack(
                 widget_options,
                 func_unique_id,
                 hidden,
+                order,
                 ..
             }) = data
             {
@@ -556,6 +563,10 @@ async fn create_prop_stack(
                     .hidden(*hidden)
                     .widget_kind(*widget_kind);
 
+                if let Some(order) = order {
+                    builder.order(*order);
+                }
+
                 if let Some(widget_options) = widget_options {
                     builder.widget_options(widget_options.to_owned());
                 }
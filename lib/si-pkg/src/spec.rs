@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use chrono::{DateTime, Utc};
 use derive_builder::{Builder, UninitializedFieldError};
 use serde::{Deserialize, Serialize};
@@ -86,6 +88,111 @@ impl PkgSpec {
             .iter()
             .find(|func_spec| func_spec.name.as_str() == name)
     }
+
+    /// Checks the structural invariants of the spec, returning every problem found rather than
+    /// stopping at the first one.
+    ///
+    /// The checks are that func, schema, and variant unique ids are not duplicated, that every
+    /// func referenced by a variant is present in the spec (including intrinsic funcs, which are
+    /// exported like any other func), and that each schema's default variant is one of its
+    /// variants.
+    pub fn validate(&self) -> Result<(), Vec<SpecError>> {
+        let mut errors = Vec::new();
+
+        let mut func_unique_ids = HashSet::new();
+        for func in &self.funcs {
+            if !func_unique_ids.insert(func.unique_id.as_str()) {
+                errors.push(SpecError::DuplicateUniqueId("func", func.unique_id.clone()));
+            }
+        }
+
+        let mut schema_unique_ids = HashSet::new();
+        let mut variant_unique_ids = HashSet::new();
+        for schema in &self.schemas {
+            if let Some(unique_id) = &schema.unique_id {
+                if !schema_unique_ids.insert(unique_id.as_str()) {
+                    errors.push(SpecError::DuplicateUniqueId("schema", unique_id.clone()));
+                }
+            }
+
+            for variant in &schema.variants {
+                if let Some(unique_id) = &variant.unique_id {
+                    if !variant_unique_ids.insert(unique_id.as_str()) {
+                        errors.push(SpecError::DuplicateUniqueId("variant", unique_id.clone()));
+                    }
+                }
+
+                let referenced_by = format!("{} variant {}", schema.name, variant.version);
+                for func_unique_id in variant_func_references(variant) {
+                    if !func_unique_ids.contains(func_unique_id) {
+                        errors.push(SpecError::MissingFuncReference(
+                            referenced_by.clone(),
+                            func_unique_id.to_owned(),
+                        ));
+                    }
+                }
+            }
+
+            let default_variant = schema
+                .data
+                .as_ref()
+                .and_then(|data| data.default_schema_variant.as_deref());
+            if let Some(default_variant) = default_variant {
+                if !schema
+                    .variants
+                    .iter()
+                    .any(|variant| variant.unique_id.as_deref() == Some(default_variant))
+                {
+                    errors.push(SpecError::MissingDefaultSchemaVariant(
+                        schema.name.clone(),
+                        default_variant.to_owned(),
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Returns the unique ids of the funcs a variant refers to directly.
+fn variant_func_references(variant: &SchemaVariantSpec) -> Vec<&str> {
+    let mut references = Vec::new();
+    if let Some(data) = &variant.data {
+        references.push(data.func_unique_id.as_str());
+    }
+    for func in &variant.action_funcs {
+        references.push(func.func_unique_id.as_str());
+    }
+    for func in &variant.auth_funcs {
+        references.push(func.func_unique_id.as_str());
+    }
+    for func in &variant.leaf_functions {
+        references.push(func.func_unique_id.as_str());
+    }
+    for func in &variant.management_funcs {
+        references.push(func.func_unique_id.as_str());
+    }
+    for func in &variant.si_prop_funcs {
+        references.push(func.func_unique_id.as_str());
+    }
+    for func in &variant.root_prop_funcs {
+        references.push(func.func_unique_id.as_str());
+    }
+    for socket in &variant.sockets {
+        if let Some(func_unique_id) = socket
+            .data
+            .as_ref()
+            .and_then(|data| data.func_unique_id.as_deref())
+        {
+            references.push(func_unique_id);
+        }
+    }
+    references
 }
 
 impl PkgSpecBuilder {
@@ -119,8 +226,14 @@ impl TryFrom<PkgSpecBuilder> for PkgSpec {
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum SpecError {
+    #[error("duplicate {0} unique id: {1}")]
+    DuplicateUniqueId(&'static str, String),
     #[error("Can't convert {0} to LeafInputLocation")]
     LeafInputLocationConversionError(String),
+    #[error("default schema variant {1} for schema {0} is not one of its variants")]
+    MissingDefaultSchemaVariant(String, String),
+    #[error("func {1} referenced by {0} is missing from the package")]
+    MissingFuncReference(String, String),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     /// Uninitialized field
@@ -142,3 +255,102 @@ impl From<String> for SpecError {
         Self::ValidationError(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn func(unique_id: &str) -> FuncSpec {
+        FuncSpec::builder()
+            .name(unique_id)
+            .unique_id(unique_id)
+            .build()
+            .expect("build func spec")
+    }
+
+    fn pkg_spec(funcs: Vec<FuncSpec>, schemas: Vec<SchemaSpec>) -> PkgSpec {
+        PkgSpec::builder()
+            .name("monte_cristo")
+            .version("1")
+            .created_by("edmond")
+            .funcs(funcs)
+            .schemas(schemas)
+            .build()
+            .expect("build pkg spec")
+    }
+
+    #[test]
+    fn validate_accepts_consistent_spec() {
+        let schema = SchemaSpec::builder()
+            .name("chateau_d_if")
+            .unique_id("schema")
+            .variant(
+                SchemaVariantSpec::builder()
+                    .version("v0")
+                    .unique_id("variant")
+                    .data(
+                        SchemaVariantSpecData::builder()
+                            .version("v0")
+                            .func_unique_id("asset")
+                            .build()
+                            .expect("build variant spec data"),
+                    )
+                    .build()
+                    .expect("build variant spec"),
+            )
+            .build()
+            .expect("build schema spec");
+
+        let spec = pkg_spec(vec![func("asset")], vec![schema]);
+
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_reports_all_problems() {
+        let schema = SchemaSpec::builder()
+            .name("chateau_d_if")
+            .unique_id("schema")
+            .data(
+                SchemaSpecData::builder()
+                    .name("chateau_d_if")
+                    .category("prisons")
+                    .default_schema_variant("missing_variant")
+                    .build()
+                    .expect("build schema spec data"),
+            )
+            .variant(
+                SchemaVariantSpec::builder()
+                    .version("v0")
+                    .unique_id("variant")
+                    .data(
+                        SchemaVariantSpecData::builder()
+                            .version("v0")
+                            .func_unique_id("missing_asset")
+                            .build()
+                            .expect("build variant spec data"),
+                    )
+                    .build()
+                    .expect("build variant spec"),
+            )
+            .build()
+            .expect("build schema spec");
+
+        let spec = pkg_spec(vec![func("dantes"), func("dantes")], vec![schema]);
+
+        let errors = spec.validate().expect_err("spec should be invalid");
+        assert_eq!(3, errors.len());
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            SpecError::DuplicateUniqueId("func", unique_id) if unique_id == "dantes"
+        )));
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            SpecError::MissingFuncReference(_, unique_id) if unique_id == "missing_asset"
+        )));
+        assert!(errors.iter().any(|err| matches!(
+            err,
+            SpecError::MissingDefaultSchemaVariant(_, unique_id) if unique_id == "missing_variant"
+        )));
+    }
+}
@@ -2,7 +2,11 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
 use super::SpecError;
-use super::{attribute_value::AttributeValueSpec, position::PositionSpec};
+use super::{
+    attribute_value::{AttributeValuePath, AttributeValueSpec},
+    position::PositionSpec,
+    PropSpec, SchemaVariantSpecPropRoot, PROP_PATH_SEPARATOR,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -55,4 +59,113 @@ impl ComponentSpec {
     pub fn builder() -> ComponentSpecBuilder {
         ComponentSpecBuilder::default()
     }
+
+    /// Clears the values of all attributes in the component's resource value tree, which may hold
+    /// cloud identifiers or secrets. The attributes themselves (and the functions which set them)
+    /// are kept, so the values are recomputed once the resource is refreshed.
+    pub fn strip_resource_data(&mut self) {
+        let resource_value_path =
+            PropSpec::make_path(SchemaVariantSpecPropRoot::ResourceValue.path_parts(), None);
+
+        for attribute in self.attributes.iter_mut().filter(|attribute| {
+            matches!(
+                &attribute.path,
+                AttributeValuePath::Prop { path, .. }
+                    if path == &resource_value_path
+                        || path
+                            .strip_prefix(&resource_value_path)
+                            .is_some_and(|rest| rest.starts_with(PROP_PATH_SEPARATOR))
+            )
+        }) {
+            attribute.value = None;
+            attribute.unprocessed_value = None;
+            attribute.implicit_value = None;
+            attribute.output_stream = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{FuncSpecBackendKind, FuncSpecBackendResponseType};
+
+    fn attribute(path: &[&str], value: serde_json::Value) -> AttributeValueSpec {
+        AttributeValueSpec::builder()
+            .path(AttributeValuePath::Prop {
+                path: PropSpec::make_path(path, None),
+                key_or_index: None,
+            })
+            .func_unique_id("si:setString")
+            .func_binding_args(json!({}))
+            .backend_kind(FuncSpecBackendKind::String)
+            .response_type(FuncSpecBackendResponseType::String)
+            .value(value.clone())
+            .unprocessed_value(value)
+            .build()
+            .expect("build attribute value spec")
+    }
+
+    fn component() -> ComponentSpec {
+        ComponentSpec::builder()
+            .name("nautilus")
+            .position(
+                PositionSpec::builder()
+                    .x("0")
+                    .y("0")
+                    .width(None::<String>)
+                    .height(None::<String>)
+                    .build()
+                    .expect("build position spec"),
+            )
+            .variant(ComponentSpecVariant::WorkspaceVariant {
+                variant_unique_id: "variant".to_owned(),
+            })
+            .needs_destroy(false)
+            .deletion_user_pk(None::<String>)
+            .unique_id("component")
+            .deleted(false)
+            .attribute(attribute(&["root", "domain", "name"], json!("nemo")))
+            .attribute(attribute(&["root", "resource_value"], json!({})))
+            .attribute(attribute(
+                &["root", "resource_value", "arn"],
+                json!("arn:aws:ec2:us-east-1:123456789012:instance/i-1234"),
+            ))
+            .attribute(attribute(&["root", "resource_values"], json!("kept")))
+            .build()
+            .expect("build component spec")
+    }
+
+    #[test]
+    fn strip_resource_data_clears_resource_values() {
+        let mut component = component();
+        component.strip_resource_data();
+
+        let values: Vec<_> = component
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.value.clone(), attribute.unprocessed_value.clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Some(json!("nemo")), Some(json!("nemo"))),
+                (None, None),
+                (None, None),
+                (Some(json!("kept")), Some(json!("kept"))),
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn resource_values_kept_without_stripping() {
+        let component = component();
+
+        assert_eq!(
+            Some(json!("arn:aws:ec2:us-east-1:123456789012:instance/i-1234")),
+            component.attributes[2].value
+        );
+    }
 }
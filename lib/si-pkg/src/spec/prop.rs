@@ -64,6 +64,8 @@ pub struct PropSpecData {
     pub hidden: Option<bool>,
     pub doc_link: Option<Url>,
     pub documentation: Option<String>,
+    /// The position of this prop among its siblings, so that their order survives a round trip.
+    pub order: Option<u32>,
 }
 
 #[remain::sorted]
@@ -197,6 +199,9 @@ impl PropSpec {
             if let Some(hidden) = data.hidden {
                 builder.hidden(hidden);
             }
+            if let Some(order) = data.order {
+                builder.order(order);
+            }
         }
 
         if let PropSpec::Map {
@@ -494,6 +499,7 @@ pub struct PropSpecBuilder {
     kind: Option<PropSpecKind>,
     map_key_funcs: Vec<MapKeyFuncSpec>,
     name: Option<String>,
+    order: Option<u32>,
     type_prop: Option<PropSpec>,
     validation_format: Option<String>,
     widget_kind: Option<PropSpecWidgetKind>,
@@ -515,6 +521,7 @@ impl Default for PropSpecBuilder {
             kind: None,
             map_key_funcs: vec![],
             name: None,
+            order: None,
             type_prop: None,
             validation_format: None,
             widget_kind: None,
@@ -618,6 +625,11 @@ impl PropSpecBuilder {
         self
     }
 
+    pub fn order(&mut self, value: impl Into<u32>) -> &mut Self {
+        self.order = Some(value.into());
+        self
+    }
+
     pub fn map_key_func(&mut self, value: impl Into<MapKeyFuncSpec>) -> &mut Self {
         self.has_data = true;
         self.map_key_funcs.push(value.into());
@@ -674,6 +686,7 @@ impl PropSpecBuilder {
                 hidden: Some(self.hidden),
                 doc_link: self.doc_link.to_owned(),
                 documentation: self.documentation.to_owned(),
+                order: self.order,
             })
         } else {
             None
@@ -535,6 +535,7 @@ impl SchemaVariantSpecBuilder {
                 doc_link: None,
                 documentation: None,
                 validation_format: None,
+                order: None,
             }),
             entries: vec![],
         }
@@ -555,6 +556,7 @@ impl SchemaVariantSpecBuilder {
                 doc_link: None,
                 documentation: None,
                 validation_format: None,
+                order: None,
             }),
             entries: vec![],
         }
@@ -575,6 +577,7 @@ impl SchemaVariantSpecBuilder {
                 doc_link: None,
                 documentation: None,
                 validation_format: None,
+                order: None,
             }),
             entries: vec![],
         })
@@ -595,6 +598,7 @@ impl SchemaVariantSpecBuilder {
                 doc_link: None,
                 documentation: None,
                 validation_format: None,
+                order: None,
             }),
             entries: vec![],
         }
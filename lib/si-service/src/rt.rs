@@ -1,9 +1,21 @@
 //! Common Tokio runtime related behavior.
 
-use std::future::Future;
+use std::{any::Any, future::Future, time::Duration};
 
 use color_eyre::{eyre::eyre, Result};
 use si_runtime::DEFAULT_TOKIO_RT_THREAD_STACK_SIZE;
+use thiserror::Error;
+use tokio::time;
+
+/// An error returned when a future run with [`with_timeout`] does not complete in time.
+#[derive(Debug, Error, Eq, PartialEq)]
+#[error("{phase} timed out after {duration:?}")]
+pub struct TimeoutError {
+    /// A short description of the work that timed out, e.g. "startup".
+    pub phase: &'static str,
+    /// How long the work was given to complete.
+    pub duration: Duration,
+}
 
 /// Create a Tokio runtime and block on a primary async function, i.e. an "async_main()".
 ///
@@ -29,3 +41,107 @@ where
         Err(_) => Err(eyre!("couldn't join on the associated thread")),
     }
 }
+
+/// Create a Tokio runtime and block on a primary async function, converting a panic in it into an
+/// error.
+///
+/// # Notes
+///
+/// This behaves like [`block_on`], except that the future is spawned as a task on the runtime so
+/// that a panic is returned as an error carrying the panic payload rather than unwinding through
+/// the runtime's thread.
+pub fn try_block_on<S, Fut>(thread_name: S, future: Fut) -> Result<()>
+where
+    S: Into<String>,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    block_on(thread_name, async move {
+        match tokio::spawn(future).await {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => Err(eyre!(
+                "primary async function panicked: {}",
+                panic_message(err.into_panic())
+            )),
+            Err(err) => Err(eyre!("primary async function failed to complete: {err}")),
+        }
+    })
+}
+
+/// Returns the message of a panic payload, which is typically a `&str` or a `String`.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => (*message).to_string(),
+            Err(_) => "<non-string panic payload>".to_string(),
+        },
+    }
+}
+
+/// Runs a future to completion, returning a [`TimeoutError`] naming the `phase` if it does not
+/// complete within the given duration.
+///
+/// This is intended to bound startup work (e.g. loading config or resolving hosts) which could
+/// otherwise hang a service indefinitely before it has even started.
+pub async fn with_timeout<Fut>(
+    phase: &'static str,
+    duration: Duration,
+    future: Fut,
+) -> std::result::Result<Fut::Output, TimeoutError>
+where
+    Fut: Future,
+{
+    time::timeout(duration, future)
+        .await
+        .map_err(|_elapsed| TimeoutError { phase, duration })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future;
+
+    use super::*;
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn try_block_on_converts_panic_into_error() {
+        let result = try_block_on("try-block-on-test", async {
+            if true {
+                panic!("boom");
+            }
+            Ok(())
+        });
+
+        let err = result.expect_err("a panic should be returned as an error");
+        assert!(err.to_string().contains("boom"));
+    }
+
+    #[test]
+    fn try_block_on_returns_output() {
+        try_block_on("try-block-on-test", async { Ok(()) }).expect("should complete");
+    }
+
+    #[tokio::test]
+    async fn with_timeout_times_out_hanging_future() {
+        let timeout = Duration::from_millis(10);
+
+        let result = with_timeout("test work", timeout, future::pending::<()>()).await;
+
+        let err = result.expect_err("a hanging future should time out");
+        assert_eq!(
+            TimeoutError {
+                phase: "test work",
+                duration: timeout,
+            },
+            err
+        );
+        assert!(err.to_string().starts_with("test work timed out after"));
+    }
+
+    #[tokio::test]
+    async fn with_timeout_returns_output() {
+        let result = with_timeout("test work", Duration::from_secs(5), async { 42 }).await;
+
+        assert_eq!(Ok(42), result);
+    }
+}
@@ -1,17 +1,26 @@
 //! Graceful service/server shutdown using cancellation tokens, task trackers, driven by Unix
 //! signal handling.
 
-use std::{convert::Infallible, error, future::Future, io, time::Duration};
+use std::{
+    convert::Infallible,
+    error,
+    future::{self, Future},
+    io,
+    time::Duration,
+};
 
 use telemetry::prelude::*;
+use telemetry_utils::metric;
 use thiserror::Error;
 use tokio::{
     signal::unix::{self, SignalKind},
-    task::JoinHandle,
-    time,
+    task::{JoinHandle, JoinSet},
+    time::{self, Instant},
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
+const TASKS_REMAINING_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
 /// An error that can be returned when gracefully shutting down.
 ///
 /// See [`graceful`] for more details.
@@ -34,6 +43,67 @@ pub enum ShutdownError {
     TimeoutElapsed(Duration),
 }
 
+/// The outcome of a graceful shutdown, reporting how each supplied shutdown group was drained.
+///
+/// See [`GracefulShutdown::wait_with_outcome`] for more details.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShutdownOutcome {
+    /// The outcome for each shutdown group, in the order the groups were added
+    pub groups: Vec<GroupOutcome>,
+    /// The overall timeout, if it elapsed before all groups were drained
+    pub timeout_elapsed: Option<Duration>,
+}
+
+impl ShutdownOutcome {
+    /// Returns whether every shutdown group drained before any timeout elapsed.
+    pub fn is_clean(&self) -> bool {
+        self.timeout_elapsed.is_none()
+            && self
+                .groups
+                .iter()
+                .all(|group| group.status == GroupDrainStatus::Drained)
+    }
+
+    /// Returns the shutdown groups which did not drain before a timeout elapsed.
+    pub fn timed_out_groups(&self) -> impl Iterator<Item = &GroupOutcome> {
+        self.groups
+            .iter()
+            .filter(|group| group.status == GroupDrainStatus::TimedOut)
+    }
+
+    /// Converts the outcome into a result, which is an error if the overall timeout elapsed.
+    ///
+    /// A group exceeding its own timeout is not an error as the shutdown was configured to move on
+    /// without it.
+    pub fn into_result(self) -> Result<(), ShutdownError> {
+        match self.timeout_elapsed {
+            Some(timeout) => Err(ShutdownError::TimeoutElapsed(timeout)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The outcome of draining a single shutdown group.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupOutcome {
+    /// The group's name, if it was added with [`GracefulShutdown::named_group`]
+    pub name: Option<String>,
+    /// Whether the group drained or a timeout elapsed first
+    pub status: GroupDrainStatus,
+    /// How long the group took to drain, or how long was spent waiting on it before giving up
+    pub elapsed: Duration,
+}
+
+/// Whether a shutdown group drained before a timeout elapsed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroupDrainStatus {
+    /// All of the group's tasks finished
+    Drained,
+    /// A group or overall timeout elapsed before all of the group's tasks finished, or the group
+    /// was cancelled before graceful shutdown began and so was not waited on
+    TimedOut,
+}
+
 impl ShutdownError {
     fn telemetry<E>(err: E) -> Self
     where
@@ -81,9 +151,35 @@ where
 #[derive(Debug)]
 pub struct GracefulShutdown<TelemetryFut, HanErr> {
     main_handle: Option<JoinHandle<Result<(), HanErr>>>,
-    groups: Vec<(TaskTracker, CancellationToken)>,
+    phases: Vec<ShutdownPhase>,
     telemetry_guard: Option<TelemetryFut>,
     timeout: Option<Duration>,
+    named_trackers: Vec<(String, TaskTracker)>,
+}
+
+/// A set of shutdown groups which are cancelled together and drained before the next phase.
+#[derive(Debug)]
+struct ShutdownPhase {
+    groups: Vec<ShutdownGroup>,
+    timeout: Option<Duration>,
+}
+
+/// A related [`TaskTracker`] and [`CancellationToken`], optionally named.
+#[derive(Debug)]
+struct ShutdownGroup {
+    name: Option<String>,
+    tracker: TaskTracker,
+    token: CancellationToken,
+}
+
+impl From<(TaskTracker, CancellationToken)> for ShutdownGroup {
+    fn from((tracker, token): (TaskTracker, CancellationToken)) -> Self {
+        Self {
+            name: None,
+            tracker,
+            token,
+        }
+    }
 }
 
 impl<TelemetryFut, E, HanErr> Default for GracefulShutdown<TelemetryFut, HanErr>
@@ -94,9 +190,10 @@ where
     fn default() -> Self {
         Self {
             main_handle: Default::default(),
-            groups: Default::default(),
+            phases: Default::default(),
             telemetry_guard: Default::default(),
             timeout: Default::default(),
+            named_trackers: Default::default(),
         }
     }
 }
@@ -108,18 +205,76 @@ where
     HanErr: error::Error + Send + Sync + 'static,
 {
     /// Adds a shutdown group, consisting of a related [`TaskTracker`] and [`CancellationToken`].
-    pub fn group(mut self, tracker: TaskTracker, token: CancellationToken) -> Self {
-        self.groups.extend([(tracker, token)]);
+    ///
+    /// Each group is drained in its own phase, in the order the groups were added.
+    pub fn group(self, tracker: TaskTracker, token: CancellationToken) -> Self {
+        self.phase([(tracker, token)], None)
+    }
+
+    /// Adds a named shutdown group.
+    ///
+    /// While shutting down, the number of tasks remaining in the group's tracker is periodically
+    /// reported as a `shutdown.tasks_remaining` gauge, labeled with the group's name.
+    pub fn named_group(
+        mut self,
+        name: impl Into<String>,
+        tracker: TaskTracker,
+        token: CancellationToken,
+    ) -> Self {
+        let name = name.into();
+        self.named_trackers.push((name.clone(), tracker.clone()));
+        self.phases.push(ShutdownPhase {
+            groups: vec![ShutdownGroup {
+                name: Some(name),
+                tracker,
+                token,
+            }],
+            timeout: None,
+        });
         self
     }
 
+    /// Adds a shutdown group with its own timeout, after which the shutdown moves on without
+    /// waiting for the rest of the group's tasks.
+    ///
+    /// Any overall timeout set with [`Self::timeout`] remains a hard cap across all groups.
+    pub fn group_with_timeout(
+        self,
+        tracker: TaskTracker,
+        token: CancellationToken,
+        timeout: Duration,
+    ) -> Self {
+        self.phase([(tracker, token)], timeout)
+    }
+
     /// Adds a collection of shutdown groups, consisting of a related [`TaskTracker`] and
     /// [`CancellationToken`].
     pub fn groups<I>(mut self, shutdown_groups: I) -> Self
     where
         I: IntoIterator<Item = (TaskTracker, CancellationToken)>,
     {
-        self.groups.extend(shutdown_groups);
+        self.phases
+            .extend(shutdown_groups.into_iter().map(|group| ShutdownPhase {
+                groups: vec![group.into()],
+                timeout: None,
+            }));
+        self
+    }
+
+    /// Adds a shutdown phase, consisting of groups which are cancelled together and drained before
+    /// any later phase is cancelled.
+    ///
+    /// Phases are drained in the order they were added. When a phase has a timeout, the shutdown
+    /// moves on to the next phase once it elapses, even if the phase's tasks have not finished.
+    /// Any overall timeout set with [`Self::timeout`] remains a hard cap across all phases.
+    pub fn phase<I>(mut self, shutdown_groups: I, timeout: impl Into<Option<Duration>>) -> Self
+    where
+        I: IntoIterator<Item = (TaskTracker, CancellationToken)>,
+    {
+        self.phases.push(ShutdownPhase {
+            groups: shutdown_groups.into_iter().map(Into::into).collect(),
+            timeout: timeout.into(),
+        });
         self
     }
 
@@ -148,11 +303,26 @@ where
     /// This function sets up a signal handler for both `SIGINT` (i.e. `Ctrl+c`) and `SIGTERM` so
     /// usage of this function with other code intercepting these signals is *highly* discouraged.
     pub async fn wait(self) -> Result<(), ShutdownError> {
+        self.wait_with_outcome().await?.into_result()
+    }
+
+    /// Waits until all graceful shutdown conditions have been met, returning how each shutdown
+    /// group was drained.
+    ///
+    /// Unlike [`Self::wait`], exceeding the overall timeout is not an error but is reported in the
+    /// [`ShutdownOutcome`], along with which groups failed to drain in time.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function sets up a signal handler for both `SIGINT` (i.e. `Ctrl+c`) and `SIGTERM` so
+    /// usage of this function with other code intercepting these signals is *highly* discouraged.
+    pub async fn wait_with_outcome(self) -> Result<ShutdownOutcome, ShutdownError> {
         let Self {
             main_handle,
-            groups,
+            phases,
             telemetry_guard,
             timeout,
+            named_trackers,
         } = self;
 
         let mut sig_int = unix::signal(SignalKind::interrupt()).map_err(ShutdownError::Signal)?;
@@ -194,45 +364,402 @@ where
             }
         };
 
-        let total = groups.len();
+        let total = phases.len();
         let mut current: usize = 1;
 
-        let await_groups = async {
-            for (tracker, token) in groups {
-                debug!("performing graceful shutdown for group(s) {current}/{total}");
-                tracker.close();
-                token.cancel();
-                tracker.wait().await;
+        // Every group is considered timed out until it is observed to have drained
+        let mut group_outcomes: Vec<GroupOutcome> = phases
+            .iter()
+            .flat_map(|phase| &phase.groups)
+            .map(|group| GroupOutcome {
+                name: group.name.clone(),
+                status: GroupDrainStatus::TimedOut,
+                elapsed: Duration::ZERO,
+            })
+            .collect();
+        let mut group_started_at: Vec<Option<Instant>> = vec![None; group_outcomes.len()];
+
+        let drain_phases = async {
+            let mut next_group_index: usize = 0;
+            for phase in phases {
+                debug!("performing graceful shutdown for phase {current}/{total}");
+                // When every token in the phase was already cancelled (e.g. shutdown was triggered
+                // early by a startup error), its tasks have had their chance to wind down, so don't
+                // wait any longer on them
+                let pre_cancelled = !phase.groups.is_empty()
+                    && phase.groups.iter().all(|group| group.token.is_cancelled());
+
+                let started_at = Instant::now();
+                let mut drains = JoinSet::new();
+                for group in phase.groups {
+                    group.tracker.close();
+                    group.token.cancel();
+
+                    let group_index = next_group_index;
+                    next_group_index = next_group_index.saturating_add(1);
+                    if let Some(group_started) = group_started_at.get_mut(group_index) {
+                        *group_started = Some(started_at);
+                    }
+                    if group.tracker.is_empty() {
+                        if let Some(outcome) = group_outcomes.get_mut(group_index) {
+                            outcome.status = GroupDrainStatus::Drained;
+                        }
+                        continue;
+                    }
+                    drains.spawn(async move {
+                        group.tracker.wait().await;
+                        group_index
+                    });
+                }
+                if pre_cancelled {
+                    // Groups with tasks remaining are left reported as timed out
+                    info!(
+                        "phase {current}/{total} was cancelled before graceful shutdown; \
+                        not waiting on its tasks"
+                    );
+                } else {
+                    let drained = async {
+                        while let Some(join_result) = drains.join_next().await {
+                            if let Some(outcome) = join_result
+                                .ok()
+                                .and_then(|group_index| group_outcomes.get_mut(group_index))
+                            {
+                                outcome.status = GroupDrainStatus::Drained;
+                                outcome.elapsed = started_at.elapsed();
+                            }
+                        }
+                    };
+                    match phase.timeout {
+                        Some(phase_timeout) => {
+                            if let Err(_elapsed) = time::timeout(phase_timeout, drained).await {
+                                warn!(
+                                    "graceful shutdown timeout exceeded for phase \
+                                    {current}/{total}; continuing shutdown anyway"
+                                );
+                                mark_timed_out(&mut group_outcomes, &group_started_at);
+                            }
+                        }
+                        None => drained.await,
+                    }
+                }
                 current = current.saturating_add(1);
             }
         };
+        let await_groups = async {
+            tokio::select! {
+                _ = drain_phases => {}
+                _ = report_tasks_remaining(
+                    &named_trackers,
+                    TASKS_REMAINING_REPORT_INTERVAL,
+                    |name, remaining| {
+                        metric!(gauge.shutdown.tasks_remaining = remaining, tracker = name);
+                    },
+                ) => {}
+            }
+        };
 
         // Wait for all tasks to finish
-        match timeout {
-            Some(timeout) => {
-                if let Err(_elapsed) = time::timeout(timeout, await_groups).await {
+        let timeout_elapsed = match timeout {
+            Some(timeout) => match time::timeout(timeout, await_groups).await {
+                Ok(()) => None,
+                Err(_elapsed) => {
                     warn!("graceful shutdown timeout exceeded; completing shutdown anyway");
-                    if let Some(telemetry_guard) = telemetry_guard {
-                        debug!("performing graceful shutdown for telemetry guard");
-                        telemetry_guard.await.map_err(ShutdownError::telemetry)?;
-                    }
-                    return Err(ShutdownError::TimeoutElapsed(timeout));
+                    mark_timed_out(&mut group_outcomes, &group_started_at);
+                    Some(timeout)
                 }
-            }
+            },
             None => {
                 await_groups.await;
+                None
             }
+        };
+
+        let outcome = ShutdownOutcome {
+            groups: group_outcomes,
+            timeout_elapsed,
+        };
+        for group in outcome.timed_out_groups() {
+            warn!(
+                group = group.name.as_deref().unwrap_or("unnamed"),
+                elapsed = ?group.elapsed,
+                "shutdown group did not drain before timeout",
+            );
         }
+        debug!(?outcome, "graceful shutdown outcome");
 
         if let Some(telemetry_guard) = telemetry_guard {
             debug!("performing graceful shutdown for telemetry guard");
             telemetry_guard.await.map_err(ShutdownError::telemetry)?;
         }
 
-        info!("graceful shutdown complete.");
+        if outcome.timeout_elapsed.is_none() {
+            info!("graceful shutdown complete.");
+        }
         match maybe_handle_result {
-            Some(handle_result) => handle_result,
-            None => Ok(()),
+            Some(Err(err)) if outcome.timeout_elapsed.is_none() => Err(err),
+            _ => Ok(outcome),
         }
     }
 }
+
+/// Records how long was spent waiting on each started group which has not drained.
+fn mark_timed_out(group_outcomes: &mut [GroupOutcome], group_started_at: &[Option<Instant>]) {
+    for (outcome, started_at) in group_outcomes.iter_mut().zip(group_started_at) {
+        if outcome.status != GroupDrainStatus::TimedOut || !outcome.elapsed.is_zero() {
+            continue;
+        }
+        if let Some(started_at) = started_at {
+            outcome.elapsed = started_at.elapsed();
+        }
+    }
+}
+
+/// Periodically reports the number of tasks remaining in each named tracker, until cancelled by
+/// being dropped.
+async fn report_tasks_remaining(
+    named_trackers: &[(String, TaskTracker)],
+    interval: Duration,
+    report: impl Fn(&str, usize),
+) {
+    if named_trackers.is_empty() {
+        return future::pending().await;
+    }
+
+    let mut interval = time::interval(interval);
+    loop {
+        interval.tick().await;
+        for (name, tracker) in named_trackers {
+            report(name, tracker.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::{self, Ready},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn group_timeouts_are_per_group() {
+        let fast_finished = Arc::new(AtomicBool::new(false));
+        let slow_finished = Arc::new(AtomicBool::new(false));
+
+        let fast_tracker = TaskTracker::new();
+        let fast_token = CancellationToken::new();
+        fast_tracker.spawn({
+            let token = fast_token.clone();
+            let finished = fast_finished.clone();
+            async move {
+                token.cancelled().await;
+                time::sleep(Duration::from_millis(50)).await;
+                finished.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let slow_tracker = TaskTracker::new();
+        let slow_token = CancellationToken::new();
+        slow_tracker.spawn({
+            let token = slow_token.clone();
+            let finished = slow_finished.clone();
+            async move {
+                token.cancelled().await;
+                time::sleep(Duration::from_secs(60)).await;
+                finished.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let handle = tokio::spawn(async { Ok::<(), io::Error>(()) });
+        graceful_with_handle::<Ready<Result<(), io::Error>>, _, _>(handle)
+            .group_with_timeout(fast_tracker, fast_token, Duration::from_secs(5))
+            .group_with_timeout(slow_tracker, slow_token, Duration::from_millis(100))
+            .telemetry_guard(future::ready(Ok(())))
+            .timeout(Duration::from_secs(10))
+            .wait()
+            .await
+            .expect("shutdown should complete within the overall timeout");
+
+        assert!(fast_finished.load(Ordering::SeqCst));
+        assert!(!slow_finished.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn outcome_reports_which_groups_timed_out() {
+        let spawn_group = |drain_time: Duration| {
+            let tracker = TaskTracker::new();
+            let token = CancellationToken::new();
+            tracker.spawn({
+                let token = token.clone();
+                async move {
+                    token.cancelled().await;
+                    time::sleep(drain_time).await;
+                }
+            });
+            (tracker, token)
+        };
+
+        let (fast_tracker, fast_token) = spawn_group(Duration::from_millis(10));
+        let (slow_tracker, slow_token) = spawn_group(Duration::from_secs(60));
+        let (stuck_tracker, stuck_token) = spawn_group(Duration::from_secs(60));
+
+        let handle = tokio::spawn(async { Ok::<(), io::Error>(()) });
+        let outcome = graceful_with_handle::<Ready<Result<(), io::Error>>, _, _>(handle)
+            .named_group("fast", fast_tracker, fast_token)
+            .group_with_timeout(slow_tracker, slow_token, Duration::from_millis(50))
+            .named_group("stuck", stuck_tracker, stuck_token)
+            .timeout(Duration::from_millis(200))
+            .wait_with_outcome()
+            .await
+            .expect("shutdown should report an outcome");
+
+        assert!(!outcome.is_clean());
+        assert_eq!(Some(Duration::from_millis(200)), outcome.timeout_elapsed);
+        assert_eq!(
+            vec![
+                (Some("fast"), GroupDrainStatus::Drained),
+                (None, GroupDrainStatus::TimedOut),
+                (Some("stuck"), GroupDrainStatus::TimedOut),
+            ],
+            outcome
+                .groups
+                .iter()
+                .map(|group| (group.name.as_deref(), group.status))
+                .collect::<Vec<_>>()
+        );
+        assert!(outcome
+            .groups
+            .iter()
+            .all(|group| group.elapsed > Duration::ZERO));
+        assert!(matches!(
+            outcome.into_result(),
+            Err(ShutdownError::TimeoutElapsed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pre_cancelled_groups_are_not_waited_on() {
+        let drained_tracker = TaskTracker::new();
+        let drained_token = CancellationToken::new();
+        drained_tracker.close();
+        drained_token.cancel();
+
+        let stuck_tracker = TaskTracker::new();
+        let stuck_token = CancellationToken::new();
+        stuck_tracker.spawn(time::sleep(Duration::from_secs(60)));
+        stuck_token.cancel();
+
+        let started_at = Instant::now();
+        let handle = tokio::spawn(async { Ok::<(), io::Error>(()) });
+        let outcome = graceful_with_handle::<Ready<Result<(), io::Error>>, _, _>(handle)
+            .named_group("drained", drained_tracker, drained_token)
+            .named_group("stuck", stuck_tracker, stuck_token)
+            .timeout(Duration::from_secs(10))
+            .wait_with_outcome()
+            .await
+            .expect("shutdown should report an outcome");
+
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+        assert_eq!(None, outcome.timeout_elapsed);
+        assert_eq!(
+            vec![
+                (Some("drained"), GroupDrainStatus::Drained, Duration::ZERO),
+                (Some("stuck"), GroupDrainStatus::TimedOut, Duration::ZERO),
+            ],
+            outcome
+                .groups
+                .iter()
+                .map(|group| (group.name.as_deref(), group.status, group.elapsed))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn phases_are_drained_in_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let spawn_group = |name: &'static str, drain_time: Duration| {
+            let tracker = TaskTracker::new();
+            let token = CancellationToken::new();
+            tracker.spawn({
+                let token = token.clone();
+                let events = events.clone();
+                async move {
+                    token.cancelled().await;
+                    events
+                        .lock()
+                        .expect("poisoned")
+                        .push(format!("{name} cancelled"));
+                    time::sleep(drain_time).await;
+                    events
+                        .lock()
+                        .expect("poisoned")
+                        .push(format!("{name} drained"));
+                }
+            });
+            (tracker, token)
+        };
+
+        let server = spawn_group("server", Duration::from_millis(100));
+        let layer_db = spawn_group("layer_db", Duration::from_millis(10));
+        let telemetry = spawn_group("telemetry", Duration::from_millis(10));
+
+        let handle = tokio::spawn(async { Ok::<(), io::Error>(()) });
+        graceful_with_handle::<Ready<Result<(), io::Error>>, _, _>(handle)
+            .phase([server], None)
+            .phase([layer_db], Duration::from_secs(5))
+            .phase([telemetry], None)
+            .timeout(Duration::from_secs(10))
+            .wait()
+            .await
+            .expect("shutdown should complete within the overall timeout");
+
+        assert_eq!(
+            vec![
+                "server cancelled",
+                "server drained",
+                "layer_db cancelled",
+                "layer_db drained",
+                "telemetry cancelled",
+                "telemetry drained",
+            ],
+            *events.lock().expect("poisoned")
+        );
+    }
+
+    #[tokio::test]
+    async fn tasks_remaining_decreases_as_tasks_complete() {
+        let tracker = TaskTracker::new();
+        for millis in [20, 60, 100] {
+            tracker.spawn(time::sleep(Duration::from_millis(millis)));
+        }
+        tracker.close();
+
+        let reported = Mutex::new(Vec::new());
+        let named_trackers = vec![("main".to_string(), tracker.clone())];
+        let _ = time::timeout(
+            Duration::from_millis(200),
+            report_tasks_remaining(
+                &named_trackers,
+                Duration::from_millis(10),
+                |name, remaining| {
+                    assert_eq!("main", name);
+                    reported.lock().expect("poisoned").push(remaining);
+                },
+            ),
+        )
+        .await;
+
+        let reported = reported.into_inner().expect("poisoned");
+        assert_eq!(Some(&3), reported.first());
+        assert_eq!(Some(&0), reported.last());
+        assert!(reported
+            .windows(2)
+            .all(|pair| matches!(pair, [earlier, later] if earlier >= later)));
+    }
+}
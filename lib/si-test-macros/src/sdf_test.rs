@@ -413,6 +413,9 @@ impl SdfTestFnSetupExpander {
                     #cancellation_token.clone(),
                     #spicedb_client,
                     #audit_database_context,
+                    ::sdf_server::ModuleIndexRetryConfig::default(),
+                    ::std::time::Duration::from_secs(24 * 60 * 60),
+                    500,
                 ).into_inner()
             };
         });
@@ -9,7 +9,8 @@
 #![allow(clippy::missing_errors_doc)]
 use telemetry::opentelemetry::{
     metrics::MetricsError,
-    trace::{TraceError, TracerProvider},
+    trace::{Link, SamplingResult, SpanKind, TraceError, TraceId, TracerProvider},
+    Context,
 };
 use tracing_subscriber::{filter::FilterExt, layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -20,7 +21,9 @@ use std::{
     io::{self, IsTerminal},
     ops::Deref,
     pin::Pin,
-    result, thread,
+    result,
+    sync::{Arc, PoisonError, RwLock},
+    thread,
     time::{Duration, Instant},
 };
 use tracing::Metadata;
@@ -32,7 +35,7 @@ use opentelemetry_sdk::{
     propagation::TraceContextPropagator,
     resource::EnvResourceDetector,
     runtime,
-    trace::{self, Config, Tracer},
+    trace::{self, Config, Sampler, ShouldSample, Tracer},
     Resource,
 };
 use opentelemetry_semantic_conventions::resource;
@@ -156,6 +159,11 @@ pub struct TelemetryConfig {
     #[builder(setter(into), default)]
     console_log_format: ConsoleLogFormat,
 
+    /// Whether span close events, including each span's busy and idle time, are included in the
+    /// console log output.
+    #[builder(default)]
+    log_span_timings: bool,
+
     #[builder(default = "true")]
     signal_handlers: bool,
 }
@@ -265,6 +273,52 @@ pub fn init(
     Ok((client, guard))
 }
 
+/// Creates an [`ApplicationTelemetryClient`] whose verbosity changes reload the filter of the
+/// given layer, without any OpenTelemetry exporters or signal handlers.
+///
+/// This is intended for tests which need to observe real filter changes. The returned subscriber
+/// is not installed, so the caller should set it as the default (for example with
+/// [`tracing::subscriber::set_default`]). Must be called from within a Tokio runtime.
+///
+/// Only available with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub fn init_for_test<L>(
+    config: TelemetryConfig,
+    layer: L,
+) -> Result<(ApplicationTelemetryClient, impl Subscriber + Send + Sync)>
+where
+    L: Layer<Registry> + Send + Sync + 'static,
+{
+    let tracing_level = default_tracing_level(&config);
+    let directives = TracingDirectives::from(&tracing_level);
+
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(directives.as_str())?);
+    let subscriber = Registry::default().with(layer.with_filter(filter));
+
+    let handles = TelemetryHandles {
+        console_log_filter_reload: Box::new(move |updated: EnvFilter| {
+            handle.reload(updated).map_err(Into::into)
+        }),
+        otel_filter_reload: Box::new(|_: EnvFilter| -> Result<()> { Ok(()) }),
+        metrics_filter_reload: Box::new(|_: EnvFilter| -> Result<()> { Ok(()) }),
+        otel_sampler: OtelSampler::default(),
+    };
+
+    let (update_telemetry_tx, update_telemetry_rx) = mpsc::unbounded_channel();
+
+    let client = ApplicationTelemetryClient::new(
+        config.app_modules,
+        config.interesting_modules,
+        config.never_modules,
+        tracing_level.clone(),
+        update_telemetry_tx,
+    );
+
+    tokio::spawn(TelemetryUpdateTask::new(handles, tracing_level, update_telemetry_rx).run());
+
+    Ok((client, subscriber))
+}
+
 fn default_tracing_level(config: &TelemetryConfig) -> TracingLevel {
     if let Some(log_env_var) = config.log_env_var.as_deref() {
         #[allow(clippy::disallowed_methods)] // We use consistently named env var names, always
@@ -298,6 +352,16 @@ fn default_tracing_level(config: &TelemetryConfig) -> TracingLevel {
 }
 
 fn default_span_events_fmt(config: &TelemetryConfig) -> Result<FmtSpan> {
+    let span_events_fmt = span_events_fmt_from_env(config)?;
+
+    if config.log_span_timings {
+        Ok(span_events_fmt | FmtSpan::CLOSE)
+    } else {
+        Ok(span_events_fmt)
+    }
+}
+
+fn span_events_fmt_from_env(config: &TelemetryConfig) -> Result<FmtSpan> {
     if let Some(log_span_events_env_var) = config.log_span_events_env_var.as_deref() {
         #[allow(clippy::disallowed_methods)] // We use consistently named env var names, always
         // prefixed with `SI_`
@@ -373,8 +437,11 @@ fn tracing_subscriber(
         (layer, reloader)
     };
 
+    let otel_sampler = OtelSampler::default();
+
     let (otel_layer, otel_filter_reload) = {
-        let layer = tracing_opentelemetry::layer().with_tracer(otel_tracer(config)?);
+        let layer =
+            tracing_opentelemetry::layer().with_tracer(otel_tracer(config, otel_sampler.clone())?);
         let env_filter = EnvFilter::try_new(directives.as_str())?;
         let (filter, handle) = reload::Layer::new(env_filter);
         let layer = layer.with_filter(filter.and(ExcludeMetricsFilter));
@@ -408,16 +475,21 @@ fn tracing_subscriber(
         console_log_filter_reload,
         otel_filter_reload,
         metrics_filter_reload,
+        otel_sampler,
     };
 
     Ok((registry, handles))
 }
 
-fn otel_tracer(config: &TelemetryConfig) -> Result<Tracer> {
+fn otel_tracer(config: &TelemetryConfig, sampler: OtelSampler) -> Result<Tracer> {
     Ok(opentelemetry_otlp::new_pipeline()
         .tracing()
         .with_exporter(opentelemetry_otlp::new_exporter().tonic())
-        .with_trace_config(Config::default().with_resource(telemetry_resource(config)))
+        .with_trace_config(
+            Config::default()
+                .with_resource(telemetry_resource(config))
+                .with_sampler(sampler),
+        )
         .with_batch_config(
             trace::BatchConfigBuilder::default()
                 .with_max_queue_size(4096)
@@ -469,7 +541,7 @@ fn create_client(
         config.app_modules,
         config.interesting_modules,
         config.never_modules,
-        tracing_level,
+        tracing_level.clone(),
         update_telemetry_tx.clone(),
     );
 
@@ -478,7 +550,7 @@ fn create_client(
     };
 
     // Spawn this task free of the tracker as we want it to outlive the tracker when shutting down
-    tokio::spawn(TelemetryUpdateTask::new(handles, update_telemetry_rx).run());
+    tokio::spawn(TelemetryUpdateTask::new(handles, tracing_level, update_telemetry_rx).run());
 
     if config.signal_handlers {
         tracker.spawn(
@@ -548,6 +620,78 @@ struct TelemetryHandles {
     console_log_filter_reload: ReloadHandle,
     otel_filter_reload: ReloadHandle,
     metrics_filter_reload: ReloadHandle,
+    otel_sampler: OtelSampler,
+}
+
+/// An OpenTelemetry sampler whose ratio and on/off state can be changed at runtime.
+///
+/// Clones share their state so the copy held by the update task can adjust the copy installed in
+/// the tracer provider. The effective [`Sampler`] is only rebuilt when the state changes, as it is
+/// consulted for every new span.
+#[derive(Clone, Debug)]
+struct OtelSampler {
+    state: Arc<RwLock<OtelSamplerState>>,
+}
+
+#[derive(Debug)]
+struct OtelSamplerState {
+    enabled: bool,
+    ratio: f64,
+    sampler: Sampler,
+}
+
+impl OtelSamplerState {
+    fn new(enabled: bool, ratio: f64) -> Self {
+        let sampler = if enabled {
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio)))
+        } else {
+            Sampler::AlwaysOff
+        };
+
+        Self {
+            enabled,
+            ratio,
+            sampler,
+        }
+    }
+}
+
+impl Default for OtelSampler {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(OtelSamplerState::new(true, 1.0))),
+        }
+    }
+}
+
+impl OtelSampler {
+    fn set_enabled(&self, enabled: bool) {
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        *state = OtelSamplerState::new(enabled, state.ratio);
+    }
+
+    fn set_ratio(&self, ratio: f64) {
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        *state = OtelSamplerState::new(state.enabled, ratio);
+    }
+}
+
+impl ShouldSample for OtelSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.state
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .sampler
+            .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
 }
 
 struct TelemetrySignalHandlerTask {
@@ -602,6 +746,8 @@ impl TelemetrySignalHandlerTask {
 
 struct TelemetryUpdateTask {
     handles: TelemetryHandles,
+    /// The tracing level most recently applied, which module overrides are layered over.
+    tracing_level: TracingLevel,
     update_command_rx: mpsc::UnboundedReceiver<TelemetryCommand>,
     is_shutdown: bool,
 }
@@ -611,10 +757,12 @@ impl TelemetryUpdateTask {
 
     fn new(
         handles: TelemetryHandles,
+        tracing_level: TracingLevel,
         update_command_rx: mpsc::UnboundedReceiver<TelemetryCommand>,
     ) -> Self {
         Self {
             handles,
+            tracing_level,
             update_command_rx,
             is_shutdown: false,
         }
@@ -633,12 +781,13 @@ impl TelemetryUpdateTask {
                     // `.instrument()` combinator on the future.
                     let span = info_span!("telemetry_update_task.update_tracing_level");
                     span.in_scope(|| {
-                        if let Err(err) = self.update_tracing_level(level) {
-                            warn!(
+                        match self.apply_directives(&TracingDirectives::from(&level)) {
+                            Ok(()) => self.tracing_level = level,
+                            Err(err) => warn!(
                                 task = Self::NAME,
                                 error = ?err,
                                 "failed to update tracing level, using prior value",
-                            );
+                            ),
                         }
                         if let Some(tx) = wait {
                             if let Err(err) = tx.send(()) {
@@ -650,6 +799,35 @@ impl TelemetryUpdateTask {
                         }
                     })
                 }
+                TelemetryCommand::InterestingModulesVerbosity { modules, verbosity } => {
+                    let span =
+                        info_span!("telemetry_update_task.update_interesting_modules_verbosity");
+                    span.in_scope(|| {
+                        let directives = TracingDirectives::from(&self.tracing_level)
+                            .with_module_level(&modules, interesting_level(verbosity));
+                        if let Err(err) = self.apply_directives(&directives) {
+                            warn!(
+                                task = Self::NAME,
+                                error = ?err,
+                                "failed to update interesting modules verbosity, using prior value",
+                            );
+                        }
+                    })
+                }
+                TelemetryCommand::SamplingRatio(ratio) => {
+                    let span = info_span!("telemetry_update_task.update_sampling_ratio");
+                    span.in_scope(|| {
+                        self.handles.otel_sampler.set_ratio(ratio);
+                        info!(task = Self::NAME, ratio, "updated otel sampling ratio");
+                    })
+                }
+                TelemetryCommand::ToggleOtel(enabled) => {
+                    let span = info_span!("telemetry_update_task.toggle_otel");
+                    span.in_scope(|| {
+                        self.handles.otel_sampler.set_enabled(enabled);
+                        info!(task = Self::NAME, enabled, "updated otel trace export");
+                    })
+                }
                 TelemetryCommand::Shutdown(token) => {
                     if !self.is_shutdown {
                         Self::shutdown().await;
@@ -664,9 +842,7 @@ impl TelemetryUpdateTask {
         debug!(task = Self::NAME, "shutdown complete");
     }
 
-    fn update_tracing_level(&self, tracing_level: TracingLevel) -> Result<()> {
-        let directives = TracingDirectives::from(tracing_level);
-
+    fn apply_directives(&self, directives: &TracingDirectives) -> Result<()> {
         (self.handles.console_log_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
         (self.handles.otel_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
         (self.handles.metrics_filter_reload)(EnvFilter::try_new(directives.as_str())?)?;
@@ -857,6 +1033,38 @@ impl TracingDirectives {
     pub fn as_str(&self) -> &str {
         self.0.as_ref()
     }
+
+    /// Returns these directives with the given modules set to the given level, replacing any
+    /// directives already targeting those modules.
+    #[must_use]
+    fn with_module_level(self, modules: &[String], level: &str) -> Self {
+        let overrides = modules.iter().map(|module| format!("{module}={level}"));
+        let kept = self
+            .as_str()
+            .split(',')
+            .filter(|directive| {
+                let target = directive.split_once('=').map_or("", |(target, _)| target);
+                !directive.is_empty() && !modules.iter().any(|module| module == target)
+            })
+            .map(ToString::to_string);
+
+        Self(Cow::Owned(
+            overrides.chain(kept).collect::<Vec<_>>().join(","),
+        ))
+    }
+}
+
+/// Returns the level a [`Verbosity`] applies to interesting modules.
+fn interesting_level(verbosity: Verbosity) -> &'static str {
+    match verbosity {
+        Verbosity::InfoAll | Verbosity::DebugAppInfoInterestingInfoAll => "info",
+        Verbosity::DebugAppDebugInterestingInfoAll | Verbosity::TraceAppDebugInterestingInfoAll => {
+            "debug"
+        }
+        Verbosity::TraceAppTraceInterestingInfoAll
+        | Verbosity::TraceAppTraceInterestingDebugAll
+        | Verbosity::TraceAll => "trace",
+    }
 }
 
 impl From<String> for TracingDirectives {
@@ -878,3 +1086,129 @@ impl Deref for TracingDirectives {
         self.as_str()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use telemetry::{tracing::Event, TelemetryClient};
+    use tracing_subscriber::layer::Context as LayerContext;
+
+    use super::*;
+
+    /// Counts the trace-level events emitted from this module.
+    #[derive(Clone, Default)]
+    struct TraceEventCounter(Arc<AtomicUsize>);
+
+    impl TraceEventCounter {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for TraceEventCounter {
+        fn on_event(&self, event: &Event<'_>, _ctx: LayerContext<'_, S>) {
+            let metadata = event.metadata();
+            if metadata.target() == module_path!() && *metadata.level() == tracing::Level::TRACE {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Captures console log output in memory.
+    #[derive(Clone, Default)]
+    struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedOutput {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().expect("lock poisoned")).into_owned()
+        }
+    }
+
+    impl io::Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("lock poisoned").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn span_timings_emit_close_event_with_duration() {
+        let config = TelemetryConfig::builder()
+            .service_name("telemetry-application-test")
+            .service_namespace("si-test")
+            .log_span_timings(true)
+            .build()
+            .expect("failed to build config");
+        let span_events_fmt = default_span_events_fmt(&config).expect("failed to get span events");
+
+        let output = CapturedOutput::default();
+        let writer = output.clone();
+        let layer = tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .with_span_events(span_events_fmt);
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            let span = info_span!("timed_operation");
+            let _entered = span.enter();
+        });
+
+        let contents = output.contents();
+        assert!(contents.contains("timed_operation"), "{contents}");
+        assert!(contents.contains("close"), "{contents}");
+        assert!(contents.contains("time.busy"), "{contents}");
+    }
+
+    #[tokio::test]
+    async fn trace_verbosity_enables_filtered_event() {
+        let config = TelemetryConfig::builder()
+            .service_name("telemetry-application-test")
+            .service_namespace("si-test")
+            .app_modules(vec![module_path!()])
+            .build()
+            .expect("failed to build config");
+        let counter = TraceEventCounter::default();
+
+        let (mut client, subscriber) =
+            init_for_test(config, counter.clone()).expect("failed to create test client");
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        trace!("filtered at the default verbosity");
+        assert_eq!(0, counter.count());
+
+        client
+            .set_verbosity_and_wait(Verbosity::TraceAppTraceInterestingInfoAll)
+            .await
+            .expect("failed to set verbosity");
+
+        trace!("enabled at trace verbosity");
+        assert_eq!(1, counter.count());
+    }
+
+    #[test]
+    fn module_level_overrides_layer_over_directives() {
+        let directives = TracingDirectives::new(
+            Verbosity::InfoAll,
+            &Some(vec!["sdf".into()]),
+            &Some(vec!["dal".into(), "si_layer_cache".into()]),
+            &None,
+        )
+        .with_module_level(
+            &["si_layer_cache".to_string()],
+            interesting_level(Verbosity::DebugAppDebugInterestingInfoAll),
+        );
+
+        assert_eq!(
+            "si_layer_cache=debug,sdf=info,dal=info,info",
+            directives.as_str()
+        );
+    }
+}
@@ -11,13 +11,15 @@
 use std::{
     borrow::Cow,
     env,
-    fmt::{Debug, Display},
+    fmt::{self, Debug, Display},
     ops::{Deref, DerefMut},
     result::Result,
+    str::FromStr,
     sync::Arc,
 };
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio_util::sync::CancellationToken;
@@ -26,9 +28,13 @@ pub use opentelemetry::{self, trace::SpanKind};
 pub use tracing;
 use tracing::warn;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 pub mod prelude {
     pub use super::{
-        current_span_for_instrument_at, MessagingOperation, SpanExt, SpanKind, SpanKindExt,
+        current_span_for_instrument_at, messaging_attributes, MessagingOperation, SpanExt,
+        SpanKind, SpanKindExt,
     };
     pub use tracing::{
         self, debug, debug_span, enabled, error, error_span, event, event_enabled, field::Empty,
@@ -38,33 +44,56 @@ pub mod prelude {
 }
 
 #[remain::sorted]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OtelStatusCode {
     Error,
     Ok,
-    // Unset is not currently used, although represents a valid state.
-    #[allow(dead_code)]
     Unset,
 }
 
 impl OtelStatusCode {
+    pub const ERROR_STR: &'static str = "ERROR";
+    pub const OK_STR: &'static str = "OK";
+    pub const UNSET_STR: &'static str = "";
+
     pub fn as_str(&self) -> &'static str {
         match self {
-            Self::Error => "ERROR",
-            Self::Ok => "OK",
-            Self::Unset => "",
+            Self::Error => Self::ERROR_STR,
+            Self::Ok => Self::OK_STR,
+            Self::Unset => Self::UNSET_STR,
+        }
+    }
+}
+
+impl FromStr for OtelStatusCode {
+    type Err = ParseOtelStatusCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::ERROR_STR => Ok(Self::Error),
+            Self::OK_STR => Ok(Self::Ok),
+            Self::UNSET_STR => Ok(Self::Unset),
+            unknown => Err(ParseOtelStatusCodeError(unknown.to_string())),
         }
     }
 }
 
+/// Error returned when parsing an [`OtelStatusCode`] from a string which isn't a valid status code.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("unknown otel status code: {0}")]
+pub struct ParseOtelStatusCodeError(pub String);
+
 /// Represents valied states for OpenTelemetry's `messaging.operation` field.
 ///
 /// `messaging.operation` has the following list of well-known values. If one of them applies, then
 /// the respective value MUST be used, otherwise a custom value MAY be used.
 ///
+/// Custom values are out of scope for this type: parsing anything other than a well-known value
+/// fails with a [`ParseMessagingOperationError`].
+///
 /// See: <https://opentelemetry.io/docs/specs/semconv/attributes-registry/messaging/>
 #[remain::sorted]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MessagingOperation {
     /// A message is created. “Create” spans always refer to a single message and are used to
     /// provide a unique creation context for messages in batch publishing scenarios.
@@ -97,15 +126,71 @@ impl MessagingOperation {
     }
 }
 
+impl Display for MessagingOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MessagingOperation {
+    type Err = ParseMessagingOperationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            Self::CREATE_STR => Ok(Self::Create),
+            Self::DELIVER_STR => Ok(Self::Deliver),
+            Self::PUBLISH_STR => Ok(Self::Publish),
+            Self::RECEIVE_STR => Ok(Self::Receive),
+            unknown => Err(ParseMessagingOperationError(unknown.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing a [`MessagingOperation`] from a string which isn't a well-known
+/// value.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("unknown messaging operation: {0}")]
+pub struct ParseMessagingOperationError(pub String);
+
+/// Key of OpenTelemetry's `messaging.operation` span attribute.
+pub const MESSAGING_OPERATION_KEY: &str = "messaging.operation";
+/// Key of OpenTelemetry's `messaging.system` span attribute.
+pub const MESSAGING_SYSTEM_KEY: &str = "messaging.system";
+/// Key of OpenTelemetry's `messaging.destination.name` span attribute.
+pub const MESSAGING_DESTINATION_NAME_KEY: &str = "messaging.destination.name";
+
+/// Returns the OpenTelemetry `messaging.*` span attributes describing a messaging operation on the
+/// given system (i.e. `nats`) and destination (i.e. a subject).
+pub fn messaging_attributes(
+    op: MessagingOperation,
+    system: &str,
+    destination: &str,
+) -> Vec<(&'static str, String)> {
+    vec![
+        (MESSAGING_OPERATION_KEY, op.to_string()),
+        (MESSAGING_SYSTEM_KEY, system.to_string()),
+        (MESSAGING_DESTINATION_NAME_KEY, destination.to_string()),
+    ]
+}
+
 /// An extention trait for [`SpanKind`] providing string representations.
 pub trait SpanKindExt {
     /// Returns a static str representation.
     fn as_str(&self) -> &'static str;
 
+    /// Returns the OpenTelemetry protocol constant name, i.e. `SPAN_KIND_SERVER`.
+    fn as_otel_const_str(&self) -> &'static str;
+
     /// Returns an allocated string representation.
     fn to_string(&self) -> String {
         self.as_str().to_string()
     }
+
+    /// Parses either the lowercase representation returned by [`SpanKindExt::as_str`] or the
+    /// OpenTelemetry protocol constant name returned by [`SpanKindExt::as_otel_const_str`].
+    fn parse(s: &str) -> Result<Self, ParseSpanKindError>
+    where
+        Self: Sized;
 }
 
 impl SpanKindExt for SpanKind {
@@ -118,10 +203,38 @@ impl SpanKindExt for SpanKind {
             SpanKind::Internal => "internal",
         }
     }
+
+    fn as_otel_const_str(&self) -> &'static str {
+        match self {
+            SpanKind::Client => "SPAN_KIND_CLIENT",
+            SpanKind::Server => "SPAN_KIND_SERVER",
+            SpanKind::Producer => "SPAN_KIND_PRODUCER",
+            SpanKind::Consumer => "SPAN_KIND_CONSUMER",
+            SpanKind::Internal => "SPAN_KIND_INTERNAL",
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, ParseSpanKindError> {
+        match s {
+            "client" | "SPAN_KIND_CLIENT" => Ok(SpanKind::Client),
+            "server" | "SPAN_KIND_SERVER" => Ok(SpanKind::Server),
+            "producer" | "SPAN_KIND_PRODUCER" => Ok(SpanKind::Producer),
+            "consumer" | "SPAN_KIND_CONSUMER" => Ok(SpanKind::Consumer),
+            "internal" | "SPAN_KIND_INTERNAL" => Ok(SpanKind::Internal),
+            unknown => Err(ParseSpanKindError(unknown.to_string())),
+        }
+    }
 }
 
+/// Error returned when parsing a [`SpanKind`] from a string which isn't a known representation.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("unknown span kind: {0}")]
+pub struct ParseSpanKindError(pub String);
+
 pub trait SpanExt {
     fn record_ok(&self);
+    /// Records the given status code, which may be [`OtelStatusCode::Unset`] to clear a status.
+    fn record_status_code(&self, code: OtelStatusCode);
     fn record_err<E>(&self, err: E) -> E
     where
         E: Debug + Display;
@@ -143,14 +256,18 @@ pub trait SpanExt {
 
 impl SpanExt for tracing::Span {
     fn record_ok(&self) {
-        self.record("otel.status_code", OtelStatusCode::Ok.as_str());
+        self.record_status_code(OtelStatusCode::Ok);
+    }
+
+    fn record_status_code(&self, code: OtelStatusCode) {
+        self.record("otel.status_code", code.as_str());
     }
 
     fn record_err<E>(&self, err: E) -> E
     where
         E: Debug + Display,
     {
-        self.record("otel.status_code", OtelStatusCode::Error.as_str());
+        self.record_status_code(OtelStatusCode::Error);
         self.record("otel.status_message", err.to_string().as_str());
         err
     }
@@ -163,17 +280,48 @@ impl SpanExt for tracing::Span {
 #[async_trait]
 pub trait TelemetryClient: Clone + Send + Sync + 'static {
     async fn set_verbosity(&mut self, updated: Verbosity) -> Result<(), ClientError>;
+    /// Sets the verbosity and waits until the tracing layer has applied the change.
+    async fn set_verbosity_and_wait(&mut self, updated: Verbosity) -> Result<(), ClientError>;
     async fn modify_verbosity(&mut self) -> Result<(), ClientError>;
     async fn set_custom_tracing(
         &mut self,
         directives: impl Into<String> + Send + 'async_trait,
     ) -> Result<(), ClientError>;
+    /// Appends module names to be treated as application modules, e.g. for plugins loaded after
+    /// startup, and re-applies the current verbosity (if any) so the tracing filter picks them up.
+    async fn add_app_modules(&mut self, modules: Vec<&'static str>) -> Result<(), ClientError>;
+    /// Sets the ratio of traces exported via OpenTelemetry, which must be within `0.0..=1.0`.
+    async fn set_sampling_ratio(&mut self, ratio: f64) -> Result<(), ClientError>;
+    /// Enables or disables exporting traces via OpenTelemetry.
+    async fn set_otel_enabled(&mut self, enabled: bool) -> Result<(), ClientError>;
+    /// Raises (or lowers) the given modules to the level the verbosity applies to interesting
+    /// modules, layered over the current tracing level rather than replacing it. The override lasts
+    /// until the tracing level is next changed.
+    async fn set_interesting_modules_verbosity(
+        &mut self,
+        modules: Vec<String>,
+        verbosity: Verbosity,
+    ) -> Result<(), ClientError>;
 }
 
 /// A telemetry type that can report its tracing level.
 #[async_trait]
 pub trait TelemetryLevel: Send + Sync {
     async fn is_debug_or_lower(&self) -> bool;
+
+    /// Returns whether an expensive diagnostic should be captured.
+    ///
+    /// Diagnostics are always captured when the level is debug or lower, and are otherwise
+    /// sampled with the given `rate` (clamped to `0.0..=1.0`).
+    async fn should_sample_diagnostic(&self, rate: f64) -> bool {
+        if self.is_debug_or_lower().await {
+            return true;
+        }
+        if rate.is_nan() {
+            return false;
+        }
+        rand::random::<f64>() < rate.clamp(0.0, 1.0)
+    }
 }
 
 /// A telemetry client which holds handles to a process' tracing and OpenTelemetry setup.
@@ -203,16 +351,14 @@ impl ApplicationTelemetryClient {
         }
     }
 
-    pub async fn set_verbosity_and_wait(&mut self, updated: Verbosity) -> Result<(), ClientError> {
-        let (tx, rx) = oneshot::channel();
-
-        self.set_verbosity_inner(updated, Some(tx)).await?;
-
-        if let Err(err) = rx.await {
-            warn!(error = ?err, "sender already closed while waiting on verbosity change");
-        }
+    /// Returns the application's own module names.
+    pub fn app_modules(&self) -> &[&'static str] {
+        self.app_modules.as_slice()
+    }
 
-        Ok(())
+    /// Returns the module names of interest beyond the application's own modules.
+    pub fn interesting_modules(&self) -> &[&'static str] {
+        self.interesting_modules.as_slice()
     }
 
     pub async fn modify_verbosity_and_wait(&mut self) -> Result<(), ClientError> {
@@ -321,6 +467,14 @@ impl TelemetryClient for ApplicationTelemetryClient {
         self.set_verbosity_inner(updated, None).await
     }
 
+    async fn set_verbosity_and_wait(&mut self, updated: Verbosity) -> Result<(), ClientError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.set_verbosity_inner(updated, Some(tx)).await?;
+
+        rx.await.map_err(ClientError::AckDropped)
+    }
+
     async fn modify_verbosity(&mut self) -> Result<(), ClientError> {
         self.modify_verbosity_inner(None).await
     }
@@ -331,6 +485,62 @@ impl TelemetryClient for ApplicationTelemetryClient {
     ) -> Result<(), ClientError> {
         self.set_custom_tracing_inner(directives, None).await
     }
+
+    async fn add_app_modules(&mut self, modules: Vec<&'static str>) -> Result<(), ClientError> {
+        let mut updated_modules = self.app_modules.as_ref().clone();
+        for module in modules {
+            if !updated_modules.contains(&module) {
+                updated_modules.push(module);
+            }
+        }
+        self.app_modules = Arc::new(updated_modules);
+
+        let mut guard = self.tracing_level.lock().await;
+        let tracing_level = guard.deref_mut();
+
+        if let TracingLevel::Verbosity { verbosity, .. } = tracing_level {
+            let verbosity = *verbosity;
+            *tracing_level = TracingLevel::new(
+                verbosity,
+                Some(self.app_modules.as_slice()),
+                Some(self.interesting_modules.as_slice()),
+                Some(self.never_modules.as_slice()),
+            );
+            self.update_telemetry_tx
+                .send(TelemetryCommand::TracingLevel {
+                    level: tracing_level.clone(),
+                    wait: None,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_sampling_ratio(&mut self, ratio: f64) -> Result<(), ClientError> {
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(ClientError::InvalidSamplingRatio(ratio));
+        }
+
+        self.update_telemetry_tx
+            .send(TelemetryCommand::SamplingRatio(ratio))?;
+        Ok(())
+    }
+
+    async fn set_otel_enabled(&mut self, enabled: bool) -> Result<(), ClientError> {
+        self.update_telemetry_tx
+            .send(TelemetryCommand::ToggleOtel(enabled))?;
+        Ok(())
+    }
+
+    async fn set_interesting_modules_verbosity(
+        &mut self,
+        modules: Vec<String>,
+        verbosity: Verbosity,
+    ) -> Result<(), ClientError> {
+        self.update_telemetry_tx
+            .send(TelemetryCommand::InterestingModulesVerbosity { modules, verbosity })?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -342,8 +552,9 @@ impl TelemetryLevel for ApplicationTelemetryClient {
 
 /// A "no-nothing" telemetry client suitable for test code.
 ///
-/// Note that it will respond to `is_debug_or_lower` if the `SI_TEST_VERBOSE` environment variable
-/// is set which may increase logging output in tests (typically useful when debugging).
+/// Note that it will respond to `is_debug_or_lower` (and so always sample diagnostics) if the
+/// `SI_TEST_VERBOSE` environment variable is set which may increase logging output in tests
+/// (typically useful when debugging).
 #[derive(Clone, Copy, Debug)]
 pub struct NoopClient;
 
@@ -353,6 +564,10 @@ impl TelemetryClient for NoopClient {
         Ok(())
     }
 
+    async fn set_verbosity_and_wait(&mut self, _updated: Verbosity) -> Result<(), ClientError> {
+        Ok(())
+    }
+
     async fn modify_verbosity(&mut self) -> Result<(), ClientError> {
         Ok(())
     }
@@ -363,6 +578,26 @@ impl TelemetryClient for NoopClient {
     ) -> Result<(), ClientError> {
         Ok(())
     }
+
+    async fn add_app_modules(&mut self, _modules: Vec<&'static str>) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    async fn set_sampling_ratio(&mut self, _ratio: f64) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    async fn set_otel_enabled(&mut self, _enabled: bool) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    async fn set_interesting_modules_verbosity(
+        &mut self,
+        _modules: Vec<String>,
+        _verbosity: Verbosity,
+    ) -> Result<(), ClientError> {
+        Ok(())
+    }
 }
 #[async_trait]
 impl TelemetryLevel for NoopClient {
@@ -377,24 +612,45 @@ impl TelemetryLevel for NoopClient {
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ClientError {
+    #[error("acknowledgement channel dropped before tracing level change was applied")]
+    AckDropped(#[source] oneshot::error::RecvError),
     #[error("custom tracing level has no verbosity")]
     CustomHasNoVerbosity,
+    #[error("sampling ratio must be within 0.0..=1.0, got: {0}")]
+    InvalidSamplingRatio(f64),
     #[error("error while updating tracing level")]
     UpdateTracingLevel(#[from] mpsc::error::SendError<TelemetryCommand>),
 }
 
+/// A command sent to a process' telemetry task.
+///
+/// Only the state carried by a command is serializable: the shutdown token and any wait channel
+/// are process-local and are skipped, so a deserialized command never carries a wait channel.
 #[remain::sorted]
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum TelemetryCommand {
+    InterestingModulesVerbosity {
+        modules: Vec<String>,
+        verbosity: Verbosity,
+    },
+    SamplingRatio(f64),
+    #[serde(skip)]
     Shutdown(CancellationToken),
+    ToggleOtel(bool),
     TracingLevel {
         level: TracingLevel,
+        #[serde(skip)]
         wait: Option<oneshot::Sender<()>>,
     },
 }
 
+/// The tracing level for a process.
+///
+/// A [`TracingLevel::Custom`] serializes transparently as its directive string.
 #[remain::sorted]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum TracingLevel {
     Custom(String),
     Verbosity {
@@ -432,7 +688,8 @@ impl TracingLevel {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
 #[allow(clippy::enum_variant_names)]
 pub enum Verbosity {
     InfoAll,
@@ -445,6 +702,11 @@ pub enum Verbosity {
 }
 
 impl Verbosity {
+    /// The least verbose level.
+    pub const MIN: Self = Self::InfoAll;
+    /// The most verbose level.
+    pub const MAX: Self = Self::TraceAll;
+
     #[must_use]
     pub fn increase(self) -> Self {
         self.as_u8().saturating_add(1).into()
@@ -459,8 +721,14 @@ impl Verbosity {
         !matches!(self, Self::InfoAll)
     }
 
-    fn is_max(&self) -> bool {
-        matches!(self, Self::TraceAll)
+    /// Returns whether this is the most verbose level, i.e. [`Self::increase`] has no effect.
+    pub fn is_max(&self) -> bool {
+        *self == Self::MAX
+    }
+
+    /// Returns whether this is the least verbose level, i.e. [`Self::decrease`] has no effect.
+    pub fn is_min(&self) -> bool {
+        *self == Self::MIN
     }
 
     #[inline]
@@ -563,3 +831,293 @@ macro_rules! current_span_for_instrument_at {
         }
     };
 }
+
+#[allow(clippy::panic)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_VERBOSITIES: [Verbosity; 7] = [
+        Verbosity::InfoAll,
+        Verbosity::DebugAppInfoInterestingInfoAll,
+        Verbosity::DebugAppDebugInterestingInfoAll,
+        Verbosity::TraceAppDebugInterestingInfoAll,
+        Verbosity::TraceAppTraceInterestingInfoAll,
+        Verbosity::TraceAppTraceInterestingDebugAll,
+        Verbosity::TraceAll,
+    ];
+
+    #[test]
+    fn verbosity_serde_round_trip() {
+        for verbosity in ALL_VERBOSITIES {
+            let serialized = serde_json::to_string(&verbosity).expect("failed to serialize");
+            let deserialized: Verbosity =
+                serde_json::from_str(&serialized).expect("failed to deserialize");
+            assert_eq!(verbosity, deserialized);
+        }
+
+        assert_eq!(
+            "\"debug-app-info-interesting-info-all\"",
+            serde_json::to_string(&Verbosity::DebugAppInfoInterestingInfoAll)
+                .expect("failed to serialize")
+        );
+    }
+
+    #[test]
+    fn tracing_level_serde_round_trip() {
+        let levels = [
+            TracingLevel::new(
+                Verbosity::TraceAll,
+                Some(vec!["sdf", "dal"]),
+                Some(vec!["naxum"]),
+                None::<Vec<&'static str>>,
+            ),
+            TracingLevel::new(
+                Verbosity::InfoAll,
+                None::<Vec<&'static str>>,
+                None::<Vec<&'static str>>,
+                None::<Vec<&'static str>>,
+            ),
+            TracingLevel::custom("info,dal=trace"),
+        ];
+
+        for level in levels {
+            let serialized = serde_json::to_string(&level).expect("failed to serialize");
+            let deserialized: TracingLevel =
+                serde_json::from_str(&serialized).expect("failed to deserialize");
+            assert_eq!(level, deserialized);
+        }
+
+        assert_eq!(
+            "\"info,dal=trace\"",
+            serde_json::to_string(&TracingLevel::custom("info,dal=trace"))
+                .expect("failed to serialize")
+        );
+    }
+
+    #[test]
+    fn telemetry_command_serde_round_trip() {
+        let level = TracingLevel::custom("debug");
+        let (wait, _rx) = oneshot::channel();
+        let command = TelemetryCommand::TracingLevel {
+            level: level.clone(),
+            wait: Some(wait),
+        };
+
+        let serialized = serde_json::to_string(&command).expect("failed to serialize");
+        let deserialized: TelemetryCommand =
+            serde_json::from_str(&serialized).expect("failed to deserialize");
+        match deserialized {
+            TelemetryCommand::TracingLevel {
+                level: deserialized_level,
+                wait,
+            } => {
+                assert_eq!(level, deserialized_level);
+                assert!(wait.is_none());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(
+            serde_json::to_string(&TelemetryCommand::Shutdown(CancellationToken::new())).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn add_app_modules_resends_verbosity() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut client = ApplicationTelemetryClient::new(
+            vec!["sdf"],
+            vec!["naxum"],
+            vec![],
+            TracingLevel::new(
+                Verbosity::InfoAll,
+                Some(vec!["sdf"]),
+                Some(vec!["naxum"]),
+                None::<Vec<&'static str>>,
+            ),
+            tx,
+        );
+
+        client
+            .add_app_modules(vec!["sdf", "plugin"])
+            .await
+            .expect("failed to add app modules");
+
+        assert_eq!(&["sdf", "plugin"], client.app_modules());
+        match rx.try_recv().expect("no command sent") {
+            TelemetryCommand::TracingLevel { level, .. } => assert_eq!(
+                TracingLevel::new(
+                    Verbosity::InfoAll,
+                    Some(vec!["sdf", "plugin"]),
+                    Some(vec!["naxum"]),
+                    Some(Vec::<&'static str>::new()),
+                ),
+                level
+            ),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn add_app_modules_with_custom_tracing_sends_nothing() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut client = ApplicationTelemetryClient::new(
+            vec!["sdf"],
+            vec![],
+            vec![],
+            TracingLevel::custom("info"),
+            tx,
+        );
+
+        client
+            .add_app_modules(vec!["plugin"])
+            .await
+            .expect("failed to add app modules");
+
+        assert_eq!(&["sdf", "plugin"], client.app_modules());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn set_sampling_ratio_rejects_out_of_range() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut client = ApplicationTelemetryClient::new(
+            vec![],
+            vec![],
+            vec![],
+            TracingLevel::custom("info"),
+            tx,
+        );
+
+        for ratio in [-0.1, 1.1, f64::NAN] {
+            assert!(matches!(
+                client.set_sampling_ratio(ratio).await,
+                Err(ClientError::InvalidSamplingRatio(_))
+            ));
+        }
+        assert!(rx.try_recv().is_err());
+
+        client
+            .set_sampling_ratio(0.25)
+            .await
+            .expect("failed to set sampling ratio");
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(TelemetryCommand::SamplingRatio(ratio)) if ratio == 0.25
+        ));
+    }
+
+    #[test]
+    fn messaging_operation_round_trip() {
+        for op in [
+            MessagingOperation::Create,
+            MessagingOperation::Deliver,
+            MessagingOperation::Publish,
+            MessagingOperation::Receive,
+        ] {
+            assert_eq!(Ok(op), MessagingOperation::from_str(op.as_str()));
+            assert_eq!(op.as_str(), op.to_string());
+        }
+
+        assert_eq!(
+            Err(ParseMessagingOperationError("settle".to_string())),
+            MessagingOperation::from_str("settle")
+        );
+    }
+
+    #[test]
+    fn span_kind_representations_round_trip() {
+        for kind in [
+            SpanKind::Client,
+            SpanKind::Server,
+            SpanKind::Producer,
+            SpanKind::Consumer,
+            SpanKind::Internal,
+        ] {
+            assert_eq!(Ok(kind.clone()), SpanKind::parse(kind.as_str()));
+            assert_eq!(Ok(kind.clone()), SpanKind::parse(kind.as_otel_const_str()));
+        }
+
+        assert_eq!("SPAN_KIND_SERVER", SpanKind::Server.as_otel_const_str());
+        assert_eq!(
+            Err(ParseSpanKindError("SPAN_KIND_UNSPECIFIED".to_string())),
+            SpanKind::parse("SPAN_KIND_UNSPECIFIED")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_sample_diagnostic_by_level() {
+        let client_at = |verbosity| {
+            let (tx, _rx) = mpsc::unbounded_channel();
+            ApplicationTelemetryClient::new(
+                vec!["sdf"],
+                vec![],
+                vec![],
+                TracingLevel::new(
+                    verbosity,
+                    Some(vec!["sdf"]),
+                    None::<Vec<&'static str>>,
+                    None::<Vec<&'static str>>,
+                ),
+                tx,
+            )
+        };
+
+        let info = client_at(Verbosity::InfoAll);
+        for _ in 0..100 {
+            assert!(!info.should_sample_diagnostic(0.0).await);
+            assert!(info.should_sample_diagnostic(1.0).await);
+        }
+
+        let debug = client_at(Verbosity::DebugAppInfoInterestingInfoAll);
+        for _ in 0..100 {
+            assert!(debug.should_sample_diagnostic(0.0).await);
+        }
+    }
+
+    #[test]
+    fn messaging_attributes_for_operation() {
+        assert_eq!(
+            vec![
+                ("messaging.operation", "publish".to_string()),
+                ("messaging.system", "nats".to_string()),
+                (
+                    "messaging.destination.name",
+                    "si.rebaser.requests".to_string()
+                ),
+            ],
+            messaging_attributes(MessagingOperation::Publish, "nats", "si.rebaser.requests")
+        );
+    }
+
+    #[test]
+    fn otel_status_code_round_trips() {
+        for code in [
+            OtelStatusCode::Error,
+            OtelStatusCode::Ok,
+            OtelStatusCode::Unset,
+        ] {
+            assert_eq!(
+                code,
+                OtelStatusCode::from_str(code.as_str()).expect("failed to parse")
+            );
+        }
+        assert_eq!(
+            Err(ParseOtelStatusCodeError("ok".to_string())),
+            OtelStatusCode::from_str("ok")
+        );
+    }
+
+    #[test]
+    fn verbosity_bounds() {
+        assert!(Verbosity::MIN.is_min());
+        assert!(Verbosity::MAX.is_max());
+        assert_eq!(Verbosity::MIN, Verbosity::MIN.decrease());
+        assert_eq!(Verbosity::MAX, Verbosity::MAX.increase());
+        for verbosity in &ALL_VERBOSITIES[1..ALL_VERBOSITIES.len() - 1] {
+            assert!(!verbosity.is_min());
+            assert!(!verbosity.is_max());
+        }
+    }
+}
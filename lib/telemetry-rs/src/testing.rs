@@ -0,0 +1,148 @@
+//! Test doubles for code which drives a [`TelemetryClient`].
+//!
+//! Only available with the `testing` feature, so add `telemetry` with that feature as a
+//! dev-dependency to use them.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+
+use crate::{ClientError, NoopClient, TelemetryClient, TelemetryLevel, Verbosity};
+
+/// A call made against a [`RecordingClient`].
+#[remain::sorted]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedTelemetryCall {
+    AddAppModules(Vec<&'static str>),
+    ModifyVerbosity,
+    SetCustomTracing(String),
+    SetInterestingModulesVerbosity {
+        modules: Vec<String>,
+        verbosity: Verbosity,
+    },
+    SetOtelEnabled(bool),
+    SetSamplingRatio(f64),
+    SetVerbosity(Verbosity),
+    SetVerbosityAndWait(Verbosity),
+}
+
+/// A telemetry client for test code which records every call made against it.
+///
+/// Otherwise it behaves like a [`NoopClient`]: no tracing setup is driven and every call succeeds.
+/// Clones share the same recorded calls, so a clone can be handed to the code under test and the
+/// original used to assert on what was called.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingClient {
+    calls: Arc<Mutex<Vec<RecordedTelemetryCall>>>,
+}
+
+impl RecordingClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the calls recorded so far, in the order they were made.
+    pub fn calls(&self) -> Vec<RecordedTelemetryCall> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, call: RecordedTelemetryCall) {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(call);
+    }
+}
+
+#[async_trait]
+impl TelemetryClient for RecordingClient {
+    async fn set_verbosity(&mut self, updated: Verbosity) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetVerbosity(updated));
+        Ok(())
+    }
+
+    async fn set_verbosity_and_wait(&mut self, updated: Verbosity) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetVerbosityAndWait(updated));
+        Ok(())
+    }
+
+    async fn modify_verbosity(&mut self) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::ModifyVerbosity);
+        Ok(())
+    }
+
+    async fn set_custom_tracing(
+        &mut self,
+        directives: impl Into<String> + Send + 'async_trait,
+    ) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetCustomTracing(directives.into()));
+        Ok(())
+    }
+
+    async fn add_app_modules(&mut self, modules: Vec<&'static str>) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::AddAppModules(modules));
+        Ok(())
+    }
+
+    async fn set_sampling_ratio(&mut self, ratio: f64) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetSamplingRatio(ratio));
+        Ok(())
+    }
+
+    async fn set_otel_enabled(&mut self, enabled: bool) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetOtelEnabled(enabled));
+        Ok(())
+    }
+
+    async fn set_interesting_modules_verbosity(
+        &mut self,
+        modules: Vec<String>,
+        verbosity: Verbosity,
+    ) -> Result<(), ClientError> {
+        self.record(RecordedTelemetryCall::SetInterestingModulesVerbosity { modules, verbosity });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TelemetryLevel for RecordingClient {
+    async fn is_debug_or_lower(&self) -> bool {
+        NoopClient.is_debug_or_lower().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn recording_client_records_calls_in_order() {
+        let client = RecordingClient::new();
+        let mut handle = client.clone();
+
+        handle
+            .set_verbosity(Verbosity::TraceAll)
+            .await
+            .expect("failed to set verbosity");
+        handle
+            .modify_verbosity()
+            .await
+            .expect("failed to modify verbosity");
+        handle
+            .set_custom_tracing("sdf=debug")
+            .await
+            .expect("failed to set custom tracing");
+
+        assert_eq!(
+            vec![
+                RecordedTelemetryCall::SetVerbosity(Verbosity::TraceAll),
+                RecordedTelemetryCall::ModifyVerbosity,
+                RecordedTelemetryCall::SetCustomTracing("sdf=debug".to_string()),
+            ],
+            client.calls()
+        );
+    }
+}